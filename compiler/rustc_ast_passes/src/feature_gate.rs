@@ -393,6 +393,7 @@ macro_rules! gate_doc { ($($name:ident => $feature:ident)*) => {
                 gate_doc!(
                     cfg => doc_cfg
                     cfg_hide => doc_cfg_hide
+                    coverage => doc_coverage
                     masked => doc_masked
                     notable_trait => doc_notable_trait
                 );
@@ -79,6 +79,14 @@
 //! invocation) and allocate the corresponding strings together with a mapping
 //! for `DepNodeIndex as StringId`.
 //!
+//! # Built-in reports
+//!
+//! Reading the profiling data requires the tools from the `measureme`
+//! repository. When asked for with `-Z self-profile-summary` or
+//! `-Z self-profile-chrome-trace`, the `SelfProfiler` also keeps the events in
+//! memory and writes a summary table or a Chrome trace itself when it is
+//! dropped at the end of the session, see the `report` module.
+//!
 //! [mm]: https://github.com/rust-lang/measureme/
 
 use crate::cold_path;
@@ -89,7 +97,7 @@
 use std::convert::Into;
 use std::error::Error;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -99,6 +107,11 @@
 use parking_lot::RwLock;
 use smallvec::SmallVec;
 
+mod report;
+
+pub use report::SelfProfileReports;
+use report::{EventKind, EventLog, EventRecording, Label};
+
 bitflags::bitflags! {
     struct EventFilter: u32 {
         const GENERIC_ACTIVITIES  = 1 << 0;
@@ -285,7 +298,12 @@ pub fn generic_activity_with_arg<A>(
             } else {
                 builder.from_label(event_label)
             };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_with_label(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                event_label,
+            )
         })
     }
 
@@ -345,7 +363,12 @@ pub fn generic_activity_with_arg_recorder<F>(
             } else {
                 builder.from_label(event_label)
             };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_with_label(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                event_label,
+            )
         })
     }
 
@@ -394,7 +417,12 @@ pub fn generic_activity_with_args(
             } else {
                 builder.from_label(event_label)
             };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_with_label(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                event_label,
+            )
         })
     }
 
@@ -470,6 +498,13 @@ fn instant_query_event(
                 EventId::from_virtual(event_id),
                 thread_id,
             );
+            if let Some(event_log) = &profiler.event_log {
+                event_log.record_instant(
+                    profiler.event_kind(event_kind(profiler)),
+                    Label::QueryInvocation(query_invocation_id.0),
+                    thread_id,
+                );
+            }
 
             TimingGuard::none()
         }));
@@ -544,6 +579,13 @@ pub struct SelfProfiler {
     query_blocked_event_kind: StringId,
     query_cache_hit_event_kind: StringId,
     artifact_size_event_kind: StringId,
+
+    /// The events kept in memory for the built-in reports, if any were asked for.
+    event_log: Option<EventLog>,
+    reports: SelfProfileReports,
+    /// The path of the profiling data without extension, the reports are
+    /// written next to it.
+    report_stem: PathBuf,
 }
 
 impl SelfProfiler {
@@ -551,6 +593,7 @@ pub fn new(
         output_directory: &Path,
         crate_name: Option<&str>,
         event_filters: &Option<Vec<String>>,
+        reports: SelfProfileReports,
     ) -> Result<SelfProfiler, Box<dyn Error + Send + Sync>> {
         fs::create_dir_all(output_directory)?;
 
@@ -612,9 +655,31 @@ pub fn new(
             query_blocked_event_kind,
             query_cache_hit_event_kind,
             artifact_size_event_kind,
+            event_log: if reports.any() { Some(EventLog::new()) } else { None },
+            reports,
+            report_stem: path,
         })
     }
 
+    /// Classifies an event kind for the built-in reports.
+    fn event_kind(&self, event_kind: StringId) -> EventKind {
+        if event_kind == self.generic_activity_event_kind {
+            EventKind::GenericActivity
+        } else if event_kind == self.query_event_kind {
+            EventKind::Query
+        } else if event_kind == self.query_blocked_event_kind {
+            EventKind::QueryBlocked
+        } else if event_kind == self.query_cache_hit_event_kind {
+            EventKind::QueryCacheHit
+        } else if event_kind == self.incremental_load_result_event_kind {
+            EventKind::IncrementalLoadResult
+        } else if event_kind == self.incremental_result_hashing_event_kind {
+            EventKind::IncrementalResultHashing
+        } else {
+            EventKind::Other
+        }
+    }
+
     /// Allocates a new string in the profiling data. Does not do any caching
     /// or deduplication.
     pub fn alloc_string<STR: SerializableString + ?Sized>(&self, s: &STR) -> StringId {
@@ -659,10 +724,28 @@ pub fn bulk_map_query_invocation_id_to_single_string<I>(&self, from: I, to: Stri
     where
         I: Iterator<Item = QueryInvocationId> + ExactSizeIterator,
     {
+        if let Some(event_log) = &self.event_log {
+            let from = from.collect::<Vec<_>>();
+            for qid in &from {
+                event_log.map_query_invocation(qid.0, to);
+            }
+            let from = from.into_iter().map(|qid| StringId::new_virtual(qid.0));
+            self.profiler.bulk_map_virtual_to_single_concrete_string(from, to);
+            return;
+        }
         let from = from.map(|qid| StringId::new_virtual(qid.0));
         self.profiler.bulk_map_virtual_to_single_concrete_string(from, to);
     }
 
+    /// Records which query `from` is an invocation of, for the built-in reports.
+    /// This is only needed if the string `from` is mapped to includes the query
+    /// key, as it does with `-Z self-profile-events=query-keys`.
+    pub fn map_query_invocation_id_to_label(&self, from: QueryInvocationId, label: StringId) {
+        if let Some(event_log) = &self.event_log {
+            event_log.map_query_invocation(from.0, label);
+        }
+    }
+
     pub fn query_key_recording_enabled(&self) -> bool {
         self.event_filter_mask.contains(EventFilter::QUERY_KEYS)
     }
@@ -672,8 +755,22 @@ pub fn event_id_builder(&self) -> EventIdBuilder<'_> {
     }
 }
 
+impl Drop for SelfProfiler {
+    fn drop(&mut self) {
+        if let Some(event_log) = &self.event_log {
+            if let Err(err) = event_log.write_reports(self, self.reports, &self.report_stem) {
+                eprintln!(
+                    "failed to write self-profile reports to `{}`: {}",
+                    self.report_stem.display(),
+                    err
+                );
+            }
+        }
+    }
+}
+
 #[must_use]
-pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>);
+pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>, Option<EventRecording<'a>>);
 
 impl<'a> TimingGuard<'a> {
     #[inline]
@@ -681,18 +778,38 @@ pub fn start(
         profiler: &'a SelfProfiler,
         event_kind: StringId,
         event_id: EventId,
+    ) -> TimingGuard<'a> {
+        let label = event_id.to_string_id();
+        TimingGuard::start_with_label(profiler, event_kind, event_id, label)
+    }
+
+    /// Like `start`, but names the event `label` in the built-in reports, for
+    /// events whose id includes their arguments.
+    #[inline]
+    fn start_with_label(
+        profiler: &'a SelfProfiler,
+        event_kind: StringId,
+        event_id: EventId,
+        label: StringId,
     ) -> TimingGuard<'a> {
         let thread_id = get_thread_id();
         let raw_profiler = &profiler.profiler;
         let timing_guard =
             raw_profiler.start_recording_interval_event(event_kind, event_id, thread_id);
-        TimingGuard(Some(timing_guard))
+        let recording = profiler.event_log.as_ref().map(|event_log| {
+            event_log.start(profiler.event_kind(event_kind), Label::String(label), thread_id)
+        });
+        TimingGuard(Some(timing_guard), recording)
     }
 
     #[inline]
     pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocationId) {
-        if let Some(guard) = self.0 {
+        let TimingGuard(guard, recording) = self;
+        if let Some(guard) = guard {
             cold_path(|| {
+                if let Some(mut recording) = recording {
+                    recording.label = Label::QueryInvocation(query_invocation_id.0);
+                }
                 let event_id = StringId::new_virtual(query_invocation_id.0);
                 let event_id = EventId::from_virtual(event_id);
                 guard.finish_with_override_event_id(event_id);
@@ -702,7 +819,7 @@ pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocatio
 
     #[inline]
     pub fn none() -> TimingGuard<'a> {
-        TimingGuard(None)
+        TimingGuard(None, None)
     }
 
     #[inline(always)]
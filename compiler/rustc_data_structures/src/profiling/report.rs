@@ -0,0 +1,367 @@
+//! Reports the compiler writes by itself at the end of a self-profiling
+//! session, so that no external `measureme` tools are needed to make sense of
+//! the profile: a summary table of the time spent per query and pass, and a
+//! trace in the Chrome trace event format, which can be opened in
+//! `chrome://tracing`, Perfetto or Speedscope.
+//!
+//! The events are kept in memory for this, in addition to being written to the
+//! `measureme` data file, which is why it has to be asked for explicitly.
+
+use super::SelfProfiler;
+use crate::fx::FxHashMap;
+
+use measureme::StringId;
+use parking_lot::Mutex;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+/// The reports to write at the end of a self-profiling session, in addition to
+/// the raw `measureme` data.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SelfProfileReports {
+    /// Write a table of the time spent per query and per pass.
+    /// (rustc: see `-Z self-profile-summary`)
+    pub summary: bool,
+    /// Write the events as a Chrome trace event JSON file.
+    /// (rustc: see `-Z self-profile-chrome-trace`)
+    pub chrome_trace: bool,
+}
+
+impl SelfProfileReports {
+    pub fn any(&self) -> bool {
+        self.summary || self.chrome_trace
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum EventKind {
+    GenericActivity,
+    Query,
+    QueryBlocked,
+    QueryCacheHit,
+    IncrementalLoadResult,
+    IncrementalResultHashing,
+    /// Events of kinds allocated outside of the `SelfProfiler`, like LLVM passes.
+    Other,
+}
+
+impl EventKind {
+    fn name(self) -> &'static str {
+        match self {
+            EventKind::GenericActivity => "GenericActivity",
+            EventKind::Query => "Query",
+            EventKind::QueryBlocked => "QueryBlocked",
+            EventKind::QueryCacheHit => "QueryCacheHit",
+            EventKind::IncrementalLoadResult => "IncrementalLoadResult",
+            EventKind::IncrementalResultHashing => "IncrementalResultHashing",
+            EventKind::Other => "Other",
+        }
+    }
+}
+
+/// What an event is about, resolved to a name when the reports are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Label {
+    String(StringId),
+    QueryInvocation(u32),
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Event<L> {
+    pub kind: EventKind,
+    pub label: L,
+    pub thread_id: u32,
+    /// Nanoseconds since the start of the session.
+    pub start: u64,
+    /// Nanoseconds since the start of the session, equal to `start` for
+    /// instant events.
+    pub end: u64,
+}
+
+impl<L> Event<L> {
+    fn duration(&self) -> u64 {
+        self.end - self.start
+    }
+}
+
+/// The events of a self-profiling session, and what is needed to name them.
+pub(super) struct EventLog {
+    start: Instant,
+    events: Mutex<Vec<Event<Label>>>,
+    /// The query each query invocation is an invocation of.
+    query_labels: Mutex<FxHashMap<u32, StringId>>,
+}
+
+impl EventLog {
+    pub fn new() -> EventLog {
+        EventLog {
+            start: Instant::now(),
+            events: Default::default(),
+            query_labels: Default::default(),
+        }
+    }
+
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+
+    pub fn start(&self, kind: EventKind, label: Label, thread_id: u32) -> EventRecording<'_> {
+        EventRecording { log: self, kind, label, thread_id, start: self.now() }
+    }
+
+    pub fn record_instant(&self, kind: EventKind, label: Label, thread_id: u32) {
+        let now = self.now();
+        self.events.lock().push(Event { kind, label, thread_id, start: now, end: now });
+    }
+
+    pub fn map_query_invocation(&self, query_invocation_id: u32, label: StringId) {
+        self.query_labels.lock().insert(query_invocation_id, label);
+    }
+
+    /// Writes the `reports` next to the `measureme` data, at `stem` with the
+    /// extension of the report.
+    pub fn write_reports(
+        &self,
+        profiler: &SelfProfiler,
+        reports: SelfProfileReports,
+        stem: &Path,
+    ) -> io::Result<()> {
+        let names = profiler
+            .string_cache
+            .read()
+            .iter()
+            .map(|(name, &id)| (id, name.clone()))
+            .collect::<FxHashMap<_, _>>();
+        let query_labels = self.query_labels.lock();
+        let resolve = |event: &Event<Label>| {
+            let id = match event.label {
+                Label::String(id) => Some(id),
+                Label::QueryInvocation(invocation) => query_labels.get(&invocation).copied(),
+            };
+            match id.and_then(|id| names.get(&id)) {
+                Some(name) => name.clone(),
+                None => match event.kind {
+                    EventKind::IncrementalResultHashing => "incr_result_hashing".to_string(),
+                    kind => format!("<unknown {}>", kind.name()),
+                },
+            }
+        };
+        let events = self
+            .events
+            .lock()
+            .iter()
+            .map(|event| Event {
+                kind: event.kind,
+                label: resolve(event),
+                thread_id: event.thread_id,
+                start: event.start,
+                end: event.end,
+            })
+            .collect::<Vec<_>>();
+
+        if reports.summary {
+            fs::write(stem.with_extension("summary.txt"), summary(&events))?;
+        }
+        if reports.chrome_trace {
+            fs::write(stem.with_extension("trace.json"), chrome_trace(&events, process::id()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Records an interval event into the `EventLog` when dropped.
+pub(super) struct EventRecording<'a> {
+    log: &'a EventLog,
+    kind: EventKind,
+    pub label: Label,
+    thread_id: u32,
+    start: u64,
+}
+
+impl Drop for EventRecording<'_> {
+    fn drop(&mut self) {
+        let end = self.log.now();
+        self.log.events.lock().push(Event {
+            kind: self.kind,
+            label: self.label,
+            thread_id: self.thread_id,
+            start: self.start,
+            end,
+        });
+    }
+}
+
+#[derive(Default)]
+struct SummaryRow {
+    self_time: u64,
+    time: u64,
+    count: u64,
+    cache_hits: u64,
+    blocked_time: u64,
+    incremental_load_time: u64,
+}
+
+/// Computes the time spent in each event minus the time spent in the events
+/// nested inside of it on the same thread.
+fn self_times(events: &[Event<String>]) -> Vec<u64> {
+    let mut order = (0..events.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| (events[i].thread_id, events[i].start, u64::MAX - events[i].end));
+
+    let mut self_times = events.iter().map(|event| event.duration()).collect::<Vec<_>>();
+    let mut stack: Vec<usize> = Vec::new();
+    for i in order {
+        let event = &events[i];
+        if event.kind == EventKind::QueryCacheHit {
+            continue;
+        }
+        while let Some(&parent) = stack.last() {
+            let parent = &events[parent];
+            if parent.thread_id == event.thread_id && event.start < parent.end {
+                break;
+            }
+            stack.pop();
+        }
+        if let Some(&parent) = stack.last() {
+            self_times[parent] = self_times[parent].saturating_sub(event.duration());
+        }
+        stack.push(i);
+    }
+    self_times
+}
+
+/// Renders a table of the time spent per query and pass, sorted by self time.
+pub(super) fn summary(events: &[Event<String>]) -> String {
+    let self_times = self_times(events);
+    let mut rows: FxHashMap<&str, SummaryRow> = FxHashMap::default();
+    for (event, &self_time) in events.iter().zip(&self_times) {
+        let row = rows.entry(&event.label).or_default();
+        match event.kind {
+            EventKind::QueryCacheHit => row.cache_hits += 1,
+            EventKind::QueryBlocked => row.blocked_time += event.duration(),
+            kind => {
+                if kind == EventKind::IncrementalLoadResult {
+                    row.incremental_load_time += event.duration();
+                } else {
+                    row.count += 1;
+                }
+                row.self_time += self_time;
+                row.time += event.duration();
+            }
+        }
+    }
+    let total_self_time = rows.values().map(|row| row.self_time).sum::<u64>();
+    let mut rows = rows.into_iter().collect::<Vec<_>>();
+    rows.sort_by(|(a_name, a), (b_name, b)| b.self_time.cmp(&a.self_time).then(a_name.cmp(b_name)));
+
+    let header = [
+        "Item",
+        "Self time",
+        "% of total time",
+        "Time",
+        "Item count",
+        "Cache hits",
+        "Blocked time",
+        "Incremental load time",
+    ];
+    let mut table = vec![header.iter().map(|cell| cell.to_string()).collect::<Vec<_>>()];
+    for (name, row) in &rows {
+        let percent = if total_self_time == 0 {
+            0.0
+        } else {
+            row.self_time as f64 * 100.0 / total_self_time as f64
+        };
+        table.push(vec![
+            name.to_string(),
+            format_nanos(row.self_time),
+            format!("{percent:.3}"),
+            format_nanos(row.time),
+            row.count.to_string(),
+            row.cache_hits.to_string(),
+            format_nanos(row.blocked_time),
+            format_nanos(row.incremental_load_time),
+        ]);
+    }
+
+    let widths = (0..header.len())
+        .map(|column| table.iter().map(|row| row[column].chars().count()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+    let separator =
+        widths.iter().map(|&width| format!("+{}", "-".repeat(width + 2))).collect::<String>()
+            + "+\n";
+    let mut out = String::new();
+    for (i, row) in table.iter().enumerate() {
+        if i <= 1 {
+            out.push_str(&separator);
+        }
+        for (cell, &width) in row.iter().zip(&widths) {
+            let _ = write!(out, "| {cell:<width$} ");
+        }
+        out.push_str("|\n");
+    }
+    out.push_str(&separator);
+    let _ = writeln!(out, "\nTotal cpu time: {}", format_nanos(total_self_time));
+    out
+}
+
+fn format_nanos(nanos: u64) -> String {
+    format!("{:.2?}", Duration::from_nanos(nanos))
+}
+
+/// Renders the events in the Chrome trace event format, using the JSON object
+/// format with a `traceEvents` array.
+pub(super) fn chrome_trace(events: &[Event<String>], pid: u32) -> String {
+    let mut out = String::from("{\"traceEvents\":[");
+    for (i, event) in events.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("\n{\"name\":");
+        push_json_string(&mut out, &event.label);
+        let _ = write!(
+            out,
+            ",\"cat\":\"{}\",\"pid\":{},\"tid\":{},\"ts\":{}",
+            event.kind.name(),
+            pid,
+            event.thread_id,
+            micros(event.start),
+        );
+        if event.kind == EventKind::QueryCacheHit {
+            out.push_str(",\"ph\":\"i\",\"s\":\"t\"}");
+        } else {
+            let _ = write!(out, ",\"ph\":\"X\",\"dur\":{}}}", micros(event.duration()));
+        }
+    }
+    out.push_str("\n],\"displayTimeUnit\":\"ms\"}\n");
+    out
+}
+
+/// Trace timestamps are in microseconds, but may have a fractional part.
+fn micros(nanos: u64) -> String {
+    format!("{}.{:03}", nanos / 1000, nanos % 1000)
+}
+
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,78 @@
+use super::*;
+
+fn event(kind: EventKind, label: &str, thread_id: u32, start: u64, end: u64) -> Event<String> {
+    Event { kind, label: label.to_string(), thread_id, start, end }
+}
+
+/// Splits the rows of a summary table into their cells.
+fn rows(summary: &str) -> Vec<Vec<&str>> {
+    summary
+        .lines()
+        .filter(|line| line.starts_with('|'))
+        .map(|line| line.trim_matches('|').split('|').map(str::trim).collect())
+        .collect()
+}
+
+#[test]
+fn summary_subtracts_nested_events() {
+    let events = [
+        event(EventKind::Query, "typeck", 0, 0, 100),
+        event(EventKind::Query, "type_of", 0, 10, 30),
+        event(EventKind::Query, "type_of", 0, 40, 50),
+        event(EventKind::QueryCacheHit, "type_of", 0, 60, 60),
+        // Events on other threads are not nested in `typeck`.
+        event(EventKind::GenericActivity, "codegen", 1, 20, 60),
+    ];
+    let summary = summary(&events);
+    assert_eq!(
+        rows(&summary),
+        [
+            vec![
+                "Item",
+                "Self time",
+                "% of total time",
+                "Time",
+                "Item count",
+                "Cache hits",
+                "Blocked time",
+                "Incremental load time",
+            ],
+            vec!["typeck", "70.00ns", "50.000", "100.00ns", "1", "0", "0.00ns", "0.00ns"],
+            vec!["codegen", "40.00ns", "28.571", "40.00ns", "1", "0", "0.00ns", "0.00ns"],
+            vec!["type_of", "30.00ns", "21.429", "30.00ns", "2", "1", "0.00ns", "0.00ns"],
+        ]
+    );
+    assert!(summary.ends_with("Total cpu time: 140.00ns\n"));
+}
+
+#[test]
+fn summary_of_blocked_and_loaded_queries() {
+    let events = [
+        event(EventKind::Query, "mir_borrowck", 0, 0, 100),
+        event(EventKind::QueryBlocked, "type_of", 0, 10, 70),
+        event(EventKind::IncrementalLoadResult, "type_of", 0, 80, 90),
+    ];
+    assert_eq!(
+        rows(&summary(&events))[1..],
+        [
+            vec!["mir_borrowck", "30.00ns", "75.000", "100.00ns", "1", "0", "0.00ns", "0.00ns"],
+            vec!["type_of", "10.00ns", "25.000", "10.00ns", "0", "0", "60.00ns", "10.00ns"],
+        ]
+    );
+}
+
+#[test]
+fn chrome_trace_events() {
+    let events = [
+        event(EventKind::Query, "a\"b", 1, 1500, 4000),
+        event(EventKind::QueryCacheHit, "c", 2, 5000, 5000),
+    ];
+    assert_eq!(
+        chrome_trace(&events, 7),
+        r#"{"traceEvents":[
+{"name":"a\"b","cat":"Query","pid":7,"tid":1,"ts":1.500,"ph":"X","dur":2.500},
+{"name":"c","cat":"QueryCacheHit","pid":7,"tid":2,"ts":5.000,"ph":"i","s":"t"}
+],"displayTimeUnit":"ms"}
+"#
+    );
+}
@@ -107,6 +107,10 @@ pub struct Diagnostic {
     /// If diagnostic is from Lint, custom hash function ignores notes
     /// otherwise hash is based on the all the fields
     pub is_lint: bool,
+
+    /// Whether this is the summary of the session, like "aborting due to
+    /// previous error", rather than a diagnostic about the code itself.
+    pub is_summary: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
@@ -192,6 +196,7 @@ pub fn new_with_code<M: Into<DiagnosticMessage>>(
             args: vec![],
             sort_span: DUMMY_SP,
             is_lint: false,
+            is_summary: false,
         }
     }
 
@@ -795,6 +800,11 @@ pub fn set_is_lint(&mut self) -> &mut Self {
         self
     }
 
+    pub fn set_is_summary(&mut self) -> &mut Self {
+        self.is_summary = true;
+        self
+    }
+
     pub fn code(&mut self, s: DiagnosticId) -> &mut Self {
         self.code = Some(s);
         self
@@ -0,0 +1,317 @@
+//! Applying the suggestions of emitted diagnostics to the source files they
+//! were made for, see `-Z apply-suggestions`.
+//!
+//! Suggestions are collected as diagnostics are emitted and applied once the
+//! compilation is finished. Suggestions that would touch the same text as one
+//! collected before them are skipped as a whole, so that the result never
+//! contains half of a suggestion. Rerunning the compiler will pick them up again.
+
+use crate::{Applicability, Diagnostic, Level};
+
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
+use rustc_data_structures::sync::Lrc;
+use rustc_lint_defs::pluralize;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, Pos, RealFileName, SourceFile, Span};
+
+use std::fmt::Write;
+use std::ops::Range;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests;
+
+/// The number of unchanged lines shown around each change of a unified diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// The suggestions collected for `-Z apply-suggestions`.
+#[derive(Default)]
+pub(crate) struct SuggestionFixes {
+    /// The parts of each collected suggestion, in the order they were emitted.
+    suggestions: Vec<Vec<(Span, String)>>,
+    /// Suggestions of duplicated diagnostics are only collected once.
+    seen: FxHashSet<Vec<(Span, String)>>,
+}
+
+/// A replacement of a byte range of a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Edit {
+    pub range: Range<usize>,
+    pub snippet: String,
+}
+
+impl Edit {
+    fn overlaps(&self, other: &Edit) -> bool {
+        // Two insertions at the same position conflict as well, as their order
+        // would be arbitrary.
+        (self.range.start < other.range.end && other.range.start < self.range.end)
+            || self.range == other.range
+    }
+}
+
+/// The edits that will be made to one file.
+struct FileFixes {
+    file: Lrc<SourceFile>,
+    edits: Vec<Edit>,
+    applied: usize,
+    skipped: usize,
+}
+
+impl SuggestionFixes {
+    /// Collects the suggestions of `diagnostic` that are at least as certain as
+    /// `applicability`. Suggestions with several alternatives are left alone, as
+    /// someone needs to pick one of them.
+    pub(crate) fn collect(&mut self, diagnostic: &Diagnostic, applicability: Applicability) {
+        let Ok(suggestions) = &diagnostic.suggestions else { return };
+        for suggestion in suggestions {
+            let wanted = match suggestion.applicability {
+                Applicability::MachineApplicable => true,
+                Applicability::MaybeIncorrect => applicability == Applicability::MaybeIncorrect,
+                Applicability::HasPlaceholders | Applicability::Unspecified => false,
+            };
+            if !wanted || suggestion.substitutions.len() != 1 {
+                continue;
+            }
+            let parts = suggestion.substitutions[0]
+                .parts
+                .iter()
+                .map(|part| (part.span, part.snippet.clone()))
+                .collect::<Vec<_>>();
+            if parts.is_empty() || parts.iter().any(|(span, _)| span.is_dummy()) {
+                continue;
+            }
+            if self.seen.insert(parts.clone()) {
+                self.suggestions.push(parts);
+            }
+        }
+    }
+
+    /// Applies all collected suggestions, either by rewriting the files or by
+    /// printing a unified diff to stdout if `diff` is set. Returns diagnostics
+    /// describing the outcome, which the caller is expected to emit.
+    pub(crate) fn apply(&mut self, sm: &SourceMap, diff: bool) -> Vec<Diagnostic> {
+        let mut files: FxIndexMap<PathBuf, FileFixes> = Default::default();
+        for parts in self.suggestions.drain(..) {
+            let Some((path, file, edits)) = resolve(sm, &parts) else { continue };
+            let fixes = files.entry(path).or_insert_with(|| FileFixes {
+                file,
+                edits: Vec::new(),
+                applied: 0,
+                skipped: 0,
+            });
+            if edits.iter().any(|edit| fixes.edits.iter().any(|other| edit.overlaps(other))) {
+                fixes.skipped += 1;
+            } else {
+                fixes.edits.extend(edits);
+                fixes.applied += 1;
+            }
+        }
+
+        let mut diags = Vec::new();
+        for (path, mut fixes) in files {
+            let name = sm.filename_for_diagnostics(&fixes.file.name).to_string();
+            let Some(src) = fixes.file.src.as_ref() else { continue };
+            // The source map holds the file with normalized line endings and
+            // without a byte order mark, only write it back if that is what is
+            // on disk, and if it has not changed since.
+            if std::fs::read_to_string(&path).ok().as_deref() != Some(src.as_str()) {
+                diags.push(Diagnostic::new(
+                    Level::Warning,
+                    format!(
+                        "not applying suggestions to `{name}`, \
+                         it differs from the file that was compiled"
+                    ),
+                ));
+                continue;
+            }
+
+            fixes.edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+            if diff {
+                print!("{}", unified_diff(&name, src, &fixes.edits));
+                continue;
+            }
+            if let Err(err) = std::fs::write(&path, apply_edits(src, &fixes.edits)) {
+                diags.push(Diagnostic::new(
+                    Level::Error { lint: false },
+                    format!("failed to write `{name}`: {err}"),
+                ));
+                continue;
+            }
+
+            let mut msg = format!(
+                "applied {} suggestion{} to `{name}`",
+                fixes.applied,
+                pluralize!(fixes.applied)
+            );
+            if fixes.skipped > 0 {
+                let _ = write!(
+                    msg,
+                    ", skipped {} that overlapped with {}",
+                    fixes.skipped,
+                    if fixes.applied == 1 { "it" } else { "them" }
+                );
+            }
+            diags.push(Diagnostic::new(Level::Note, msg));
+        }
+        diags
+    }
+}
+
+/// Resolves the parts of a suggestion to edits of a single local file, returns
+/// `None` if the suggestion cannot be applied, like when it points into the
+/// standard library or spans several files.
+fn resolve(
+    sm: &SourceMap,
+    parts: &[(Span, String)],
+) -> Option<(PathBuf, Lrc<SourceFile>, Vec<Edit>)> {
+    let file = sm.lookup_source_file(parts[0].0.lo());
+    let FileName::Real(RealFileName::LocalPath(path)) = &file.name else { return None };
+    let mut edits = Vec::with_capacity(parts.len());
+    for (span, snippet) in parts {
+        if span.lo() < file.start_pos || span.hi() > file.end_pos || span.lo() > span.hi() {
+            return None;
+        }
+        let range =
+            (span.lo() - file.start_pos).to_usize()..(span.hi() - file.start_pos).to_usize();
+        let edit = Edit { range, snippet: snippet.clone() };
+        if edits.iter().any(|other| edit.overlaps(other)) {
+            return None;
+        }
+        edits.push(edit);
+    }
+    Some((path.clone(), file, edits))
+}
+
+/// Returns `src` with the non-overlapping `edits` applied, which must be sorted
+/// by their position.
+pub(crate) fn apply_edits(src: &str, edits: &[Edit]) -> String {
+    let mut fixed = String::with_capacity(src.len());
+    let mut pos = 0;
+    for edit in edits {
+        fixed.push_str(&src[pos..edit.range.start]);
+        fixed.push_str(&edit.snippet);
+        pos = edit.range.end;
+    }
+    fixed.push_str(&src[pos..]);
+    fixed
+}
+
+/// Renders the non-overlapping `edits` of `src`, which must be sorted by their
+/// position, as a unified diff of the file `name`.
+pub(crate) fn unified_diff(name: &str, src: &str, edits: &[Edit]) -> String {
+    let line_starts = std::iter::once(0)
+        .chain(src.match_indices('\n').map(|(i, _)| i + 1).filter(|&i| i < src.len()))
+        .collect::<Vec<_>>();
+    let line_of = |pos: usize| line_starts.partition_point(|&start| start <= pos).saturating_sub(1);
+    let line_end = |line: usize| line_starts.get(line + 1).copied().unwrap_or(src.len());
+    let line_count = if src.is_empty() { 0 } else { line_starts.len() };
+
+    // Edits touching the same or adjacent lines are shown as one change, as a
+    // list of `(first line, last line, edits)`.
+    let mut changes: Vec<(usize, usize, Vec<&Edit>)> = Vec::new();
+    for edit in edits {
+        let first = line_of(edit.range.start);
+        let last = if edit.range.is_empty() { first } else { line_of(edit.range.end - 1) };
+        match changes.last_mut() {
+            Some((_, prev_last, change)) if first <= *prev_last + 1 => {
+                *prev_last = (*prev_last).max(last);
+                change.push(edit);
+            }
+            _ => changes.push((first, last, vec![edit])),
+        }
+    }
+
+    // Changes whose context would overlap are shown in one hunk.
+    let mut hunks: Vec<Vec<(usize, usize, Vec<&Edit>)>> = Vec::new();
+    for change in changes {
+        match hunks.last().and_then(|hunk| hunk.last()) {
+            Some((_, prev_last, _)) if change.0 <= prev_last + 2 * DIFF_CONTEXT + 1 => {
+                hunks.last_mut().unwrap().push(change)
+            }
+            _ => hunks.push(vec![change]),
+        }
+    }
+
+    let mut out = String::new();
+    if hunks.is_empty() {
+        return out;
+    }
+    let _ = writeln!(out, "--- {name}");
+    let _ = writeln!(out, "+++ {name}");
+    // The number of lines the hunks so far added to the file.
+    let mut delta = 0isize;
+    for hunk in hunks {
+        let first = hunk[0].0.saturating_sub(DIFF_CONTEXT);
+        let last = (hunk[hunk.len() - 1].1 + DIFF_CONTEXT).min(line_count.saturating_sub(1));
+        let mut body = String::new();
+        let (mut old_len, mut new_len) = (0, 0);
+        let mut line = first;
+        for (change_first, change_last, change) in &hunk {
+            for context in line..*change_first {
+                push_line(&mut body, ' ', &src[line_starts[context]..line_end(context)]);
+            }
+            old_len += change_first - line;
+            new_len += change_first - line;
+
+            let start = line_starts.get(*change_first).copied().unwrap_or(src.len());
+            let end = line_end(*change_last);
+            let old = &src[start..end];
+            let new = apply_edits(
+                old,
+                &change
+                    .iter()
+                    .map(|edit| Edit {
+                        range: edit.range.start - start..edit.range.end - start,
+                        snippet: edit.snippet.clone(),
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            for old_line in old.split_inclusive('\n') {
+                push_line(&mut body, '-', old_line);
+                old_len += 1;
+            }
+            for new_line in new.split_inclusive('\n') {
+                push_line(&mut body, '+', new_line);
+                new_len += 1;
+            }
+            line = change_last + 1;
+        }
+        for context in line..=last {
+            if context >= line_count {
+                break;
+            }
+            push_line(&mut body, ' ', &src[line_starts[context]..line_end(context)]);
+            old_len += 1;
+            new_len += 1;
+        }
+
+        let new_first = (first as isize + delta) as usize;
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            hunk_range(first, old_len),
+            hunk_range(new_first, new_len)
+        );
+        out.push_str(&body);
+        delta += new_len as isize - old_len as isize;
+    }
+    out
+}
+
+/// Formats the start and length of one side of a hunk, `first` is 0-based.
+fn hunk_range(first: usize, len: usize) -> String {
+    // An empty range refers to the line before it.
+    if len == 0 {
+        format!("{first},0")
+    } else {
+        format!("{},{len}", first + 1)
+    }
+}
+
+fn push_line(out: &mut String, prefix: char, line: &str) {
+    out.push(prefix);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
@@ -0,0 +1,57 @@
+use super::*;
+
+fn edit(range: Range<usize>, snippet: &str) -> Edit {
+    Edit { range, snippet: snippet.to_owned() }
+}
+
+const NUMBERS: &str = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+
+#[test]
+fn overlapping_edits() {
+    assert!(!edit(0..3, "").overlaps(&edit(3..5, "")));
+    assert!(edit(0..3, "").overlaps(&edit(2..4, "")));
+    assert!(edit(2..2, "a").overlaps(&edit(2..2, "b")));
+    assert!(edit(2..2, "a").overlaps(&edit(0..4, "")));
+    assert!(!edit(0..2, "").overlaps(&edit(2..2, "a")));
+}
+
+#[test]
+fn edits_are_applied_in_order() {
+    let edits = [edit(0..3, "x"), edit(4..4, "new "), edit(8..11, "")];
+    assert_eq!(apply_edits("abc def ghi", &edits), "x new def ");
+}
+
+#[test]
+fn separate_hunks() {
+    let edits = [edit(2..3, "two"), edit(18..18, "9.5\n")];
+    assert_eq!(
+        unified_diff("a.rs", NUMBERS, &edits),
+        "--- a.rs\n+++ a.rs\n\
+         @@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n\
+         @@ -7,4 +7,5 @@\n 7\n 8\n 9\n-10\n+9.5\n+10\n"
+    );
+}
+
+#[test]
+fn merged_hunks() {
+    let edits = [edit(2..3, "two"), edit(10..11, "six")];
+    assert_eq!(
+        unified_diff("a.rs", NUMBERS, &edits),
+        "--- a.rs\n+++ a.rs\n\
+         @@ -1,9 +1,9 @@\n 1\n-2\n+two\n 3\n 4\n 5\n-6\n+six\n 7\n 8\n 9\n"
+    );
+}
+
+#[test]
+fn no_newline_at_end_of_file() {
+    assert_eq!(
+        unified_diff("a.rs", "a\nb", &[edit(2..3, "c")]),
+        "--- a.rs\n+++ a.rs\n\
+         @@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n\\ No newline at end of file\n"
+    );
+}
+
+#[test]
+fn no_edits() {
+    assert_eq!(unified_diff("a.rs", NUMBERS, &[]), "");
+}
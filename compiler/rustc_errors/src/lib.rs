@@ -52,9 +52,11 @@
 mod diagnostic;
 mod diagnostic_builder;
 pub mod emitter;
+mod fix;
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 
@@ -423,6 +425,16 @@ struct HandlerInner {
     /// When `.abort_if_errors()` is called, these are also emitted.
     stashed_diagnostics: FxIndexMap<(Span, StashKey), Diagnostic>,
 
+    /// Diagnostics held back by `-Z group-diagnostics`, keyed by their code and
+    /// the call site of the outermost macro invocation their primary span comes
+    /// from. Each group keeps the first diagnostic along with the primary spans
+    /// of the similar ones that followed it. Groups are emitted together with
+    /// the stashed diagnostics, and count towards the error count as they come in.
+    grouped_diagnostics: FxIndexMap<(DiagnosticId, Span), (Diagnostic, Vec<Span>)>,
+
+    /// The suggestions collected for `-Z apply-suggestions`.
+    suggestion_fixes: fix::SuggestionFixes,
+
     /// The warning count, used for a recap upon finishing
     deduplicated_warn_count: usize,
 
@@ -482,11 +494,24 @@ pub struct HandlerFlags {
     pub macro_backtrace: bool,
     /// If true, identical diagnostics are reported only once.
     pub deduplicate_diagnostics: bool,
+    /// If true, diagnostics with the same code that originate in the same macro
+    /// invocation are reported once, together with the locations of the others.
+    /// (rustc: see `-Z group-diagnostics`)
+    pub group_diagnostics: bool,
+    /// If set, the suggestions of at least this applicability are applied to
+    /// the source files once compilation is finished.
+    /// (rustc: see `-Z apply-suggestions`)
+    pub apply_suggestions: Option<Applicability>,
+    /// If true, the changes of `apply_suggestions` are printed as a unified diff
+    /// rather than written to the source files.
+    /// (rustc: see `-Z apply-suggestions-diff`)
+    pub apply_suggestions_diff: bool,
 }
 
 impl Drop for HandlerInner {
     fn drop(&mut self) {
         self.emit_stashed_diagnostics();
+        self.emit_grouped_diagnostics();
 
         if !self.has_errors() {
             let bugs = std::mem::replace(&mut self.delayed_span_bugs, Vec::new());
@@ -585,6 +610,8 @@ pub fn with_emitter_and_flags(
                 emitted_diagnostic_codes: Default::default(),
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
+                grouped_diagnostics: Default::default(),
+                suggestion_fixes: Default::default(),
                 future_breakage_diagnostics: Vec::new(),
                 check_unstable_expect_diagnostics: false,
                 unstable_expect_diagnostics: Vec::new(),
@@ -948,6 +975,12 @@ pub fn abort_if_errors(&self) {
         self.inner.borrow_mut().abort_if_errors()
     }
 
+    /// Applies the suggestions collected for `-Z apply-suggestions` to the
+    /// source files, or prints them as a diff. Does nothing if the flag is unset.
+    pub fn apply_suggestions(&self) {
+        self.inner.borrow_mut().apply_suggestions()
+    }
+
     /// `true` if we haven't taught a diagnostic with this code already.
     /// The caller must then teach the user about such a diagnostic.
     ///
@@ -1115,6 +1148,10 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
             self.emitted_diagnostic_codes.insert(code.clone());
         }
 
+        if let Some(applicability) = self.flags.apply_suggestions {
+            self.suggestion_fixes.collect(diagnostic, applicability);
+        }
+
         let already_emitted = |this: &mut Self| {
             let mut hasher = StableHasher::new();
             diagnostic.hash(&mut hasher);
@@ -1141,11 +1178,21 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
 
             diagnostic.children.drain_filter(already_emitted_sub).for_each(|_| {});
 
-            self.emitter.emit_diagnostic(&diagnostic);
-            if diagnostic.is_error() {
-                self.deduplicated_err_count += 1;
-            } else if diagnostic.level == Warning {
-                self.deduplicated_warn_count += 1;
+            // Similar diagnostics that join an existing group are not reported
+            // on their own, so they do not count towards the recap either.
+            let reported = match self.diagnostic_group(diagnostic) {
+                Some(key) => self.group_diagnostic(key, diagnostic),
+                None => {
+                    self.emitter.emit_diagnostic(&diagnostic);
+                    true
+                }
+            };
+            if reported {
+                if diagnostic.is_error() {
+                    self.deduplicated_err_count += 1;
+                } else if diagnostic.level == Warning {
+                    self.deduplicated_warn_count += 1;
+                }
             }
         }
         if diagnostic.is_error() {
@@ -1171,6 +1218,77 @@ fn emit_unused_externs(&mut self, lint_level: rustc_lint_defs::Level, unused_ext
         self.emitter.emit_unused_externs(lint_level, unused_externs);
     }
 
+    /// Returns the group `diagnostic` belongs to under `-Z group-diagnostics`, or
+    /// `None` if it should be emitted right away.
+    ///
+    /// Only errors and warnings with a code whose primary span comes from a macro
+    /// expansion are grouped, they are keyed by the call site of the outermost
+    /// macro invocation, as found by walking the `ExpnData` of the span.
+    fn diagnostic_group(&self, diagnostic: &Diagnostic) -> Option<(DiagnosticId, Span)> {
+        if !self.flags.group_diagnostics
+            || self.flags.dont_buffer_diagnostics
+            || self.flags.treat_err_as_bug.is_some()
+        {
+            return None;
+        }
+        if !matches!(diagnostic.level, Level::Error { .. } | Level::Warning) {
+            return None;
+        }
+        let code = diagnostic.code.clone()?;
+        let span = diagnostic.span.primary_span().filter(|span| !span.is_dummy())?;
+        let origin = span.macro_backtrace().last()?.call_site;
+        Some((code, origin))
+    }
+
+    /// Adds `diagnostic` to the group `key`, returns `true` if it is the first
+    /// diagnostic of the group and will therefore be reported.
+    fn group_diagnostic(&mut self, key: (DiagnosticId, Span), diagnostic: &Diagnostic) -> bool {
+        if let Some((_, similar)) = self.grouped_diagnostics.get_mut(&key) {
+            similar.extend(diagnostic.span.primary_span());
+            false
+        } else {
+            self.grouped_diagnostics.insert(key, (diagnostic.clone(), Vec::new()));
+            true
+        }
+    }
+
+    /// Emit all diagnostics held back by `-Z group-diagnostics`, each with a note
+    /// listing the locations of its similar occurrences.
+    fn emit_grouped_diagnostics(&mut self) {
+        let groups = self.grouped_diagnostics.drain(..).map(|x| x.1).collect::<Vec<_>>();
+        for (mut diag, similar) in groups {
+            if !similar.is_empty() {
+                let mut note = format!(
+                    "and {} similar occurrence{}:",
+                    similar.len(),
+                    pluralize!(similar.len())
+                );
+                for span in similar {
+                    note.push('\n');
+                    note.push_str(&self.span_location(span));
+                }
+                diag.note(note);
+            }
+            self.emitter.emit_diagnostic(&diag);
+        }
+    }
+
+    /// Formats the start of `span` as `file:line:col`.
+    fn span_location(&self, span: Span) -> String {
+        match self.emitter.source_map() {
+            Some(sm) => {
+                let loc = sm.lookup_char_pos(span.lo());
+                format!(
+                    "{}:{}:{}",
+                    sm.filename_for_diagnostics(&loc.file.name),
+                    loc.line,
+                    loc.col.0 + 1
+                )
+            }
+            None => format!("{:?}", span),
+        }
+    }
+
     fn treat_err_as_bug(&self) -> bool {
         self.flags
             .treat_err_as_bug
@@ -1179,6 +1297,7 @@ fn treat_err_as_bug(&self) -> bool {
 
     fn print_error_count(&mut self, registry: &Registry) {
         self.emit_stashed_diagnostics();
+        self.emit_grouped_diagnostics();
 
         let warnings = match self.deduplicated_warn_count {
             0 => String::new(),
@@ -1196,15 +1315,15 @@ fn print_error_count(&mut self, registry: &Registry) {
 
         match (errors.len(), warnings.len()) {
             (0, 0) => return,
-            (0, _) => self.emitter.emit_diagnostic(&Diagnostic::new(
-                Level::Warning,
-                DiagnosticMessage::Str(warnings),
-            )),
+            (0, _) => self.emitter.emit_diagnostic(
+                Diagnostic::new(Level::Warning, DiagnosticMessage::Str(warnings)).set_is_summary(),
+            ),
             (_, 0) => {
-                let _ = self.fatal(&errors);
+                self.emit_diagnostic(Diagnostic::new(Fatal, errors).set_is_summary());
             }
             (_, _) => {
-                let _ = self.fatal(&format!("{}; {}", &errors, &warnings));
+                let summary = format!("{}; {}", &errors, &warnings);
+                self.emit_diagnostic(Diagnostic::new(Fatal, summary).set_is_summary());
             }
         }
 
@@ -1268,12 +1387,25 @@ fn has_any_message(&self) -> bool {
 
     fn abort_if_errors(&mut self) {
         self.emit_stashed_diagnostics();
+        self.emit_grouped_diagnostics();
 
         if self.has_errors() {
             FatalError.raise();
         }
     }
 
+    fn apply_suggestions(&mut self) {
+        if self.flags.apply_suggestions.is_none() {
+            return;
+        }
+        self.emit_stashed_diagnostics();
+        self.emit_grouped_diagnostics();
+        let Some(sm) = self.emitter.source_map().cloned() else { return };
+        for mut diag in self.suggestion_fixes.apply(&sm, self.flags.apply_suggestions_diff) {
+            self.emit_diagnostic(&mut diag);
+        }
+    }
+
     fn span_bug(&mut self, sp: impl Into<MultiSpan>, msg: impl Into<DiagnosticMessage>) -> ! {
         self.emit_diag_at_span(Diagnostic::new(Bug, msg), sp);
         panic::panic_any(ExplicitBug);
@@ -0,0 +1,533 @@
+//! A SARIF emitter for errors.
+//!
+//! Unlike the JSON emitter, which writes one object per diagnostic, this
+//! collects all diagnostics of a compilation session and writes them as a single
+//! [SARIF 2.1.0] log once the emitter is dropped, which is what code scanning
+//! tools expect to ingest.
+//!
+//! Every diagnostic becomes a `result`. Error codes and lint names become the
+//! `rules` of the run, primary spans become `locations`, secondary spans and
+//! notes with a span become `relatedLocations`, and suggestions become `fixes`.
+//!
+//! [SARIF 2.1.0]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::{CodeSuggestion, DiagnosticId, FluentBundle, LazyFallbackBundle, Level, SpanLabel};
+use rustc_lint_defs::Applicability;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_error_messages::FluentArgs;
+use rustc_span::Span;
+use std::io::{self, Write};
+use std::mem;
+
+use serde::Serialize;
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    fluent_bundle: Option<Lrc<FluentBundle>>,
+    fallback_bundle: LazyFallbackBundle,
+    /// The rules referenced by `results`, in order of first use.
+    rules: Vec<ReportingDescriptor>,
+    rule_indices: FxHashMap<String, usize>,
+    results: Vec<SarifResult>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            registry,
+            source_map,
+            fluent_bundle,
+            fallback_bundle,
+        )
+    }
+
+    pub fn basic(
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(
+            None,
+            Lrc::new(SourceMap::new(file_path_mapping)),
+            fluent_bundle,
+            fallback_bundle,
+        )
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            fluent_bundle,
+            fallback_bundle,
+            rules: Vec::new(),
+            rule_indices: FxHashMap::default(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the rule for `code` in `self.rules`, adding the rule
+    /// if this is the first diagnostic using it.
+    fn rule_index(&mut self, code: &DiagnosticId) -> usize {
+        let id = match code {
+            DiagnosticId::Error(code) => code,
+            DiagnosticId::Lint { name, .. } => name,
+        };
+        if let Some(&index) = self.rule_indices.get(id) {
+            return index;
+        }
+
+        let rule = match code {
+            DiagnosticId::Error(code) => {
+                let explanation = self
+                    .registry
+                    .as_ref()
+                    .and_then(|registry| registry.try_find_description(code).ok())
+                    .flatten();
+                ReportingDescriptor {
+                    id: code.clone(),
+                    full_description: explanation.map(|explanation| MultiformatMessage {
+                        text: explanation.to_owned(),
+                        markdown: explanation.to_owned(),
+                    }),
+                    help_uri: Some(format!("https://doc.rust-lang.org/error-index.html#{code}")),
+                }
+            }
+            DiagnosticId::Lint { name, .. } => {
+                ReportingDescriptor { id: name.clone(), full_description: None, help_uri: None }
+            }
+        };
+        let index = self.rules.len();
+        self.rules.push(rule);
+        self.rule_indices.insert(id.clone(), index);
+        index
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "rustc",
+                        version: option_env!("CFG_VERSION"),
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: mem::take(&mut self.rules),
+                    },
+                },
+                results: mem::take(&mut self.results),
+                column_kind: "unicodeCodePoints",
+            }],
+        };
+        writeln!(&mut self.dst, "{}", serde_json::to_string_pretty(&log).unwrap())?;
+        self.dst.flush()
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        // Summaries like "aborting due to previous error" and the list of
+        // error codes with explanations are not findings.
+        if diag.is_summary || diag.level.is_failure_note() {
+            return;
+        }
+        let rule_index = diag.code.as_ref().map(|code| self.rule_index(code));
+        let result = SarifResult::from_errors_diagnostic(diag, rule_index, self);
+        self.results.push(result);
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.fluent_bundle.as_ref()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &**self.fallback_bundle
+    }
+
+    fn should_show_explain(&self) -> bool {
+        // The explanations are part of the rules already.
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if let Err(e) = self.write_log() {
+            // Don't turn an ICE into an abort by panicking twice.
+            if !std::thread::panicking() {
+                panic!("failed to print diagnostics: {:?}", e);
+            }
+        }
+    }
+}
+
+// The following data types are provided just for serialisation, their names
+// and fields follow the SARIF specification.
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+    /// How `startColumn` and `endColumn` of regions are counted.
+    column_kind: &'static str,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: ToolComponent,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent {
+    name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'static str>,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    /// The error code or the name of the lint.
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<MultiformatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+struct MultiformatMessage {
+    text: String,
+    markdown: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning", "note" or "none".
+    level: &'static str,
+    message: Message,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<ArtifactContent>,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+struct FixProperties {
+    applicability: Applicability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+impl SarifResult {
+    fn from_errors_diagnostic(
+        diag: &crate::Diagnostic,
+        rule_index: Option<usize>,
+        se: &SarifEmitter,
+    ) -> SarifResult {
+        let args = se.to_fluent_args(diag.args());
+        let mut text = se.translate_messages(&diag.message, &args).into_owned();
+
+        let mut locations = Vec::new();
+        let mut related_locations = Vec::new();
+        for span_label in diag.span.span_labels() {
+            let is_primary = span_label.is_primary;
+            if let Some(location) = Location::from_span_label(span_label, None, &args, se) {
+                if is_primary {
+                    locations.push(location);
+                } else {
+                    related_locations.push(location);
+                }
+            }
+        }
+
+        // Notes and helps pointing at code become related locations, the
+        // others are appended to the message the way rustc renders them.
+        for child in &diag.children {
+            let message = format!(
+                "{}: {}",
+                child.level.to_str(),
+                se.translate_messages(&child.message, &args)
+            );
+            let span = child.render_span.as_ref().unwrap_or(&child.span);
+            let mut has_location = false;
+            for span_label in span.span_labels() {
+                if let Some(location) =
+                    Location::from_span_label(span_label, Some(&message), &args, se)
+                {
+                    related_locations.push(location);
+                    has_location = true;
+                }
+            }
+            if !has_location {
+                text.push_str("\n= ");
+                text.push_str(&message);
+            }
+        }
+        for (id, location) in related_locations.iter_mut().enumerate() {
+            location.id = Some(id);
+        }
+
+        let fixes = diag
+            .suggestions
+            .iter()
+            .flatten()
+            .flat_map(|suggestion| Fix::from_suggestion(suggestion, &args, se))
+            .collect();
+
+        SarifResult {
+            rule_id: rule_index.map(|index| se.rules[index].id.clone()),
+            rule_index,
+            level: sarif_level(diag.level),
+            message: Message { text },
+            locations,
+            related_locations,
+            fixes,
+        }
+    }
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error { .. } => "error",
+        Level::Warning => "warning",
+        Level::Note | Level::OnceNote | Level::Help | Level::FailureNote => "note",
+        Level::Allow | Level::Expect(_) => "none",
+    }
+}
+
+impl Location {
+    /// Converts a span label into a location, `default_message` is used for
+    /// spans without a label of their own.
+    fn from_span_label(
+        span_label: SpanLabel,
+        default_message: Option<&str>,
+        args: &FluentArgs<'_>,
+        se: &SarifEmitter,
+    ) -> Option<Location> {
+        if span_label.span.is_dummy() {
+            return None;
+        }
+        let label = span_label.label.as_ref().map(|m| se.translate_message(m, args).into_owned());
+        Some(Location {
+            id: None,
+            physical_location: PhysicalLocation {
+                artifact_location: ArtifactLocation::from_span(span_label.span, se),
+                region: Region::from_span(span_label.span, true, se),
+            },
+            message: label
+                .or_else(|| default_message.map(str::to_owned))
+                .map(|text| Message { text }),
+        })
+    }
+}
+
+impl ArtifactLocation {
+    fn from_span(span: Span, se: &SarifEmitter) -> ArtifactLocation {
+        let file = se.sm.lookup_source_file(span.lo());
+        ArtifactLocation { uri: file_uri(&se.sm.filename_for_diagnostics(&file.name).to_string()) }
+    }
+}
+
+impl Region {
+    fn from_span(span: Span, with_snippet: bool, se: &SarifEmitter) -> Region {
+        let start = se.sm.lookup_char_pos(span.lo());
+        let end = se.sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+            byte_offset: byte_start,
+            byte_length: byte_end.saturating_sub(byte_start),
+            snippet: if with_snippet {
+                se.sm.span_to_snippet(span).ok().map(|text| ArtifactContent { text })
+            } else {
+                None
+            },
+        }
+    }
+}
+
+impl Fix {
+    /// Every substitution of a suggestion is an alternative way of fixing the
+    /// problem, so each becomes a fix of its own.
+    fn from_suggestion(
+        suggestion: &CodeSuggestion,
+        args: &FluentArgs<'_>,
+        se: &SarifEmitter,
+    ) -> Vec<Fix> {
+        let description = se.translate_message(&suggestion.msg, args);
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                let mut artifact_changes: Vec<ArtifactChange> = Vec::new();
+                for part in &substitution.parts {
+                    let artifact_location = ArtifactLocation::from_span(part.span, se);
+                    let replacement = Replacement {
+                        deleted_region: Region::from_span(part.span, false, se),
+                        inserted_content: ArtifactContent { text: part.snippet.clone() },
+                    };
+                    match artifact_changes
+                        .iter_mut()
+                        .find(|change| change.artifact_location.uri == artifact_location.uri)
+                    {
+                        Some(change) => change.replacements.push(replacement),
+                        None => artifact_changes.push(ArtifactChange {
+                            artifact_location,
+                            replacements: vec![replacement],
+                        }),
+                    }
+                }
+                Fix {
+                    description: Message { text: description.to_string() },
+                    artifact_changes,
+                    properties: FixProperties { applicability: suggestion.applicability },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Turns a file name as it is shown in diagnostics into a URI reference,
+/// absolute paths become `file` URIs and relative ones stay relative.
+fn file_uri(file_name: &str) -> String {
+    let path = file_name.replace('\\', "/");
+    let mut uri = String::with_capacity(path.len());
+    if path.starts_with('/') {
+        uri.push_str("file://");
+    } else if path.as_bytes().get(1) == Some(&b':') {
+        // A Windows path with a drive letter.
+        uri.push_str("file:///");
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
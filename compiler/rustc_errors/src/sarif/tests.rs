@@ -0,0 +1,170 @@
+use super::*;
+
+use crate::{Handler, MultiSpan};
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+/// Runs `f` with a handler emitting SARIF for `code` and returns the log.
+fn sarif_log(code: &str, f: impl FnOnce(&Handler)) -> Value {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("src/test file.rs").to_owned().into(), code.to_owned());
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(rustc_error_messages::DEFAULT_LOCALE_RESOURCES, false);
+        let registry = Registry::new(&[("E0001", Some("An explanation.")), ("E0002", None)]);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            Some(registry),
+            sm,
+            None,
+            fallback_bundle,
+        );
+
+        let handler = Handler::with_emitter(true, None, Box::new(se));
+        f(&handler);
+        drop(handler);
+
+        let bytes = output.lock().unwrap();
+        serde_json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+    })
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn empty_log() {
+    let log = sarif_log("", |_| {});
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "rustc");
+    assert_eq!(run["tool"]["driver"]["rules"], Value::Array(vec![]));
+    assert_eq!(run["results"], Value::Array(vec![]));
+}
+
+#[test]
+fn levels_rules_and_locations() {
+    let log = sarif_log("fn main() {\n    let x = 1;\n}\n", |handler| {
+        let mut multispan = MultiSpan::from_span(span(20, 21));
+        multispan.push_span_label(span(16, 19), "declared here");
+        handler
+            .struct_span_err_with_code(multispan, "first", DiagnosticId::Error("E0001".to_owned()))
+            .note("a note without a span")
+            .span_note(span(0, 2), "a note with a span")
+            .emit();
+        handler
+            .struct_span_err_with_code(
+                span(3, 7),
+                "second",
+                DiagnosticId::Error("E0001".to_owned()),
+            )
+            .emit();
+        handler
+            .struct_span_warn(span(20, 21), "unused variable")
+            .code(DiagnosticId::Lint {
+                name: "unused_variables".to_owned(),
+                has_future_breakage: false,
+                is_force_warn: false,
+            })
+            .emit();
+    });
+
+    let run = &log["runs"][0];
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0]["id"], "E0001");
+    assert_eq!(rules[0]["fullDescription"]["markdown"], "An explanation.");
+    assert_eq!(rules[1]["id"], "unused_variables");
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+
+    let first = &results[0];
+    assert_eq!(first["ruleId"], "E0001");
+    assert_eq!(first["ruleIndex"], 0);
+    assert_eq!(first["level"], "error");
+    assert_eq!(first["message"]["text"], "first\n= note: a note without a span");
+    let location = &first["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "src/test%20file.rs");
+    assert_eq!(location["region"]["startLine"], 2);
+    assert_eq!(location["region"]["startColumn"], 9);
+    assert_eq!(location["region"]["byteOffset"], 20);
+    assert_eq!(location["region"]["byteLength"], 1);
+    assert_eq!(location["region"]["snippet"]["text"], "x");
+    let related = first["relatedLocations"].as_array().unwrap();
+    assert_eq!(related.len(), 2);
+    assert_eq!(related[0]["id"], 0);
+    assert_eq!(related[0]["message"]["text"], "declared here");
+    assert_eq!(related[1]["id"], 1);
+    assert_eq!(related[1]["message"]["text"], "note: a note with a span");
+
+    assert_eq!(results[1]["ruleIndex"], 0);
+    assert_eq!(results[2]["ruleId"], "unused_variables");
+    assert_eq!(results[2]["ruleIndex"], 1);
+    assert_eq!(results[2]["level"], "warning");
+}
+
+#[test]
+fn summaries_are_not_results() {
+    let log = sarif_log("fn main() {}\n", |handler| {
+        handler.struct_span_err(span(3, 7), "an error").emit();
+        handler.struct_span_warn(span(3, 7), "a warning").emit();
+        handler.print_error_count(&Registry::new(&[]));
+    });
+
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["message"]["text"], "an error");
+    assert_eq!(results[1]["message"]["text"], "a warning");
+}
+
+#[test]
+fn suggestions_become_fixes() {
+    let log = sarif_log("fn main() {\n    let x = 1;\n}\n", |handler| {
+        handler
+            .struct_span_warn(span(20, 21), "unused variable")
+            .span_suggestion(span(20, 21), "prefix it", "_x", Applicability::MachineApplicable)
+            .emit();
+    });
+
+    let fixes = log["runs"][0]["results"][0]["fixes"].as_array().unwrap();
+    assert_eq!(fixes.len(), 1);
+    assert_eq!(fixes[0]["description"]["text"], "prefix it");
+    assert_eq!(fixes[0]["properties"]["applicability"], "MachineApplicable");
+    let change = &fixes[0]["artifactChanges"][0];
+    assert_eq!(change["artifactLocation"]["uri"], "src/test%20file.rs");
+    let replacement = &change["replacements"][0];
+    assert_eq!(replacement["deletedRegion"]["byteOffset"], 20);
+    assert_eq!(replacement["deletedRegion"]["byteLength"], 1);
+    assert_eq!(replacement["insertedContent"]["text"], "_x");
+}
+
+#[test]
+fn uris() {
+    assert_eq!(file_uri("src/main.rs"), "src/main.rs");
+    assert_eq!(file_uri("/home/user/a b.rs"), "file:///home/user/a%20b.rs");
+    assert_eq!(file_uri("C:\\src\\main.rs"), "file:///C:/src/main.rs");
+    assert_eq!(file_uri("<anon>"), "%3Canon%3E");
+}
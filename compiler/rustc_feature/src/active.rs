@@ -150,6 +150,9 @@ pub fn set(&self, features: &mut Features, span: Span) {
     (active, allow_internal_unstable, "1.0.0", None, None),
     /// Allows identifying the `compiler_builtins` crate.
     (active, compiler_builtins, "1.13.0", None, None),
+    /// Allows `#[doc(coverage(...))]`, which sets the minimum documentation coverage checked by
+    /// rustdoc's unstable `--show-coverage`.
+    (active, doc_coverage, "1.63.0", None, None),
     /// Outputs useful `assert!` messages
     (active, generic_assert, "1.63.0", None, None),
     /// Allows using the `rust-intrinsic`'s "ABI".
@@ -1,7 +1,7 @@
 use crate::interface::parse_cfgspecs;
 
 use rustc_data_structures::fx::FxHashSet;
-use rustc_errors::{emitter::HumanReadableErrorType, registry, ColorConfig};
+use rustc_errors::{emitter::HumanReadableErrorType, registry, Applicability, ColorConfig};
 use rustc_session::config::InstrumentCoverage;
 use rustc_session::config::Strip;
 use rustc_session::config::{build_configuration, build_session_options, to_crate_config};
@@ -643,6 +643,8 @@ macro_rules! untracked {
 
     // Make sure that changing an [UNTRACKED] option leaves the hash unchanged.
     // This list is in alphabetical order.
+    untracked!(apply_suggestions, Some(Applicability::MachineApplicable));
+    untracked!(apply_suggestions_diff, true);
     untracked!(assert_incr_state, Some(String::from("loaded")));
     untracked!(deduplicate_diagnostics, false);
     untracked!(dep_tasks, true);
@@ -656,6 +658,7 @@ macro_rules! untracked {
     untracked!(dump_mir_graphviz, true);
     untracked!(emit_stack_sizes, true);
     untracked!(future_incompat_test, true);
+    untracked!(group_diagnostics, true);
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
     untracked!(incremental_ignore_spans, true);
@@ -685,7 +688,9 @@ macro_rules! untracked {
     untracked!(query_dep_graph, true);
     untracked!(save_analysis, true);
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
+    untracked!(self_profile_chrome_trace, true);
     untracked!(self_profile_events, Some(vec![String::new()]));
+    untracked!(self_profile_summary, true);
     untracked!(span_debug, true);
     untracked!(span_free_formats, true);
     untracked!(temps_dir, Some(String::from("abc")));
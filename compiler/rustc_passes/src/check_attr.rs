@@ -1030,6 +1030,43 @@ fn check_test_attr(&self, meta: &NestedMetaItem, hir_id: HirId) -> bool {
         is_valid
     }
 
+    /// Checks that `#[doc(coverage(...))]` is applied to a module, and that it only sets the
+    /// minimum percentages of `docs` and `examples`. Returns `true` if valid.
+    fn check_doc_coverage(&self, meta: &NestedMetaItem, hir_id: HirId, target: Target) -> bool {
+        if target != Target::Mod {
+            self.tcx.struct_span_lint_hir(INVALID_DOC_ATTRIBUTES, hir_id, meta.span(), |lint| {
+                lint.build("`#[doc(coverage(...))]` can only be applied to modules").emit();
+            });
+            return false;
+        }
+        let Some(metas) = meta.meta_item_list() else {
+            self.tcx.struct_span_lint_hir(INVALID_DOC_ATTRIBUTES, hir_id, meta.span(), |lint| {
+                lint.build("`#[doc(coverage(...))]` takes a list of minimum percentages").emit();
+            });
+            return false;
+        };
+        let mut is_valid = true;
+        for i_meta in metas {
+            let percentage = i_meta.meta_item().and_then(|i_meta| i_meta.name_value_literal());
+            let valid = matches!(i_meta.name_or_empty(), sym::docs | sym::examples)
+                && matches!(percentage, Some(Lit { kind: LitKind::Int(0..=100, _), .. }));
+            if !valid {
+                self.tcx.struct_span_lint_hir(
+                    INVALID_DOC_ATTRIBUTES,
+                    hir_id,
+                    i_meta.span(),
+                    |lint| {
+                        lint.build("invalid `doc(coverage)` attribute")
+                            .help("expected `docs = N` or `examples = N`, N being a percentage")
+                            .emit();
+                    },
+                );
+                is_valid = false;
+            }
+        }
+        is_valid
+    }
+
     /// Runs various checks on `#[doc]` attributes. Returns `true` if valid.
     ///
     /// `specified_inline` should be initialized to `None` and kept for the scope
@@ -1076,6 +1113,10 @@ fn check_doc_attrs(
                             is_valid = false;
                         }
 
+                        sym::coverage if !self.check_doc_coverage(meta, hir_id, target) => {
+                            is_valid = false;
+                        }
+
                         sym::inline | sym::no_inline
                             if !self.check_doc_inline(
                                 attr,
@@ -1103,6 +1144,7 @@ fn check_doc_attrs(
                         sym::alias
                         | sym::cfg
                         | sym::cfg_hide
+                        | sym::coverage
                         | sym::hidden
                         | sym::html_favicon_url
                         | sym::html_logo_url
@@ -269,6 +269,7 @@ fn alloc_self_profile_query_strings_for_query_cache<'tcx, C>(
                     query_invocation_id,
                     event_id.to_string_id(),
                 );
+                profiler.map_query_invocation_id_to_label(query_invocation_id, query_name);
             }
         } else {
             // In this branch we don't allocate query keys
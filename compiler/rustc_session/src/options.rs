@@ -4,7 +4,7 @@
 use crate::lint;
 use crate::search_paths::SearchPath;
 use crate::utils::NativeLib;
-use rustc_errors::LanguageIdentifier;
+use rustc_errors::{Applicability, LanguageIdentifier};
 use rustc_target::spec::{CodeModel, LinkerFlavor, MergeFunctions, PanicStrategy, SanitizerSet};
 use rustc_target::spec::{
     RelocModel, RelroLevel, SplitDebuginfo, StackProtector, TargetTriple, TlsModel,
@@ -415,6 +415,7 @@ mod desc {
         "one of (`none` (default), `basic`, `strong`, or `all`)";
     pub const parse_branch_protection: &str =
         "a `,` separated combination of `bti`, `b-key`, `pac-ret`, or `leaf`";
+    pub const parse_apply_suggestions: &str = "either `machine-applicable` or `maybe-incorrect`";
 }
 
 mod parse {
@@ -987,6 +988,18 @@ pub(crate) fn parse_target_feature(slot: &mut String, v: Option<&str>) -> bool {
         }
     }
 
+    pub(crate) fn parse_apply_suggestions(
+        slot: &mut Option<Applicability>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            Some("machine-applicable") => *slot = Some(Applicability::MachineApplicable),
+            Some("maybe-incorrect") => *slot = Some(Applicability::MaybeIncorrect),
+            _ => return false,
+        }
+        true
+    }
+
     pub(crate) fn parse_wasi_exec_model(slot: &mut Option<WasiExecModel>, v: Option<&str>) -> bool {
         match v {
             Some("command") => *slot = Some(WasiExecModel::Command),
@@ -1197,6 +1210,12 @@ pub(crate) fn parse_branch_protection(
         "only allow the listed language features to be enabled in code (space separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata (default: no)"),
+    apply_suggestions: Option<Applicability> = (None, parse_apply_suggestions, [UNTRACKED],
+        "rewrite the source files with the suggestions of the given applicability, \
+        `maybe-incorrect` also applies `machine-applicable` suggestions"),
+    apply_suggestions_diff: bool = (false, parse_bool, [UNTRACKED],
+        "print the changes `-Z apply-suggestions` would make as a unified diff \
+        instead of rewriting the source files (default: no)"),
     assume_incomplete_release: bool = (false, parse_bool, [TRACKED],
         "make cfg(version) treat the current version as incomplete (default: no)"),
     asm_comments: bool = (false, parse_bool, [TRACKED],
@@ -1288,6 +1307,9 @@ pub(crate) fn parse_branch_protection(
     graphviz_font: String = ("Courier, monospace".to_string(), parse_string, [UNTRACKED],
         "use the given `fontname` in graphviz output; can be overridden by setting \
         environment variable `RUSTC_GRAPHVIZ_FONT` (default: `Courier, monospace`)"),
+    group_diagnostics: bool = (false, parse_bool, [UNTRACKED],
+        "report diagnostics with the same code that originate in the same macro invocation \
+        once, listing the locations of the similar ones (default: no)"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about AST and HIR (default: no)"),
     human_readable_cgu_names: bool = (false, parse_bool, [TRACKED],
@@ -1473,6 +1495,12 @@ pub(crate) fn parse_branch_protection(
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
+    self_profile_chrome_trace: bool = (false, parse_bool, [UNTRACKED],
+        "with `-Z self-profile`, also write the events as a Chrome trace event file \
+        (`<crate>-<pid>.trace.json`) that can be opened in `chrome://tracing` (default: no)"),
+    self_profile_summary: bool = (false, parse_bool, [UNTRACKED],
+        "with `-Z self-profile`, also write a table of the time spent per query and pass \
+        (`<crate>-<pid>.summary.txt`) (default: no)"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     show_span: Option<String> = (None, parse_opt_string, [TRACKED],
@@ -11,7 +11,9 @@
 use rustc_data_structures::flock;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::jobserver::{self, Client};
-use rustc_data_structures::profiling::{duration_to_secs_str, SelfProfiler, SelfProfilerRef};
+use rustc_data_structures::profiling::{
+    duration_to_secs_str, SelfProfileReports, SelfProfiler, SelfProfilerRef,
+};
 use rustc_data_structures::sync::{
     self, AtomicU64, AtomicUsize, Lock, Lrc, OnceCell, OneThread, Ordering, Ordering::SeqCst,
 };
@@ -19,6 +21,7 @@
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
 use rustc_errors::json::JsonEmitter;
 use rustc_errors::registry::Registry;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::{
     fallback_fluent_bundle, DiagnosticBuilder, DiagnosticId, DiagnosticMessage, EmissionGuarantee,
     ErrorGuaranteed, FluentBundle, LazyFallbackBundle, MultiSpan,
@@ -251,6 +254,7 @@ fn check_miri_unleashed_features(&self) {
     /// Invoked all the way at the end to finish off diagnostics printing.
     pub fn finish_diagnostics(&self, registry: &Registry) {
         self.check_miri_unleashed_features();
+        self.diagnostic().apply_suggestions();
         self.diagnostic().print_error_count(registry);
         self.emit_future_breakage();
     }
@@ -1168,6 +1172,16 @@ fn default_emitter(
             )
             .ui_testing(sopts.debugging_opts.ui_testing),
         ),
+        (config::ErrorOutputType::Sarif, None) => {
+            Box::new(SarifEmitter::stderr(Some(registry), source_map, bundle, fallback_bundle))
+        }
+        (config::ErrorOutputType::Sarif, Some(dst)) => Box::new(SarifEmitter::new(
+            dst,
+            Some(registry),
+            source_map,
+            bundle,
+            fallback_bundle,
+        )),
     }
 }
 
@@ -1253,6 +1267,10 @@ pub fn build_session(
             directory,
             sopts.crate_name.as_deref(),
             &sopts.debugging_opts.self_profile_events,
+            SelfProfileReports {
+                summary: sopts.debugging_opts.self_profile_summary,
+                chrome_trace: sopts.debugging_opts.self_profile_chrome_trace,
+            },
         );
         match profiler {
             Ok(profiler) => Some(Arc::new(profiler)),
@@ -1488,6 +1506,7 @@ fn early_error_handler(output: config::ErrorOutputType) -> rustc_errors::Handler
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, None, fallback_bundle, None, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic(None, fallback_bundle)),
     };
     rustc_errors::Handler::with_emitter(true, None, emitter)
 }
@@ -0,0 +1,136 @@
+//! Entry points that run the compiler and hand out the stable representation of
+//! the crate being compiled.
+
+use crate::convert::{internal_def_id, item_kind, Stable};
+use crate::very_unstable::driver::{self, Compilation};
+use crate::very_unstable::hir::def_id::{self, LOCAL_CRATE};
+use crate::very_unstable::interface::{interface::Compiler, Queries};
+use crate::very_unstable::middle::ty::{self, TyCtxt};
+use crate::{mir, ty as sty, Crate, CrateItem, CrateNum, DefId};
+
+/// Access to the stable representation of the crate being compiled.
+///
+/// A context is only available while the compiler is running, see
+/// [`StableMirCallbacks`] and [`run_compiler`].
+pub struct Context<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> Context<'tcx> {
+    /// Returns the crate being compiled.
+    pub fn local_crate(&self) -> Crate {
+        self.crate_info(LOCAL_CRATE)
+    }
+
+    /// Returns all crates the local crate depends on, directly or indirectly.
+    pub fn external_crates(&self) -> Vec<Crate> {
+        self.tcx.crates(()).iter().map(|&krate| self.crate_info(krate)).collect()
+    }
+
+    fn crate_info(&self, krate: def_id::CrateNum) -> Crate {
+        Crate {
+            id: krate.stable(self.tcx),
+            name: self.tcx.crate_name(krate).to_string(),
+            is_local: krate == LOCAL_CRATE,
+        }
+    }
+
+    /// Returns all items of the local crate that have a MIR body, including
+    /// closures and the constructors of tuple structs and variants.
+    pub fn all_local_items(&self) -> Vec<CrateItem> {
+        self.tcx
+            .mir_keys(())
+            .iter()
+            .filter_map(|&local_def_id| {
+                let def_id = local_def_id.to_def_id();
+                Some(CrateItem {
+                    def_id: def_id.stable(self.tcx),
+                    name: self.tcx.def_path_str(def_id),
+                    kind: item_kind(self.tcx.def_kind(def_id))?,
+                    span: self.tcx.def_span(def_id).stable(self.tcx),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the MIR body of `item` after all optimizations, or `None` if the
+    /// item has no body, like a trait method without a default.
+    pub fn mir_body(&self, item: DefId) -> Option<mir::Body> {
+        let def_id = internal_def_id(item);
+        let available = match def_id.as_local() {
+            Some(local_def_id) => self.tcx.mir_keys(()).contains(&local_def_id),
+            None => self.tcx.is_mir_available(def_id) || self.tcx.is_ctfe_mir_available(def_id),
+        };
+        if !available {
+            return None;
+        }
+        let instance = ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def_id));
+        Some(self.tcx.instance_mir(instance).stable(self.tcx))
+    }
+
+    /// Returns the declared type of `item`.
+    pub fn type_of(&self, item: DefId) -> sty::Ty {
+        self.tcx.type_of(internal_def_id(item)).stable(self.tcx)
+    }
+
+    /// Returns the crate `item` is defined in.
+    pub fn crate_of(&self, item: DefId) -> Crate {
+        self.crate_info(internal_def_id(item).krate)
+    }
+
+    /// Returns the path of `item`, as rustc prints it in diagnostics.
+    pub fn def_path_str(&self, item: DefId) -> String {
+        self.tcx.def_path_str(internal_def_id(item))
+    }
+
+    /// Returns the crate with the given id.
+    pub fn krate(&self, krate: CrateNum) -> Crate {
+        self.crate_info(def_id::CrateNum::from_u32(krate.0))
+    }
+}
+
+/// Compiler callbacks that call `callback` with a [`Context`] once the crate
+/// passed analysis, and then stop the compilation.
+pub struct StableMirCallbacks<F> {
+    callback: F,
+}
+
+impl<F> StableMirCallbacks<F>
+where
+    F: FnMut(&Context<'_>) + Send,
+{
+    pub fn new(callback: F) -> Self {
+        StableMirCallbacks { callback }
+    }
+}
+
+impl<F> driver::Callbacks for StableMirCallbacks<F>
+where
+    F: FnMut(&Context<'_>) + Send,
+{
+    fn after_analysis<'tcx>(
+        &mut self,
+        compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        compiler.session().abort_if_errors();
+        queries.global_ctxt().unwrap().peek_mut().enter(|tcx| (self.callback)(&Context { tcx }));
+        Compilation::Stop
+    }
+}
+
+/// The compiler reported errors, which have already been emitted.
+#[derive(Debug)]
+pub struct CompilationFailed;
+
+/// Runs the compiler with the command line `args`, where `args[0]` is the
+/// name of the program, and calls `callback` once the crate passed analysis.
+pub fn run_compiler<F>(args: &[String], callback: F) -> Result<(), CompilationFailed>
+where
+    F: FnMut(&Context<'_>) + Send,
+{
+    let mut callbacks = StableMirCallbacks::new(callback);
+    driver::catch_fatal_errors(|| driver::RunCompiler::new(args, &mut callbacks).run())
+        .and_then(|result| result)
+        .map_err(|_| CompilationFailed)
+}
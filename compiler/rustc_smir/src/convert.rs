@@ -0,0 +1,781 @@
+//! Conversion of the compiler's data structures into their stable mirrors.
+
+use crate::very_unstable::hir::{self, def::DefKind, def_id};
+use crate::very_unstable::middle::mir::interpret::{ConstValue, Scalar};
+use crate::very_unstable::middle::mir::{self, ConstantKind};
+use crate::very_unstable::middle::ty::{self, adjustment::PointerCast, TyCtxt};
+use crate::very_unstable::span::Span;
+use crate::{mir as smir, ty as sty};
+
+/// Converts a compiler data structure into its stable counterpart.
+pub(crate) trait Stable<'tcx> {
+    type T;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T;
+}
+
+impl<'tcx, S: Stable<'tcx>> Stable<'tcx> for Option<S> {
+    type T = Option<S::T>;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        self.as_ref().map(|s| s.stable(tcx))
+    }
+}
+
+impl<'tcx, S: Stable<'tcx>> Stable<'tcx> for [S] {
+    type T = Vec<S::T>;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        self.iter().map(|s| s.stable(tcx)).collect()
+    }
+}
+
+impl<'tcx> Stable<'tcx> for def_id::CrateNum {
+    type T = crate::CrateNum;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        crate::CrateNum(self.as_u32())
+    }
+}
+
+impl<'tcx> Stable<'tcx> for def_id::DefId {
+    type T = crate::DefId;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        crate::DefId { krate: self.krate.stable(tcx), index: self.index.as_u32() }
+    }
+}
+
+pub(crate) fn internal_def_id(def_id: crate::DefId) -> def_id::DefId {
+    def_id::DefId {
+        krate: def_id::CrateNum::from_u32(def_id.krate.0),
+        index: def_id::DefIndex::from_u32(def_id.index),
+    }
+}
+
+impl<'tcx> Stable<'tcx> for Span {
+    type T = crate::Span;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        let source_map = tcx.sess.source_map();
+        let lo = source_map.lookup_char_pos(self.lo());
+        let hi = source_map.lookup_char_pos(self.hi());
+        crate::Span {
+            file: lo.file.name.prefer_local().to_string(),
+            lo_line: lo.line,
+            lo_col: lo.col.0 + 1,
+            hi_line: hi.line,
+            hi_col: hi.col.0 + 1,
+        }
+    }
+}
+
+pub(crate) fn item_kind(def_kind: DefKind) -> Option<crate::ItemKind> {
+    Some(match def_kind {
+        DefKind::Fn => crate::ItemKind::Fn,
+        DefKind::AssocFn => crate::ItemKind::AssocFn,
+        DefKind::Closure => crate::ItemKind::Closure,
+        DefKind::Generator => crate::ItemKind::Generator,
+        DefKind::Const => crate::ItemKind::Const,
+        DefKind::AssocConst => crate::ItemKind::AssocConst,
+        DefKind::AnonConst | DefKind::InlineConst => crate::ItemKind::AnonConst,
+        DefKind::Static(_) => crate::ItemKind::Static,
+        DefKind::Ctor(..) => crate::ItemKind::Ctor,
+        _ => return None,
+    })
+}
+
+impl<'tcx> Stable<'tcx> for mir::Body<'tcx> {
+    type T = smir::Body;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::Body {
+            blocks: self.basic_blocks().raw.stable(tcx),
+            locals: self.local_decls.raw.stable(tcx),
+            arg_count: self.arg_count,
+            span: self.span.stable(tcx),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::LocalDecl<'tcx> {
+    type T = smir::LocalDecl;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::LocalDecl {
+            ty: self.ty.stable(tcx),
+            mutability: self.mutability.stable(tcx),
+            span: self.source_info.span.stable(tcx),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BasicBlockData<'tcx> {
+    type T = smir::BasicBlockData;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::BasicBlockData {
+            statements: self.statements.stable(tcx),
+            terminator: self.terminator().stable(tcx),
+            is_cleanup: self.is_cleanup,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Statement<'tcx> {
+    type T = smir::Statement;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::StatementKind::*;
+        let kind = match self.kind {
+            Assign(ref assign) => {
+                let (place, rvalue) = &**assign;
+                smir::StatementKind::Assign(place.stable(tcx), rvalue.stable(tcx))
+            }
+            FakeRead(ref fake_read) => {
+                let (cause, place) = &**fake_read;
+                smir::StatementKind::FakeRead(cause.stable(tcx), place.stable(tcx))
+            }
+            SetDiscriminant { ref place, variant_index } => smir::StatementKind::SetDiscriminant {
+                place: place.stable(tcx),
+                variant_index: variant_index.as_usize(),
+            },
+            Deinit(ref place) => smir::StatementKind::Deinit(place.stable(tcx)),
+            StorageLive(local) => smir::StatementKind::StorageLive(local.as_usize()),
+            StorageDead(local) => smir::StatementKind::StorageDead(local.as_usize()),
+            Retag(kind, ref place) => {
+                smir::StatementKind::Retag(kind.stable(tcx), place.stable(tcx))
+            }
+            AscribeUserType(ref ascription, variance) => {
+                smir::StatementKind::AscribeUserType(ascription.0.stable(tcx), variance.stable(tcx))
+            }
+            Coverage(_) => smir::StatementKind::Coverage,
+            CopyNonOverlapping(ref copy) => smir::StatementKind::CopyNonOverlapping {
+                src: copy.src.stable(tcx),
+                dst: copy.dst.stable(tcx),
+                count: copy.count.stable(tcx),
+            },
+            Nop => smir::StatementKind::Nop,
+        };
+        smir::Statement { kind, span: self.source_info.span.stable(tcx) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::FakeReadCause {
+    type T = smir::FakeReadCause;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::FakeReadCause::*;
+        match *self {
+            ForMatchGuard => smir::FakeReadCause::ForMatchGuard,
+            ForMatchedPlace(def_id) => smir::FakeReadCause::ForMatchedPlace(def_id.stable(tcx)),
+            ForGuardBinding => smir::FakeReadCause::ForGuardBinding,
+            ForLet(def_id) => smir::FakeReadCause::ForLet(def_id.stable(tcx)),
+            ForIndex => smir::FakeReadCause::ForIndex,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::RetagKind {
+    type T = smir::RetagKind;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::RetagKind::FnEntry => smir::RetagKind::FnEntry,
+            mir::RetagKind::TwoPhase => smir::RetagKind::TwoPhase,
+            mir::RetagKind::Raw => smir::RetagKind::Raw,
+            mir::RetagKind::Default => smir::RetagKind::Default,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Variance {
+    type T = smir::Variance;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            ty::Variance::Covariant => smir::Variance::Covariant,
+            ty::Variance::Invariant => smir::Variance::Invariant,
+            ty::Variance::Contravariant => smir::Variance::Contravariant,
+            ty::Variance::Bivariant => smir::Variance::Bivariant,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Terminator<'tcx> {
+    type T = smir::Terminator;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::TerminatorKind::*;
+        let kind = match self.kind {
+            Goto { target } => smir::TerminatorKind::Goto { target: target.as_usize() },
+            SwitchInt { ref discr, switch_ty, ref targets } => smir::TerminatorKind::SwitchInt {
+                discr: discr.stable(tcx),
+                switch_ty: switch_ty.stable(tcx),
+                targets: smir::SwitchTargets {
+                    branches: targets.iter().map(|(value, bb)| (value, bb.as_usize())).collect(),
+                    otherwise: targets.otherwise().as_usize(),
+                },
+            },
+            Resume => smir::TerminatorKind::Resume,
+            Abort => smir::TerminatorKind::Abort,
+            Return => smir::TerminatorKind::Return,
+            Unreachable => smir::TerminatorKind::Unreachable,
+            Drop { ref place, target, unwind } => smir::TerminatorKind::Drop {
+                place: place.stable(tcx),
+                target: target.as_usize(),
+                unwind: unwind.map(|bb| bb.as_usize()),
+            },
+            DropAndReplace { ref place, ref value, target, unwind } => {
+                smir::TerminatorKind::DropAndReplace {
+                    place: place.stable(tcx),
+                    value: value.stable(tcx),
+                    target: target.as_usize(),
+                    unwind: unwind.map(|bb| bb.as_usize()),
+                }
+            }
+            Call { ref func, ref args, ref destination, target, cleanup, .. } => {
+                smir::TerminatorKind::Call {
+                    func: func.stable(tcx),
+                    args: args.stable(tcx),
+                    destination: destination.stable(tcx),
+                    target: target.map(|bb| bb.as_usize()),
+                    cleanup: cleanup.map(|bb| bb.as_usize()),
+                }
+            }
+            Assert { ref cond, expected, ref msg, target, cleanup } => {
+                smir::TerminatorKind::Assert {
+                    cond: cond.stable(tcx),
+                    expected,
+                    msg: msg.stable(tcx),
+                    target: target.as_usize(),
+                    cleanup: cleanup.map(|bb| bb.as_usize()),
+                }
+            }
+            Yield { ref value, resume, ref resume_arg, drop } => smir::TerminatorKind::Yield {
+                value: value.stable(tcx),
+                resume: resume.as_usize(),
+                resume_arg: resume_arg.stable(tcx),
+                drop: drop.map(|bb| bb.as_usize()),
+            },
+            GeneratorDrop => smir::TerminatorKind::GeneratorDrop,
+            FalseEdge { real_target, imaginary_target } => smir::TerminatorKind::FalseEdge {
+                real_target: real_target.as_usize(),
+                imaginary_target: imaginary_target.as_usize(),
+            },
+            FalseUnwind { real_target, unwind } => smir::TerminatorKind::FalseUnwind {
+                real_target: real_target.as_usize(),
+                unwind: unwind.map(|bb| bb.as_usize()),
+            },
+            InlineAsm { template, ref operands, options, destination, cleanup, .. } => {
+                smir::TerminatorKind::InlineAsm {
+                    template: template.iter().map(|piece| format!("{}", piece)).collect(),
+                    operands: operands.stable(tcx),
+                    options: format!("{:?}", options),
+                    destination: destination.map(|bb| bb.as_usize()),
+                    cleanup: cleanup.map(|bb| bb.as_usize()),
+                }
+            }
+        };
+        smir::Terminator { kind, span: self.source_info.span.stable(tcx) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AssertMessage<'tcx> {
+    type T = smir::AssertMessage;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::AssertKind::*;
+        match *self {
+            BoundsCheck { ref len, ref index } => {
+                smir::AssertMessage::BoundsCheck { len: len.stable(tcx), index: index.stable(tcx) }
+            }
+            Overflow(op, ref l, ref r) => {
+                smir::AssertMessage::Overflow(op.stable(tcx), l.stable(tcx), r.stable(tcx))
+            }
+            OverflowNeg(ref op) => smir::AssertMessage::OverflowNeg(op.stable(tcx)),
+            DivisionByZero(ref op) => smir::AssertMessage::DivisionByZero(op.stable(tcx)),
+            RemainderByZero(ref op) => smir::AssertMessage::RemainderByZero(op.stable(tcx)),
+            ResumedAfterReturn(kind) => smir::AssertMessage::ResumedAfterReturn(kind.stable(tcx)),
+            ResumedAfterPanic(kind) => smir::AssertMessage::ResumedAfterPanic(kind.stable(tcx)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::GeneratorKind {
+    type T = smir::GeneratorKind;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            hir::GeneratorKind::Async(kind) => smir::GeneratorKind::Async(match kind {
+                hir::AsyncGeneratorKind::Block => smir::AsyncGeneratorKind::Block,
+                hir::AsyncGeneratorKind::Closure => smir::AsyncGeneratorKind::Closure,
+                hir::AsyncGeneratorKind::Fn => smir::AsyncGeneratorKind::Fn,
+            }),
+            hir::GeneratorKind::Gen => smir::GeneratorKind::Gen,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::InlineAsmOperand<'tcx> {
+    type T = smir::InlineAsmOperand;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::InlineAsmOperand::*;
+        let (in_value, out_place) = match self {
+            In { value, .. } => (Some(value.stable(tcx)), None),
+            Out { place, .. } => (None, place.stable(tcx)),
+            InOut { in_value, out_place, .. } => {
+                (Some(in_value.stable(tcx)), out_place.stable(tcx))
+            }
+            Const { value } | SymFn { value } => {
+                (Some(smir::Operand::Constant(value.stable(tcx))), None)
+            }
+            SymStatic { .. } => (None, None),
+        };
+        smir::InlineAsmOperand { in_value, out_place, raw_rpr: format!("{:?}", self) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Place<'tcx> {
+    type T = smir::Place;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::Place {
+            local: self.local.as_usize(),
+            projection: self.projection.iter().map(|elem| elem.stable(tcx)).collect(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::PlaceElem<'tcx> {
+    type T = smir::ProjectionElem;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::ProjectionElem::*;
+        match *self {
+            Deref => smir::ProjectionElem::Deref,
+            Field(field, ty) => smir::ProjectionElem::Field(field.as_usize(), ty.stable(tcx)),
+            Index(local) => smir::ProjectionElem::Index(local.as_usize()),
+            ConstantIndex { offset, min_length, from_end } => {
+                smir::ProjectionElem::ConstantIndex { offset, min_length, from_end }
+            }
+            Subslice { from, to, from_end } => {
+                smir::ProjectionElem::Subslice { from, to, from_end }
+            }
+            Downcast(name, variant) => smir::ProjectionElem::Downcast(
+                name.map(|name| name.to_string()),
+                variant.as_usize(),
+            ),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Operand<'tcx> {
+    type T = smir::Operand;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::Operand::Copy(ref place) => smir::Operand::Copy(place.stable(tcx)),
+            mir::Operand::Move(ref place) => smir::Operand::Move(place.stable(tcx)),
+            mir::Operand::Constant(ref constant) => smir::Operand::Constant(constant.stable(tcx)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Constant<'tcx> {
+    type T = smir::Constant;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::Constant { literal: self.literal.stable(tcx), span: self.span.stable(tcx) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ConstantKind<'tcx> {
+    type T = sty::Const;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            ConstantKind::Ty(c) => c.stable(tcx),
+            ConstantKind::Val(value, ty) => sty::Const {
+                ty: ty.stable(tcx),
+                kind: const_value_kind(value)
+                    .unwrap_or_else(|| sty::ConstKind::Unsupported(format!("{}", self))),
+            },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Const<'tcx> {
+    type T = sty::Const;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        let kind = match self.val() {
+            ty::ConstKind::Param(param) => {
+                Some(sty::ConstKind::Param { index: param.index, name: param.name.to_string() })
+            }
+            ty::ConstKind::Unevaluated(uv) => Some(sty::ConstKind::Unevaluated {
+                def_id: uv.def.did.stable(tcx),
+                args: uv.substs.stable(tcx),
+                promoted: uv.promoted.map(|promoted| promoted.as_u32()),
+            }),
+            ty::ConstKind::Value(value) => const_value_kind(value),
+            ty::ConstKind::Infer(_)
+            | ty::ConstKind::Bound(..)
+            | ty::ConstKind::Placeholder(_)
+            | ty::ConstKind::Error(_) => None,
+        };
+        sty::Const {
+            ty: self.ty().stable(tcx),
+            kind: kind.unwrap_or_else(|| sty::ConstKind::Unsupported(self.to_string())),
+        }
+    }
+}
+
+fn const_value_kind(value: ConstValue<'_>) -> Option<sty::ConstKind> {
+    match value {
+        ConstValue::Scalar(Scalar::Int(int)) if int.size().bytes() == 0 => {
+            Some(sty::ConstKind::ZeroSized)
+        }
+        ConstValue::Scalar(Scalar::Int(int)) => Some(sty::ConstKind::Scalar {
+            bits: int.assert_bits(int.size()),
+            size: int.size().bytes() as u8,
+        }),
+        ConstValue::Scalar(Scalar::Ptr(..))
+        | ConstValue::Slice { .. }
+        | ConstValue::ByRef { .. } => None,
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Rvalue<'tcx> {
+    type T = smir::Rvalue;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::Rvalue::*;
+        match *self {
+            Use(ref op) => smir::Rvalue::Use(op.stable(tcx)),
+            Repeat(ref op, len) => smir::Rvalue::Repeat(op.stable(tcx), len.stable(tcx)),
+            Ref(region, kind, ref place) => {
+                smir::Rvalue::Ref(region.stable(tcx), kind.stable(tcx), place.stable(tcx))
+            }
+            ThreadLocalRef(def_id) => smir::Rvalue::ThreadLocalRef(def_id.stable(tcx)),
+            AddressOf(mutability, ref place) => {
+                smir::Rvalue::AddressOf(mutability.stable(tcx), place.stable(tcx))
+            }
+            Len(ref place) => smir::Rvalue::Len(place.stable(tcx)),
+            Cast(kind, ref op, ty) => {
+                smir::Rvalue::Cast(kind.stable(tcx), op.stable(tcx), ty.stable(tcx))
+            }
+            BinaryOp(op, ref operands) => {
+                let (l, r) = &**operands;
+                smir::Rvalue::BinaryOp(op.stable(tcx), l.stable(tcx), r.stable(tcx))
+            }
+            CheckedBinaryOp(op, ref operands) => {
+                let (l, r) = &**operands;
+                smir::Rvalue::CheckedBinaryOp(op.stable(tcx), l.stable(tcx), r.stable(tcx))
+            }
+            NullaryOp(op, ty) => smir::Rvalue::NullaryOp(op.stable(tcx), ty.stable(tcx)),
+            UnaryOp(op, ref operand) => smir::Rvalue::UnaryOp(op.stable(tcx), operand.stable(tcx)),
+            Discriminant(ref place) => smir::Rvalue::Discriminant(place.stable(tcx)),
+            Aggregate(ref kind, ref operands) => {
+                smir::Rvalue::Aggregate(kind.stable(tcx), operands.stable(tcx))
+            }
+            ShallowInitBox(ref op, ty) => {
+                smir::Rvalue::ShallowInitBox(op.stable(tcx), ty.stable(tcx))
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BorrowKind {
+    type T = smir::BorrowKind;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::BorrowKind::Shared => smir::BorrowKind::Shared,
+            mir::BorrowKind::Shallow => smir::BorrowKind::Shallow,
+            mir::BorrowKind::Unique => smir::BorrowKind::Unique,
+            mir::BorrowKind::Mut { allow_two_phase_borrow } => {
+                smir::BorrowKind::Mut { allow_two_phase_borrow }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::CastKind {
+    type T = smir::CastKind;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::CastKind::PointerExposeAddress => smir::CastKind::PointerExposeAddress,
+            mir::CastKind::PointerFromExposedAddress => smir::CastKind::PointerFromExposedAddress,
+            mir::CastKind::Pointer(cast) => smir::CastKind::Pointer(match cast {
+                PointerCast::ReifyFnPointer => smir::PointerCast::ReifyFnPointer,
+                PointerCast::UnsafeFnPointer => smir::PointerCast::UnsafeFnPointer,
+                PointerCast::ClosureFnPointer(unsafety) => {
+                    smir::PointerCast::ClosureFnPointer(unsafety.stable(tcx))
+                }
+                PointerCast::MutToConstPointer => smir::PointerCast::MutToConstPointer,
+                PointerCast::ArrayToPointer => smir::PointerCast::ArrayToPointer,
+                PointerCast::Unsize => smir::PointerCast::Unsize,
+            }),
+            mir::CastKind::Misc => smir::CastKind::Misc,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BinOp {
+    type T = smir::BinOp;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::BinOp::Add => smir::BinOp::Add,
+            mir::BinOp::Sub => smir::BinOp::Sub,
+            mir::BinOp::Mul => smir::BinOp::Mul,
+            mir::BinOp::Div => smir::BinOp::Div,
+            mir::BinOp::Rem => smir::BinOp::Rem,
+            mir::BinOp::BitXor => smir::BinOp::BitXor,
+            mir::BinOp::BitAnd => smir::BinOp::BitAnd,
+            mir::BinOp::BitOr => smir::BinOp::BitOr,
+            mir::BinOp::Shl => smir::BinOp::Shl,
+            mir::BinOp::Shr => smir::BinOp::Shr,
+            mir::BinOp::Eq => smir::BinOp::Eq,
+            mir::BinOp::Lt => smir::BinOp::Lt,
+            mir::BinOp::Le => smir::BinOp::Le,
+            mir::BinOp::Ne => smir::BinOp::Ne,
+            mir::BinOp::Ge => smir::BinOp::Ge,
+            mir::BinOp::Gt => smir::BinOp::Gt,
+            mir::BinOp::Offset => smir::BinOp::Offset,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::NullOp {
+    type T = smir::NullOp;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::NullOp::SizeOf => smir::NullOp::SizeOf,
+            mir::NullOp::AlignOf => smir::NullOp::AlignOf,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::UnOp {
+    type T = smir::UnOp;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::UnOp::Not => smir::UnOp::Not,
+            mir::UnOp::Neg => smir::UnOp::Neg,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AggregateKind<'tcx> {
+    type T = smir::AggregateKind;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::AggregateKind::Array(ty) => smir::AggregateKind::Array(ty.stable(tcx)),
+            mir::AggregateKind::Tuple => smir::AggregateKind::Tuple,
+            mir::AggregateKind::Adt(def_id, variant, substs, _, active_field) => {
+                smir::AggregateKind::Adt(
+                    sty::AdtDef(def_id.stable(tcx)),
+                    variant.as_usize(),
+                    substs.stable(tcx),
+                    active_field,
+                )
+            }
+            mir::AggregateKind::Closure(def_id, substs) => {
+                smir::AggregateKind::Closure(def_id.stable(tcx), substs.stable(tcx))
+            }
+            mir::AggregateKind::Generator(def_id, substs, movability) => {
+                smir::AggregateKind::Generator(
+                    def_id.stable(tcx),
+                    substs.stable(tcx),
+                    movability.stable(tcx),
+                )
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Ty<'tcx> {
+    type T = sty::Ty;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        let kind = match *self.kind() {
+            ty::Bool => sty::TyKind::Bool,
+            ty::Char => sty::TyKind::Char,
+            ty::Int(int_ty) => sty::TyKind::Int(match int_ty {
+                ty::IntTy::Isize => sty::IntTy::Isize,
+                ty::IntTy::I8 => sty::IntTy::I8,
+                ty::IntTy::I16 => sty::IntTy::I16,
+                ty::IntTy::I32 => sty::IntTy::I32,
+                ty::IntTy::I64 => sty::IntTy::I64,
+                ty::IntTy::I128 => sty::IntTy::I128,
+            }),
+            ty::Uint(uint_ty) => sty::TyKind::Uint(match uint_ty {
+                ty::UintTy::Usize => sty::UintTy::Usize,
+                ty::UintTy::U8 => sty::UintTy::U8,
+                ty::UintTy::U16 => sty::UintTy::U16,
+                ty::UintTy::U32 => sty::UintTy::U32,
+                ty::UintTy::U64 => sty::UintTy::U64,
+                ty::UintTy::U128 => sty::UintTy::U128,
+            }),
+            ty::Float(float_ty) => sty::TyKind::Float(match float_ty {
+                ty::FloatTy::F32 => sty::FloatTy::F32,
+                ty::FloatTy::F64 => sty::FloatTy::F64,
+            }),
+            ty::Adt(adt_def, substs) => {
+                sty::TyKind::Adt(sty::AdtDef(adt_def.did().stable(tcx)), substs.stable(tcx))
+            }
+            ty::Foreign(def_id) => sty::TyKind::Foreign(def_id.stable(tcx)),
+            ty::Str => sty::TyKind::Str,
+            ty::Array(ty, len) => sty::TyKind::Array(ty.stable(tcx), len.stable(tcx)),
+            ty::Slice(ty) => sty::TyKind::Slice(ty.stable(tcx)),
+            ty::RawPtr(ty::TypeAndMut { ty, mutbl }) => {
+                sty::TyKind::RawPtr(ty.stable(tcx), mutbl.stable(tcx))
+            }
+            ty::Ref(region, ty, mutbl) => {
+                sty::TyKind::Ref(region.stable(tcx), ty.stable(tcx), mutbl.stable(tcx))
+            }
+            ty::FnDef(def_id, substs) => sty::TyKind::FnDef(def_id.stable(tcx), substs.stable(tcx)),
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                sty::TyKind::FnPtr(sty::FnSig {
+                    inputs: sig.inputs().stable(tcx),
+                    output: sig.output().stable(tcx),
+                    c_variadic: sig.c_variadic,
+                    safety: sig.unsafety.stable(tcx),
+                    abi: sig.abi.name().to_owned(),
+                })
+            }
+            ty::Dynamic(predicates, region) => sty::TyKind::Dynamic(
+                predicates.iter().map(|predicate| predicate.skip_binder().stable(tcx)).collect(),
+                region.stable(tcx),
+            ),
+            ty::Closure(def_id, substs) => {
+                sty::TyKind::Closure(def_id.stable(tcx), substs.stable(tcx))
+            }
+            ty::Generator(def_id, substs, movability) => sty::TyKind::Generator(
+                def_id.stable(tcx),
+                substs.stable(tcx),
+                movability.stable(tcx),
+            ),
+            ty::Never => sty::TyKind::Never,
+            ty::Tuple(tys) => sty::TyKind::Tuple(tys.iter().map(|ty| ty.stable(tcx)).collect()),
+            ty::Projection(projection) => sty::TyKind::Projection(
+                projection.item_def_id.stable(tcx),
+                projection.substs.stable(tcx),
+            ),
+            ty::Opaque(def_id, substs) => {
+                sty::TyKind::Opaque(def_id.stable(tcx), substs.stable(tcx))
+            }
+            ty::Param(param) => sty::TyKind::Param(sty::ParamTy {
+                index: param.index,
+                name: param.name.to_string(),
+            }),
+            ty::GeneratorWitness(_)
+            | ty::Bound(..)
+            | ty::Placeholder(_)
+            | ty::Infer(_)
+            | ty::Error(_) => sty::TyKind::Unsupported(self.to_string()),
+        };
+        sty::Ty(Box::new(kind))
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::subst::SubstsRef<'tcx> {
+    type T = sty::GenericArgs;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use ty::subst::GenericArgKind;
+        sty::GenericArgs(
+            self.iter()
+                .map(|arg| match arg.unpack() {
+                    GenericArgKind::Lifetime(region) => {
+                        sty::GenericArgKind::Lifetime(region.stable(tcx))
+                    }
+                    GenericArgKind::Type(ty) => sty::GenericArgKind::Type(ty.stable(tcx)),
+                    GenericArgKind::Const(ct) => sty::GenericArgKind::Const(ct.stable(tcx)),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Region<'tcx> {
+    type T = sty::Region;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self.kind() {
+            ty::ReStatic => sty::Region::Static,
+            ty::ReErased => sty::Region::Erased,
+            ty::ReEarlyBound(region) => {
+                sty::Region::EarlyBound { index: region.index, name: region.name.to_string() }
+            }
+            _ => sty::Region::Unsupported(self.to_string()),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::ExistentialPredicate<'tcx> {
+    type T = sty::ExistentialPredicate;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            ty::ExistentialPredicate::Trait(trait_ref) => sty::ExistentialPredicate::Trait {
+                def_id: trait_ref.def_id.stable(tcx),
+                args: trait_ref.substs.stable(tcx),
+            },
+            ty::ExistentialPredicate::Projection(projection) => {
+                sty::ExistentialPredicate::Projection {
+                    item: projection.item_def_id.stable(tcx),
+                    args: projection.substs.stable(tcx),
+                    term: match projection.term {
+                        ty::Term::Ty(ty) => sty::Term::Type(ty.stable(tcx)),
+                        ty::Term::Const(ct) => sty::Term::Const(ct.stable(tcx)),
+                    },
+                }
+            }
+            ty::ExistentialPredicate::AutoTrait(def_id) => {
+                sty::ExistentialPredicate::AutoTrait(def_id.stable(tcx))
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Mutability {
+    type T = sty::Mutability;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            hir::Mutability::Not => sty::Mutability::Not,
+            hir::Mutability::Mut => sty::Mutability::Mut,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Movability {
+    type T = sty::Movability;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            hir::Movability::Static => sty::Movability::Static,
+            hir::Movability::Movable => sty::Movability::Movable,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Unsafety {
+    type T = sty::Safety;
+
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            hir::Unsafety::Unsafe => sty::Safety::Unsafe,
+            hir::Unsafety::Normal => sty::Safety::Normal,
+        }
+    }
+}
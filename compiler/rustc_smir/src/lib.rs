@@ -2,6 +2,22 @@
 //!
 //! For more information see https://github.com/rust-lang/project-stable-mir
 //!
+//! The [`mir`] and [`ty`] modules contain owned mirrors of the compiler's data
+//! structures, which do not borrow from the compiler and can be serialized with
+//! `rustc_serialize`. A [`Context`] gives access to them while the compiler is
+//! running, the easiest way of getting one is [`run_compiler`].
+//!
+//! # Serialization
+//!
+//! The `Encodable` and `Decodable` implementations of the mirrors are derived,
+//! so their encoding follows the order and shape of the fields and variants
+//! declared here, in whatever format the `rustc_serialize` encoder uses. It is
+//! not stable: any change to these types or to `rustc_serialize` changes it,
+//! without notice. Only decode data encoded by the same build of this crate;
+//! the [`DefId`]s and [`CrateNum`]s in it still only make sense to the session
+//! they came from. It is meant for caching and for sending data to another
+//! process, not as an exchange format for other tools.
+//!
 //! # Note
 //!
 //! This API is still completely unstable and subject to change.
@@ -12,6 +28,80 @@
 )]
 #![cfg_attr(not(feature = "default"), feature(rustc_private))]
 
+// The serialization derives refer to `::rustc_serialize` directly.
+#[cfg(not(feature = "default"))]
+extern crate rustc_macros;
+#[cfg(not(feature = "default"))]
+extern crate rustc_serialize;
+
+use rustc_macros::{Decodable, Encodable};
+
+mod context;
+mod convert;
 pub mod mir;
+pub mod ty;
 
 pub mod very_unstable;
+
+pub use crate::context::{run_compiler, CompilationFailed, Context, StableMirCallbacks};
+
+/// Identifies a crate within one compilation session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encodable, Decodable)]
+pub struct CrateNum(pub(crate) u32);
+
+/// Identifies an item within one compilation session.
+///
+/// The numbering is not stable across sessions, so a `DefId` must not be used
+/// with a [`Context`] other than the one it was obtained from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encodable, Decodable)]
+pub struct DefId {
+    pub(crate) krate: CrateNum,
+    pub(crate) index: u32,
+}
+
+impl DefId {
+    pub fn krate(&self) -> CrateNum {
+        self.krate
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Crate {
+    pub id: CrateNum,
+    pub name: String,
+    pub is_local: bool,
+}
+
+/// An item of the local crate that has a MIR body.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct CrateItem {
+    pub def_id: DefId,
+    /// The path of the item, as rustc prints it in diagnostics.
+    pub name: String,
+    pub kind: ItemKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum ItemKind {
+    Fn,
+    AssocFn,
+    Closure,
+    Generator,
+    Const,
+    AssocConst,
+    AnonConst,
+    Static,
+    /// The constructor of a tuple struct or tuple variant.
+    Ctor,
+}
+
+/// A resolved source location, lines and columns are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Span {
+    pub file: String,
+    pub lo_line: usize,
+    pub lo_col: usize,
+    pub hi_line: usize,
+    pub hi_col: usize,
+}
@@ -1,10 +1,357 @@
-pub use crate::very_unstable::middle::mir::{
-    visit::MutVisitor, AggregateKind, AssertKind, BasicBlock, BasicBlockData, BinOp, BindingForm,
-    BlockTailInfo, Body, BorrowKind, CastKind, ClearCrossCrate, Constant, ConstantKind,
-    CopyNonOverlapping, Coverage, FakeReadCause, Field, GeneratorInfo, ImplicitSelfKind,
-    InlineAsmOperand, Local, LocalDecl, LocalInfo, LocalKind, Location, MirPhase, MirSource,
-    NullOp, Operand, Place, PlaceRef, ProjectionElem, ProjectionKind, Promoted, RetagKind, Rvalue,
-    Safety, SourceInfo, SourceScope, SourceScopeData, SourceScopeLocalData, Statement,
-    StatementKind, UnOp, UserTypeProjection, UserTypeProjections, VarBindingForm, VarDebugInfo,
-    VarDebugInfoContents,
-};
+//! Owned mirrors of the MIR of a single body.
+//!
+//! The shapes follow `rustc_middle::mir` closely, but drop everything that is
+//! only needed by the compiler itself (user type annotations, source scopes,
+//! caches, ...). Locals and basic blocks are plain indices into
+//! `Body::locals` and `Body::blocks` respectively.
+//!
+//! The serialized form of these types is not stable, see the
+//! [crate documentation](crate#serialization).
+
+use crate::ty::{AdtDef, Const, GenericArgs, Movability, Mutability, Region, Safety, Ty};
+use crate::{DefId, Span};
+use rustc_macros::{Decodable, Encodable};
+
+pub type Local = usize;
+
+/// The local holding the return value of a body, arguments follow it.
+pub const RETURN_PLACE: Local = 0;
+
+pub type BasicBlock = usize;
+
+/// The basic block every body starts executing at.
+pub const START_BLOCK: BasicBlock = 0;
+
+pub type FieldIdx = usize;
+
+pub type VariantIdx = usize;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Body {
+    pub blocks: Vec<BasicBlockData>,
+    /// The return place first, then `arg_count` arguments, then all other
+    /// user variables and temporaries.
+    pub locals: Vec<LocalDecl>,
+    pub arg_count: usize,
+    pub span: Span,
+}
+
+impl Body {
+    /// Returns the locals holding the arguments of the body.
+    pub fn arg_locals(&self) -> &[LocalDecl] {
+        &self.locals[1..][..self.arg_count]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct LocalDecl {
+    pub ty: Ty,
+    pub mutability: Mutability,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct BasicBlockData {
+    pub statements: Vec<Statement>,
+    pub terminator: Terminator,
+    pub is_cleanup: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum StatementKind {
+    Assign(Place, Rvalue),
+    FakeRead(FakeReadCause, Place),
+    SetDiscriminant { place: Place, variant_index: VariantIdx },
+    Deinit(Place),
+    StorageLive(Local),
+    StorageDead(Local),
+    Retag(RetagKind, Place),
+    AscribeUserType(Place, Variance),
+    Coverage,
+    CopyNonOverlapping { src: Operand, dst: Operand, count: Operand },
+    Nop,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum FakeReadCause {
+    ForMatchGuard,
+    ForMatchedPlace(Option<DefId>),
+    ForGuardBinding,
+    ForLet(Option<DefId>),
+    ForIndex,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum RetagKind {
+    FnEntry,
+    TwoPhase,
+    Raw,
+    Default,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Variance {
+    Covariant,
+    Invariant,
+    Contravariant,
+    Bivariant,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Terminator {
+    pub kind: TerminatorKind,
+    pub span: Span,
+}
+
+impl Terminator {
+    /// Returns the blocks control may continue at after this terminator.
+    pub fn successors(&self) -> Vec<BasicBlock> {
+        use self::TerminatorKind::*;
+        match self.kind {
+            Goto { target } => vec![target],
+            SwitchInt { ref targets, .. } => {
+                targets.branches.iter().map(|&(_, bb)| bb).chain(Some(targets.otherwise)).collect()
+            }
+            Resume | Abort | Return | Unreachable | GeneratorDrop => vec![],
+            Drop { target, unwind, .. } | DropAndReplace { target, unwind, .. } => {
+                Some(target).into_iter().chain(unwind).collect()
+            }
+            Call { target, cleanup, .. } => target.into_iter().chain(cleanup).collect(),
+            Assert { target, cleanup, .. } => Some(target).into_iter().chain(cleanup).collect(),
+            Yield { resume, drop, .. } => Some(resume).into_iter().chain(drop).collect(),
+            FalseEdge { real_target, imaginary_target } => vec![real_target, imaginary_target],
+            FalseUnwind { real_target, unwind } => {
+                Some(real_target).into_iter().chain(unwind).collect()
+            }
+            InlineAsm { destination, cleanup, .. } => {
+                destination.into_iter().chain(cleanup).collect()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum TerminatorKind {
+    Goto {
+        target: BasicBlock,
+    },
+    SwitchInt {
+        discr: Operand,
+        switch_ty: Ty,
+        targets: SwitchTargets,
+    },
+    Resume,
+    Abort,
+    Return,
+    Unreachable,
+    Drop {
+        place: Place,
+        target: BasicBlock,
+        unwind: Option<BasicBlock>,
+    },
+    DropAndReplace {
+        place: Place,
+        value: Operand,
+        target: BasicBlock,
+        unwind: Option<BasicBlock>,
+    },
+    Call {
+        func: Operand,
+        args: Vec<Operand>,
+        destination: Place,
+        target: Option<BasicBlock>,
+        cleanup: Option<BasicBlock>,
+    },
+    Assert {
+        cond: Operand,
+        expected: bool,
+        msg: AssertMessage,
+        target: BasicBlock,
+        cleanup: Option<BasicBlock>,
+    },
+    Yield {
+        value: Operand,
+        resume: BasicBlock,
+        resume_arg: Place,
+        drop: Option<BasicBlock>,
+    },
+    GeneratorDrop,
+    FalseEdge {
+        real_target: BasicBlock,
+        imaginary_target: BasicBlock,
+    },
+    FalseUnwind {
+        real_target: BasicBlock,
+        unwind: Option<BasicBlock>,
+    },
+    InlineAsm {
+        template: String,
+        operands: Vec<InlineAsmOperand>,
+        options: String,
+        destination: Option<BasicBlock>,
+        cleanup: Option<BasicBlock>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct SwitchTargets {
+    /// The value of the discriminant and the block to jump to for it.
+    pub branches: Vec<(u128, BasicBlock)>,
+    /// The block to jump to if no branch matches.
+    pub otherwise: BasicBlock,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum AssertMessage {
+    BoundsCheck { len: Operand, index: Operand },
+    Overflow(BinOp, Operand, Operand),
+    OverflowNeg(Operand),
+    DivisionByZero(Operand),
+    RemainderByZero(Operand),
+    ResumedAfterReturn(GeneratorKind),
+    ResumedAfterPanic(GeneratorKind),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum GeneratorKind {
+    Async(AsyncGeneratorKind),
+    Gen,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum AsyncGeneratorKind {
+    Block,
+    Closure,
+    Fn,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct InlineAsmOperand {
+    pub in_value: Option<Operand>,
+    pub out_place: Option<Place>,
+    /// The operand as it is printed in MIR dumps. Register classes and the
+    /// operand kind have no stable representation yet.
+    pub raw_rpr: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Place {
+    pub local: Local,
+    pub projection: Vec<ProjectionElem>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum ProjectionElem {
+    Deref,
+    Field(FieldIdx, Ty),
+    Index(Local),
+    ConstantIndex { offset: u64, min_length: u64, from_end: bool },
+    Subslice { from: u64, to: u64, from_end: bool },
+    Downcast(Option<String>, VariantIdx),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Operand {
+    Copy(Place),
+    Move(Place),
+    Constant(Constant),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Constant {
+    pub literal: Const,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Rvalue {
+    Use(Operand),
+    Repeat(Operand, Const),
+    Ref(Region, BorrowKind, Place),
+    ThreadLocalRef(DefId),
+    AddressOf(Mutability, Place),
+    Len(Place),
+    Cast(CastKind, Operand, Ty),
+    BinaryOp(BinOp, Operand, Operand),
+    CheckedBinaryOp(BinOp, Operand, Operand),
+    NullaryOp(NullOp, Ty),
+    UnaryOp(UnOp, Operand),
+    Discriminant(Place),
+    Aggregate(AggregateKind, Vec<Operand>),
+    ShallowInitBox(Operand, Ty),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum BorrowKind {
+    Shared,
+    Shallow,
+    Unique,
+    Mut { allow_two_phase_borrow: bool },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum CastKind {
+    PointerExposeAddress,
+    PointerFromExposedAddress,
+    Pointer(PointerCast),
+    Misc,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum PointerCast {
+    ReifyFnPointer,
+    UnsafeFnPointer,
+    ClosureFnPointer(Safety),
+    MutToConstPointer,
+    ArrayToPointer,
+    Unsize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitXor,
+    BitAnd,
+    BitOr,
+    Shl,
+    Shr,
+    Eq,
+    Lt,
+    Le,
+    Ne,
+    Ge,
+    Gt,
+    Offset,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum NullOp {
+    SizeOf,
+    AlignOf,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum UnOp {
+    Not,
+    Neg,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum AggregateKind {
+    Array(Ty),
+    Tuple,
+    /// The last field is the active field of a union.
+    Adt(AdtDef, VariantIdx, GenericArgs, Option<FieldIdx>),
+    Closure(DefId, GenericArgs),
+    Generator(DefId, GenericArgs, Movability),
+}
@@ -0,0 +1,177 @@
+//! Owned mirrors of the rustc type representation.
+//!
+//! Types are converted eagerly into plain trees, so they can be kept around,
+//! compared and serialized after the compiler session that produced them has
+//! been torn down. Anything that only makes sense during type checking (inference
+//! variables, bound variables, placeholders, ...) is kept as its printed form.
+//!
+//! See the [crate documentation](crate#serialization) for what the serialized
+//! form can be relied on for.
+
+use crate::DefId;
+use rustc_macros::{Decodable, Encodable};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Ty(pub Box<TyKind>);
+
+impl Ty {
+    pub fn kind(&self) -> &TyKind {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum TyKind {
+    Bool,
+    Char,
+    Int(IntTy),
+    Uint(UintTy),
+    Float(FloatTy),
+    Adt(AdtDef, GenericArgs),
+    Foreign(DefId),
+    Str,
+    Array(Ty, Const),
+    Slice(Ty),
+    RawPtr(Ty, Mutability),
+    Ref(Region, Ty, Mutability),
+    FnDef(DefId, GenericArgs),
+    FnPtr(FnSig),
+    Dynamic(Vec<ExistentialPredicate>, Region),
+    Closure(DefId, GenericArgs),
+    Generator(DefId, GenericArgs, Movability),
+    Never,
+    Tuple(Vec<Ty>),
+    Projection(DefId, GenericArgs),
+    Opaque(DefId, GenericArgs),
+    Param(ParamTy),
+    /// A type that has no stable counterpart, like inference variables or
+    /// generator witnesses, in the form rustc would print it.
+    Unsupported(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum IntTy {
+    Isize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum UintTy {
+    Usize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Mutability {
+    Not,
+    Mut,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Movability {
+    Static,
+    Movable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Safety {
+    Unsafe,
+    Normal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct AdtDef(pub DefId);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct GenericArgs(pub Vec<GenericArgKind>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum GenericArgKind {
+    Lifetime(Region),
+    Type(Ty),
+    Const(Const),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Region {
+    Static,
+    /// Regions are erased in all MIR that has gone through borrowck.
+    Erased,
+    EarlyBound {
+        index: u32,
+        name: String,
+    },
+    Unsupported(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct ParamTy {
+    pub index: u32,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct FnSig {
+    pub inputs: Vec<Ty>,
+    pub output: Ty,
+    pub c_variadic: bool,
+    pub safety: Safety,
+    pub abi: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum ExistentialPredicate {
+    Trait { def_id: DefId, args: GenericArgs },
+    Projection { item: DefId, args: GenericArgs, term: Term },
+    AutoTrait(DefId),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Term {
+    Type(Ty),
+    Const(Const),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Const {
+    pub ty: Ty,
+    pub kind: ConstKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum ConstKind {
+    /// An integer-like value (integers, `bool`, `char`, raw addresses of
+    /// known value), `size` is in bytes.
+    Scalar {
+        bits: u128,
+        size: u8,
+    },
+    /// A value of a type without any data, like `()` or a function item.
+    ZeroSized,
+    Param {
+        index: u32,
+        name: String,
+    },
+    Unevaluated {
+        def_id: DefId,
+        args: GenericArgs,
+        promoted: Option<u32>,
+    },
+    /// Any other value, like a pointer into an allocation, in the form rustc
+    /// would print it.
+    Unsupported(String),
+}
@@ -528,6 +528,7 @@
         cosf32,
         cosf64,
         count,
+        coverage,
         cr,
         crate_id,
         crate_in_paths,
@@ -595,11 +596,13 @@
         doc_auto_cfg,
         doc_cfg,
         doc_cfg_hide,
+        doc_coverage,
         doc_keyword,
         doc_masked,
         doc_notable_trait,
         doc_primitive,
         doc_spotlight,
+        docs,
         doctest,
         document_private_items,
         dotdot: "..",
@@ -637,6 +640,7 @@
         eq,
         ermsb_target_feature,
         exact_div,
+        examples,
         except,
         exchange_malloc,
         exclusive_range_pattern,
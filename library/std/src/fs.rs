@@ -590,6 +590,218 @@ pub fn try_clone(&self) -> io::Result<File> {
     pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
         self.inner.set_permissions(perm.0)
     }
+
+    /// Acquires a shared lock on the file, blocking until the lock can be acquired.
+    ///
+    /// Any number of files may hold a shared lock on the same underlying file at
+    /// the same time, but not while an exclusive lock is held on it. The lock is
+    /// released by [`unlock`], or when the file is closed.
+    ///
+    /// If this file already holds a lock, whether it is replaced by a shared lock
+    /// or whether this deadlocks is platform-specific, see below.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with
+    /// the `LOCK_SH` flag, and the `LockFileEx` function on Windows. Note that,
+    /// this [may change in the future][changes].
+    ///
+    /// On Unix, the lock is advisory: it only excludes the other locks, not
+    /// reads and writes. It belongs to the open file description rather than to
+    /// the file descriptor, so it is shared with the duplicates of this file,
+    /// whether they are created with [`try_clone`], `dup`, or inherited by a
+    /// child process through `fork` or [`Command`]. Locking or unlocking any of
+    /// the duplicates replaces or releases the lock for all of them: a child
+    /// process which unlocks an inherited file also releases the lock of its
+    /// parent. Closing a duplicate does not release the lock, which is only
+    /// released once all of them are closed. A file opened separately, even in
+    /// the same process, has its own open file description and its own lock, so
+    /// it is excluded by this one. If the file holds an exclusive lock, it is
+    /// converted into a shared lock, though not atomically.
+    ///
+    /// On Windows, the lock is mandatory: while it is held, the other handles
+    /// to the file can't write to it. It belongs to the handle, so the handles
+    /// created with [`try_clone`] or inherited by a child process don't hold it.
+    /// If the handle holds an exclusive lock, this acquires a shared lock in
+    /// addition to it, and [`unlock`] releases both.
+    ///
+    /// [changes]: io#platform-specific-behavior
+    /// [`unlock`]: File::unlock
+    /// [`try_clone`]: File::try_clone
+    /// [`Command`]: crate::process::Command
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error of kind [`Unsupported`] on the platforms
+    /// which don't support file locking. It may also return an error in other
+    /// os-specific unspecified cases.
+    ///
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.txt")?;
+    ///     f.lock_shared()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock_shared()
+    }
+
+    /// Acquires an exclusive lock on the file, blocking until the lock can be
+    /// acquired.
+    ///
+    /// No other file may hold a lock on the same underlying file while an
+    /// exclusive lock is held on it. The lock is released by [`unlock`], or when
+    /// the file is closed.
+    ///
+    /// If this file already holds a lock, whether it is replaced by an exclusive
+    /// lock or whether this deadlocks is platform-specific, see below.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with
+    /// the `LOCK_EX` flag, and the `LockFileEx` function on Windows with the
+    /// `LOCKFILE_EXCLUSIVE_LOCK` flag. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// On Unix, the lock is shared with the duplicates of this file, including
+    /// those created with `dup` or inherited through `fork`: it is released when
+    /// any of them is unlocked, or once all of them are closed. On Windows, it
+    /// belongs to this handle only. See [`lock_shared`] for the details.
+    ///
+    /// If the file holds a shared lock, it is converted into an exclusive lock
+    /// on Unix, though not atomically, while this deadlocks on Windows.
+    ///
+    /// [changes]: io#platform-specific-behavior
+    /// [`unlock`]: File::unlock
+    /// [`lock_shared`]: File::lock_shared
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error of kind [`Unsupported`] on the platforms
+    /// which don't support file locking. It may also return an error in other
+    /// os-specific unspecified cases.
+    ///
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.txt")?;
+    ///     f.lock_exclusive()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.inner.lock_exclusive()
+    }
+
+    /// Tries to acquire a shared lock on the file, without blocking.
+    ///
+    /// Returns `Ok(false)` if an exclusive lock is held on the file, and
+    /// `Ok(true)` once the lock is acquired. See [`lock_shared`] for the
+    /// semantics of the lock and the platform-specific behavior.
+    ///
+    /// [`lock_shared`]: File::lock_shared
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.txt")?;
+    ///     if !f.try_lock_shared()? {
+    ///         eprintln!("foo.txt is being written, waiting for it");
+    ///         f.lock_shared()?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.inner.try_lock_shared()
+    }
+
+    /// Tries to acquire an exclusive lock on the file, without blocking.
+    ///
+    /// Returns `Ok(false)` if another lock is held on the file, and `Ok(true)`
+    /// once the lock is acquired. See [`lock_exclusive`] for the semantics of
+    /// the lock and the platform-specific behavior.
+    ///
+    /// [`lock_exclusive`]: File::lock_exclusive
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.txt")?;
+    ///     if !f.try_lock_exclusive()? {
+    ///         eprintln!("foo.txt is already in use");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        self.inner.try_lock_exclusive()
+    }
+
+    /// Releases the lock held on the file.
+    ///
+    /// Closing the file also releases its lock, so there is no need to call
+    /// this function before dropping the file. Unlocking a file which doesn't
+    /// hold a lock does nothing.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with
+    /// the `LOCK_UN` flag, and the `UnlockFile` function on Windows. Note that,
+    /// this [may change in the future][changes].
+    ///
+    /// On Unix, this also releases the lock for the duplicates of this file,
+    /// including those held by the child processes which inherited it, see
+    /// [`lock_shared`] for the details.
+    ///
+    /// [changes]: io#platform-specific-behavior
+    /// [`lock_shared`]: File::lock_shared
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::Write;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let mut f = File::create("foo.txt")?;
+    ///     f.lock_exclusive()?;
+    ///     f.write_all(b"Hello, world!")?;
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 // In addition to the `impl`s here, `File` also has `impl`s for
@@ -1233,6 +1233,85 @@ fn file_try_clone() {
     check!(f1.write_all(b"!"));
 }
 
+#[cfg(any(
+    windows,
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "solaris",
+))]
+mod file_lock {
+    use super::*;
+
+    #[test]
+    fn file_lock_multiple_shared() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("file");
+        let f1 = check!(File::create(&path));
+        let f2 = check!(File::open(&path));
+
+        check!(f1.lock_shared());
+        assert!(check!(f2.try_lock_shared()));
+        assert!(!check!(f2.try_lock_exclusive()));
+        check!(f1.unlock());
+        check!(f2.unlock());
+    }
+
+    #[test]
+    fn file_lock_exclusive() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("file");
+        let f1 = check!(File::create(&path));
+        let f2 = check!(File::open(&path));
+
+        check!(f1.lock_exclusive());
+        assert!(!check!(f2.try_lock_shared()));
+        assert!(!check!(f2.try_lock_exclusive()));
+        check!(f1.unlock());
+        assert!(check!(f2.try_lock_exclusive()));
+        assert!(!check!(f1.try_lock_shared()));
+        check!(f2.unlock());
+    }
+
+    #[test]
+    fn file_lock_released_on_drop() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("file");
+        let f1 = check!(File::create(&path));
+        let f2 = check!(File::open(&path));
+
+        check!(f1.lock_exclusive());
+        drop(f1);
+        assert!(check!(f2.try_lock_exclusive()));
+        check!(f2.unlock());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn file_lock_shared_with_duplicates() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("file");
+        let f1 = check!(File::create(&path));
+        let f2 = check!(f1.try_clone());
+        let f3 = check!(File::open(&path));
+
+        // The lock belongs to the open file description, which the clone shares.
+        check!(f1.lock_exclusive());
+        assert!(check!(f2.try_lock_exclusive()));
+        drop(f1);
+        assert!(!check!(f3.try_lock_shared()));
+        check!(f2.unlock());
+        assert!(check!(f3.try_lock_shared()));
+    }
+}
+
 #[test]
 #[cfg(not(windows))]
 fn unlink_readonly() {
@@ -38,6 +38,11 @@
 ///
 /// // The file descriptor will be closed when `pidfd` is dropped.
 /// ```
+///
+/// A `PidFd` becomes readable once the process has exited, so it can be
+/// polled alongside other file descriptors through [`AsFd`], after which the
+/// exit status can be collected with [`try_wait`].
+///
 /// Refer to the man page of [`pidfd_open(2)`] for further details.
 ///
 /// [`Command`]: process::Command
@@ -45,6 +50,7 @@
 /// [`Child`]: process::Child
 /// [`pidfd`]: fn@ChildExt::pidfd
 /// [`take_pidfd`]: ChildExt::take_pidfd
+/// [`try_wait`]: process::Child::try_wait
 /// [`pidfd_open(2)`]: https://man7.org/linux/man-pages/man2/pidfd_open.2.html
 #[derive(Debug)]
 pub struct PidFd {
@@ -134,6 +140,21 @@ pub trait ChildExt: Sealed {
     /// [`create_pidfd`]: CommandExt::create_pidfd
     /// [`Child`]: process::Child
     fn take_pidfd(&mut self) -> Result<PidFd>;
+
+    /// Sends the signal `signal` to the child.
+    ///
+    /// If the child has a pidfd, this uses [`pidfd_send_signal(2)`], so the
+    /// signal can never reach an unrelated process that reused the child's
+    /// pid. Otherwise, or if that system call fails for a reason unrelated to
+    /// the child (e.g. it is missing or blocked by seccomp), this falls back to
+    /// [`kill(2)`]. [`Child::kill`] goes through the same path.
+    ///
+    /// This returns an error if the child has already been reaped.
+    ///
+    /// [`Child::kill`]: process::Child::kill
+    /// [`pidfd_send_signal(2)`]: https://man7.org/linux/man-pages/man2/pidfd_send_signal.2.html
+    /// [`kill(2)`]: https://man7.org/linux/man-pages/man2/kill.2.html
+    fn send_signal(&self, signal: i32) -> Result<()>;
 }
 
 /// Os-specific extensions for [`Command`]
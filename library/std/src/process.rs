@@ -58,6 +58,9 @@
 //! assert_eq!(b"Oh no, a typo!\n", output.stdout.as_slice());
 //! ```
 //!
+//! Longer chains of commands can be built with [`Pipeline`], which also waits
+//! on all of them and reports each of their exit statuses.
+//!
 //! Note that [`ChildStderr`] and [`ChildStdout`] implement [`Read`] and
 //! [`ChildStdin`] implements [`Write`]:
 //!
@@ -119,6 +122,7 @@
 #[stable(feature = "command_access", since = "1.57.0")]
 pub use crate::sys_common::process::CommandEnvs;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
+use crate::time::Duration;
 
 /// Representation of a running or exited child process.
 ///
@@ -1919,6 +1923,44 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         Ok(self.handle.try_wait()?.map(ExitStatus))
     }
 
+    /// Waits for the child to exit for at most `timeout`, returning `Ok(None)`
+    /// if it is still running after that.
+    ///
+    /// This is like [`try_wait`] with a timeout, and returns the exit status
+    /// straight away if the child has already exited. Note that unlike
+    /// [`wait`], this function will not attempt to drop stdin.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Linux, this waits on the child's pidfd, or on one opened for the
+    /// duration of the call, and on Windows, this uses `WaitForSingleObject`.
+    /// On versions of Linux without pidfd support and on the other Unix
+    /// platforms, this polls [`try_wait`] with a growing delay. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [`try_wait`]: Child::try_wait
+    /// [`wait`]: Child::wait
+    /// [changes]: io#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_wait_timeout)]
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    ///
+    /// if child.wait_timeout(Duration::from_secs(1)).unwrap().is_none() {
+    ///     child.kill().unwrap();
+    ///     child.wait().unwrap();
+    /// }
+    /// ```
+    #[unstable(feature = "child_wait_timeout", issue = "none")]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        Ok(self.handle.wait_timeout(timeout)?.map(ExitStatus))
+    }
+
     /// Simultaneously waits for the child to exit and collect all remaining
     /// output on the stdout/stderr handles, returning an `Output`
     /// instance.
@@ -1977,6 +2019,321 @@ pub fn wait_with_output(mut self) -> io::Result<Output> {
     }
 }
 
+/// A builder for a pipeline of processes, where the standard output of each
+/// command is connected to the standard input of the next one, as in the
+/// shell's `a | b | c`.
+///
+/// The standard input of the first command and the standard output of the
+/// last one are configured on those [`Command`]s as usual. The standard input
+/// of every other command and the standard output of every command but the
+/// last are replaced by pipes whenever the pipeline is spawned, without
+/// changing the configuration of the commands themselves.
+///
+/// On Unix, the commands can also be placed in a process group of their own
+/// with [`process_group`].
+///
+/// [`process_group`]: Pipeline::process_group
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(process_pipeline)]
+/// use std::process::{Command, Pipeline};
+///
+/// let output = Pipeline::new()
+///     .pipe(Command::new("ls"))
+///     .pipe(Command::new("sort").arg("-r"))
+///     .pipe(Command::new("head").arg("-n").arg("3"))
+///     .output()
+///     .expect("failed to execute pipeline");
+///
+/// assert!(output.status.success());
+/// println!("{}", String::from_utf8_lossy(&output.stdout));
+/// ```
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(Debug, Default)]
+pub struct Pipeline {
+    commands: Vec<Command>,
+    #[cfg_attr(not(unix), allow(dead_code))]
+    process_group: bool,
+}
+
+impl Pipeline {
+    /// Constructs a new, empty `Pipeline`.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn new() -> Pipeline {
+        Pipeline { commands: Vec::new(), process_group: false }
+    }
+
+    /// Appends a command to the end of the pipeline.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn pipe(&mut self, command: Command) -> &mut Pipeline {
+        self.commands.push(command);
+        self
+    }
+
+    /// Sets whether the commands are placed in a new process group, led by the
+    /// first command, so that they can be signaled together (e.g. with
+    /// `killpg`).
+    ///
+    /// By default, the commands stay in the process group of the parent. A new
+    /// group is not the foreground process group of the controlling terminal,
+    /// so its commands are stopped if they try to read from the terminal.
+    ///
+    /// This only has an effect on Unix.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn process_group(&mut self, new_group: bool) -> &mut Pipeline {
+        self.process_group = new_group;
+        self
+    }
+
+    /// Returns the commands of the pipeline, in order.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn get_commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Spawns all commands of the pipeline, returning a handle to them.
+    ///
+    /// If one of the commands fails to spawn, those already spawned are killed
+    /// and waited on before the error is returned. An empty pipeline results
+    /// in an [`InvalidInput`] error.
+    ///
+    /// [`InvalidInput`]: io::ErrorKind::InvalidInput
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn spawn(&mut self) -> io::Result<PipelineChild> {
+        self.spawn_inner(false)
+    }
+
+    /// Executes the pipeline, waiting for all of its commands to finish and
+    /// collecting their statuses.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn status(&mut self) -> io::Result<PipelineStatus> {
+        self.spawn()?.wait()
+    }
+
+    /// Executes the pipeline, waiting for all of its commands to finish and
+    /// collecting the standard output of the last one.
+    ///
+    /// The standard output of the last command is captured through a pipe;
+    /// other streams are left as configured.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn output(&mut self) -> io::Result<PipelineOutput> {
+        self.spawn_inner(true)?.wait_with_output()
+    }
+
+    fn spawn_inner(&mut self, capture_stdout: bool) -> io::Result<PipelineChild> {
+        if self.commands.is_empty() {
+            return Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "cannot spawn an empty pipeline",
+            ));
+        }
+
+        let last = self.commands.len() - 1;
+        #[cfg(unix)]
+        let new_group = self.process_group;
+        let mut children: Vec<Child> = Vec::with_capacity(self.commands.len());
+        for (i, command) in self.commands.iter_mut().enumerate() {
+            // The pipes and the process group only apply to this spawn, so
+            // the previous configuration of the command is restored after it.
+            let saved_stdin = children.last_mut().map(|prev| {
+                let stdout = prev.stdout.take().expect("previous command has piped stdout");
+                command.inner.replace_stdin(Some(Stdio::from(stdout).0))
+            });
+            let saved_stdout = (i < last || capture_stdout)
+                .then(|| command.inner.replace_stdout(Some(Stdio::piped().0)));
+            #[cfg(unix)]
+            let saved_pgroup = new_group.then(|| {
+                let pgroup = children.first().map_or(0, |leader| leader.id() as i32);
+                command.inner.replace_pgroup(Some(pgroup))
+            });
+
+            let res = command.spawn();
+
+            // Restoring stdin also drops our end of the pipe, so that the
+            // previous command gets `EPIPE` once this one exits.
+            if let Some(stdin) = saved_stdin {
+                command.inner.replace_stdin(stdin);
+            }
+            if let Some(stdout) = saved_stdout {
+                command.inner.replace_stdout(stdout);
+            }
+            #[cfg(unix)]
+            if let Some(pgroup) = saved_pgroup {
+                command.inner.replace_pgroup(pgroup);
+            }
+
+            match res {
+                Ok(child) => children.push(child),
+                Err(e) => {
+                    for mut child in children {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        let stdin = children[0].stdin.take();
+        let stdout = children[last].stdout.take();
+        Ok(PipelineChild { children, stdin, stdout })
+    }
+}
+
+/// Representation of a running or exited pipeline of processes.
+///
+/// This is returned by [`Pipeline::spawn`]. Like [`Child`], it does not wait
+/// on its processes when dropped.
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(Debug)]
+pub struct PipelineChild {
+    children: Vec<Child>,
+
+    /// The handle for writing to the first command's standard input, if it
+    /// has been captured.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stdin: Option<ChildStdin>,
+
+    /// The handle for reading from the last command's standard output, if it
+    /// has been captured.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stdout: Option<ChildStdout>,
+}
+
+impl PipelineChild {
+    /// Returns the OS-assigned process identifier of the first command.
+    ///
+    /// With [`Pipeline::process_group`], this is also the identifier of the
+    /// pipeline's process group.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn id(&self) -> u32 {
+        self.children[0].id()
+    }
+
+    /// Returns the child processes of the pipeline, in order.
+    ///
+    /// Their standard input and output handles have been moved into the
+    /// pipes, or into this `PipelineChild`.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn children(&mut self) -> &mut [Child] {
+        &mut self.children
+    }
+
+    /// Forces all processes of the pipeline to exit.
+    ///
+    /// Processes that have already exited are skipped. Every other process is
+    /// killed even if killing an earlier one fails, in which case the first
+    /// error is returned.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn kill(&mut self) -> io::Result<()> {
+        let mut res = Ok(());
+        for child in &mut self.children {
+            let res_child = match child.try_wait() {
+                Ok(Some(_)) => Ok(()),
+                Ok(None) => child.kill(),
+                Err(e) => Err(e),
+            };
+            res = res.and(res_child);
+        }
+        res
+    }
+
+    /// Waits for all processes of the pipeline to exit completely, returning
+    /// their statuses.
+    ///
+    /// The stdin handle of the pipeline, if any, is closed before waiting.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn wait(&mut self) -> io::Result<PipelineStatus> {
+        drop(self.stdin.take());
+        let statuses = self.children.iter_mut().map(Child::wait).collect::<io::Result<_>>()?;
+        Ok(PipelineStatus { statuses })
+    }
+
+    /// Attempts to collect the statuses of the pipeline's processes, without
+    /// blocking. Returns `Ok(None)` if any of them is still running.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn try_wait(&mut self) -> io::Result<Option<PipelineStatus>> {
+        let mut statuses = Vec::with_capacity(self.children.len());
+        for child in &mut self.children {
+            match child.try_wait()? {
+                Some(status) => statuses.push(status),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(PipelineStatus { statuses }))
+    }
+
+    /// Simultaneously waits for all processes to exit and collects the
+    /// remaining output of the last command.
+    ///
+    /// The stdin handle of the pipeline, if any, is closed before waiting.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn wait_with_output(mut self) -> io::Result<PipelineOutput> {
+        drop(self.stdin.take());
+
+        let mut stdout = Vec::new();
+        if let Some(mut out) = self.stdout.take() {
+            out.read_to_end(&mut stdout)?;
+        }
+
+        let status = self.wait()?;
+        Ok(PipelineOutput { status, stdout })
+    }
+}
+
+/// The statuses of a finished pipeline, one per command.
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PipelineStatus {
+    statuses: Vec<ExitStatus>,
+}
+
+impl PipelineStatus {
+    /// Was termination successful for every command of the pipeline?
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn success(&self) -> bool {
+        self.statuses.iter().all(ExitStatus::success)
+    }
+
+    /// Returns the overall status of the pipeline, like the shell's
+    /// `pipefail` option: the status of the last command that did not
+    /// succeed, or that of the last command if all of them succeeded.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn pipefail(&self) -> ExitStatus {
+        self.statuses.iter().rev().find(|s| !s.success()).copied().unwrap_or_else(|| self.last())
+    }
+
+    /// Returns the status of the last command, which is what the shell
+    /// reports for a pipeline by default.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn last(&self) -> ExitStatus {
+        *self.statuses.last().unwrap()
+    }
+
+    /// Returns the statuses of all commands, in order.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn statuses(&self) -> &[ExitStatus] {
+        &self.statuses
+    }
+}
+
+/// The output of a finished pipeline.
+///
+/// This is returned by either [`Pipeline::output`] or
+/// [`PipelineChild::wait_with_output`].
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PipelineOutput {
+    /// The statuses of the pipeline's commands.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub status: PipelineStatus,
+    /// The data that the last command wrote to stdout.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stdout: Vec<u8>,
+}
+
 /// Terminates the current process with the specified exit code.
 ///
 /// This function will never return and will immediately terminate the current
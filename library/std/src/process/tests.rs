@@ -198,6 +198,32 @@ fn test_finish_twice() {
     assert!(prog.wait().unwrap().code() == Some(1));
 }
 
+#[test]
+#[cfg_attr(any(target_os = "vxworks"), ignore)]
+fn test_wait_timeout() {
+    use crate::time::Duration;
+
+    // The child exits once its stdin is closed, which also happens when the
+    // test fails.
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(&["/C", "more"]);
+        cmd
+    } else {
+        let mut cmd = shell_cmd();
+        cmd.arg("-c").arg("cat");
+        cmd
+    };
+    let mut child = cmd.stdin(Stdio::piped()).stdout(Stdio::null()).spawn().unwrap();
+    assert_eq!(child.wait_timeout(Duration::from_millis(10)).unwrap(), None);
+
+    drop(child.stdin.take());
+    let status = child.wait_timeout(Duration::from_secs(60)).unwrap();
+    assert!(status.map_or(false, |status| status.success()));
+    // Once reaped, the status is returned straight away.
+    assert_eq!(child.wait_timeout(Duration::ZERO).unwrap(), status);
+}
+
 #[test]
 #[cfg_attr(any(target_os = "vxworks"), ignore)]
 fn test_wait_with_output_once() {
@@ -456,3 +482,97 @@ fn run_canonical_bat_script() {
     assert!(output.status.success());
     assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "Hello, fellow Rustaceans!");
 }
+
+#[test]
+#[cfg_attr(any(windows, target_os = "vxworks"), ignore)]
+fn test_pipeline_output() {
+    use super::Pipeline;
+
+    let mut upper = shell_cmd();
+    upper.arg("-c").arg("tr a-z A-Z");
+    let mut echo = shell_cmd();
+    echo.arg("-c").arg("echo hello");
+
+    let mut pipeline = Pipeline::new();
+    pipeline.pipe(echo).pipe(upper);
+    let output = pipeline.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.status.statuses().len(), 2);
+    assert_eq!(output.stdout, b"HELLO\n");
+
+    // Capturing the output must not leak into later spawns.
+    let mut child = pipeline.spawn().unwrap();
+    assert!(child.stdout.is_none());
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+#[cfg_attr(any(windows, target_os = "vxworks"), ignore)]
+fn test_pipeline_pipefail() {
+    use super::Pipeline;
+
+    let mut fail = shell_cmd();
+    fail.arg("-c").arg("exit 3");
+    let mut cat = shell_cmd();
+    cat.arg("-c").arg("cat");
+
+    let status = Pipeline::new().pipe(fail).pipe(cat).status().unwrap();
+    assert!(!status.success());
+    assert!(status.last().success());
+    assert_eq!(status.statuses()[0].code(), Some(3));
+    assert_eq!(status.pipefail().code(), Some(3));
+}
+
+#[test]
+fn test_pipeline_empty() {
+    let err = super::Pipeline::new().spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+#[cfg_attr(any(windows, target_os = "vxworks"), ignore)]
+fn test_pipeline_early_exit() {
+    use super::Pipeline;
+
+    // `head` exits after the first line, which must not leave `yes` blocked
+    // on a pipe that we still hold open.
+    let mut yes = shell_cmd();
+    yes.arg("-c").arg("yes");
+    let mut head = shell_cmd();
+    head.arg("-c").arg("head -n 1");
+
+    let output = Pipeline::new().pipe(yes).pipe(head).output().unwrap();
+    assert!(output.status.last().success());
+    assert_eq!(output.stdout, b"y\n");
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "vxworks")))]
+fn test_pipeline_process_group() {
+    use super::{Pipeline, Stdio};
+
+    // Both commands exit once the stdin of the pipeline is closed, which also
+    // happens when the test fails.
+    let mut first = shell_cmd();
+    first.arg("-c").arg("cat").stdin(Stdio::piped());
+    let mut second = shell_cmd();
+    second.arg("-c").arg("cat >/dev/null");
+    let mut pipeline = Pipeline::new();
+    pipeline.pipe(first).pipe(second);
+
+    // By default, the commands stay in our process group.
+    let own_pgid = unsafe { libc::getpgid(0) };
+    let mut child = pipeline.spawn().unwrap();
+    for p in child.children() {
+        assert_eq!(unsafe { libc::getpgid(p.id() as libc::pid_t) }, own_pgid);
+    }
+    assert!(child.wait().unwrap().success());
+
+    let mut child = pipeline.process_group(true).spawn().unwrap();
+    let pgid = child.id() as libc::pid_t;
+    assert_ne!(pgid, own_pgid);
+    for p in child.children() {
+        assert_eq!(unsafe { libc::getpgid(p.id() as libc::pid_t) }, pgid);
+    }
+    assert!(child.wait().unwrap().success());
+}
@@ -288,8 +288,6 @@
 // believe that there is anything fundamental that needs to change about these
 // channels, however, in order to support a more efficient select().
 //
-// FIXME: Select is now removed, so these factors are ready to be cleaned up!
-//
 // # Conclusion
 //
 // And now that you've seen all the races that I found and attempted to fix,
@@ -302,9 +300,12 @@
 use crate::sync::Arc;
 use crate::time::{Duration, Instant};
 
+use self::blocking::SignalToken;
+
 mod blocking;
 mod mpsc_queue;
 mod oneshot;
+mod select;
 mod shared;
 mod spsc_queue;
 mod stream;
@@ -312,6 +313,9 @@
 
 mod cache_aligned;
 
+#[unstable(feature = "mpsc_select", issue = "none")]
+pub use self::select::Select;
+
 /// The receiving half of Rust's [`channel`] (or [`sync_channel`]) type.
 /// This half can only be owned by one thread.
 ///
@@ -1497,6 +1501,71 @@ fn into_iter(self) -> IntoIter<T> {
     }
 }
 
+impl<T> select::Packet for Receiver<T> {
+    fn can_recv(&self) -> bool {
+        loop {
+            let new_port = match *unsafe { self.inner() } {
+                Flavor::Oneshot(ref p) => match p.can_recv() {
+                    Ok(ret) => return ret,
+                    Err(upgrade) => upgrade,
+                },
+                Flavor::Stream(ref p) => match p.can_recv() {
+                    Ok(ret) => return ret,
+                    Err(upgrade) => upgrade,
+                },
+                Flavor::Shared(ref p) => return p.can_recv(),
+                Flavor::Sync(ref p) => return p.can_recv(),
+            };
+            unsafe {
+                mem::swap(self.inner_mut(), new_port.inner_mut());
+            }
+        }
+    }
+
+    fn start_selection(&self, mut token: SignalToken) -> select::StartResult {
+        loop {
+            let (t, new_port) = match *unsafe { self.inner() } {
+                Flavor::Oneshot(ref p) => match p.start_selection(token) {
+                    oneshot::SelSuccess => return select::StartResult::Installed,
+                    oneshot::SelCanceled => return select::StartResult::Abort,
+                    oneshot::SelUpgraded(t, rx) => (t, rx),
+                },
+                Flavor::Stream(ref p) => match p.start_selection(token) {
+                    stream::SelSuccess => return select::StartResult::Installed,
+                    stream::SelCanceled => return select::StartResult::Abort,
+                    stream::SelUpgraded(t, rx) => (t, rx),
+                },
+                Flavor::Shared(ref p) => return p.start_selection(token),
+                Flavor::Sync(ref p) => return p.start_selection(token),
+            };
+            token = t;
+            unsafe {
+                mem::swap(self.inner_mut(), new_port.inner_mut());
+            }
+        }
+    }
+
+    fn abort_selection(&self) -> bool {
+        let mut was_upgrade = false;
+        loop {
+            let result = match *unsafe { self.inner() } {
+                Flavor::Oneshot(ref p) => p.abort_selection(),
+                Flavor::Stream(ref p) => p.abort_selection(was_upgrade),
+                Flavor::Shared(ref p) => return p.abort_selection(was_upgrade),
+                Flavor::Sync(ref p) => return p.abort_selection(),
+            };
+            let new_port = match result {
+                Ok(b) => return b,
+                Err(p) => p,
+            };
+            was_upgrade = true;
+            unsafe {
+                mem::swap(self.inner_mut(), new_port.inner_mut());
+            }
+        }
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T> Drop for Receiver<T> {
     fn drop(&mut self) {
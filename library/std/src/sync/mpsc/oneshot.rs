@@ -23,6 +23,7 @@
 /// it must check for data because there is no "data plus upgrade" state.
 pub use self::Failure::*;
 use self::MyUpgrade::*;
+pub use self::SelectionResult::*;
 pub use self::UpgradeResult::*;
 
 use crate::cell::UnsafeCell;
@@ -64,6 +65,12 @@ pub enum UpgradeResult {
     UpWoke(SignalToken),
 }
 
+pub enum SelectionResult<T> {
+    SelCanceled,
+    SelUpgraded(SignalToken, Receiver<T>),
+    SelSuccess,
+}
+
 enum MyUpgrade<T> {
     NothingSent,
     SendUsed,
@@ -257,6 +264,72 @@ pub fn drop_port(&self) {
     // select implementation
     ////////////////////////////////////////////////////////////////////////////
 
+    // If Ok, the value is whether this port has data, if Err, then the upgraded
+    // port needs to be checked instead of this one.
+    pub fn can_recv(&self) -> Result<bool, Receiver<T>> {
+        unsafe {
+            match self.state.load(Ordering::SeqCst) {
+                // Welp, we tried
+                EMPTY => Ok(false),
+                // we have some un-acquired data
+                DATA => Ok(true),
+                DISCONNECTED if (*self.data.get()).is_some() => Ok(true),
+                DISCONNECTED => {
+                    match ptr::replace(self.upgrade.get(), SendUsed) {
+                        // The other end sent us an upgrade, so we need to
+                        // propagate upwards whether the upgrade can receive
+                        // data
+                        GoUp(upgrade) => Err(upgrade),
+
+                        // If the other end disconnected without sending an
+                        // upgrade, then we have data to receive (the channel is
+                        // disconnected).
+                        up => {
+                            ptr::write(self.upgrade.get(), up);
+                            Ok(true)
+                        }
+                    }
+                }
+                _ => unreachable!(), // we're the "one blocker"
+            }
+        }
+    }
+
+    // Attempts to start selection on this port. This can either succeed, fail
+    // because there is data, or fail because there is an upgrade pending.
+    pub fn start_selection(&self, token: SignalToken) -> SelectionResult<T> {
+        unsafe {
+            let ptr = token.to_raw();
+            match self.state.compare_exchange(EMPTY, ptr, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => SelSuccess,
+                Err(DATA) => {
+                    drop(SignalToken::from_raw(ptr));
+                    SelCanceled
+                }
+                Err(DISCONNECTED) if (*self.data.get()).is_some() => {
+                    drop(SignalToken::from_raw(ptr));
+                    SelCanceled
+                }
+                Err(DISCONNECTED) => match ptr::replace(self.upgrade.get(), SendUsed) {
+                    // The other end sent us an upgrade, so we need to
+                    // propagate upwards whether the upgrade can receive
+                    // data
+                    GoUp(upgrade) => SelUpgraded(SignalToken::from_raw(ptr), upgrade),
+
+                    // If the other end disconnected without sending an
+                    // upgrade, then we have data to receive (the channel is
+                    // disconnected).
+                    up => {
+                        ptr::write(self.upgrade.get(), up);
+                        drop(SignalToken::from_raw(ptr));
+                        SelCanceled
+                    }
+                },
+                Err(_) => unreachable!(), // we're the "one blocker"
+            }
+        }
+    }
+
     // Remove a previous selecting thread from this port. This ensures that the
     // blocked thread will no longer be visible to any other threads.
     //
@@ -0,0 +1,373 @@
+//! Selection over a set of receivers.
+//!
+//! The implementation is the one described in the "Select" section of the
+//! comment in the parent module: every receiver of the set gets a clone of the
+//! same `SignalToken` installed, exactly like a blocking `recv` would install
+//! its own, and the first sender to see one of them wakes the selecting thread
+//! up. The selection is then aborted on all the receivers, which both removes
+//! the tokens and tells which receivers can be received from.
+
+use crate::fmt;
+use crate::sync::mpsc::blocking::{self, SignalToken};
+use crate::sync::mpsc::Receiver;
+use crate::time::{Duration, Instant};
+
+/// The result of installing a `SignalToken` in a channel.
+#[derive(PartialEq, Eq)]
+pub enum StartResult {
+    /// The token was installed, the channel will signal it.
+    Installed,
+    /// The token was not installed because the channel can already be
+    /// received from.
+    Abort,
+}
+
+/// The channel side of a selection, implemented by [`Receiver`] on top of the
+/// selection methods of each flavor.
+pub trait Packet {
+    /// Returns whether a receive can complete without blocking, either with a
+    /// message or with a disconnection.
+    fn can_recv(&self) -> bool;
+
+    /// Installs `token` in the channel, to be signaled when it becomes ready.
+    fn start_selection(&self, token: SignalToken) -> StartResult;
+
+    /// Removes the token previously installed by `start_selection`, returning
+    /// whether the channel is ready.
+    fn abort_selection(&self) -> bool;
+}
+
+/// A set of [`Receiver`]s to wait on at the same time.
+///
+/// Receivers are added to the set with [`recv`], which returns the index used
+/// to refer to them. [`ready`] then blocks the current thread until one of the
+/// receivers is *ready*, and returns its index. A receiver is ready when
+/// receiving from it will not block: either a message is available, or all of
+/// its senders have hung up. The message itself is then received with
+/// [`Receiver::try_recv`] or [`Receiver::recv`].
+///
+/// A `Select` borrows its receivers, so they can't be moved to another thread
+/// while they are in the set, and it only ever blocks the thread which owns
+/// them.
+///
+/// [`recv`]: Select::recv
+/// [`ready`]: Select::ready
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpsc_select)]
+/// use std::sync::mpsc::{channel, Select};
+/// use std::thread;
+///
+/// let (jobs_tx, jobs) = channel();
+/// let (stop_tx, stop) = channel::<()>();
+///
+/// thread::spawn(move || {
+///     jobs_tx.send(1).unwrap();
+///     jobs_tx.send(2).unwrap();
+///     stop_tx.send(()).unwrap();
+/// });
+///
+/// let mut sel = Select::new();
+/// let jobs_idx = sel.recv(&jobs);
+/// let stop_idx = sel.recv(&stop);
+/// let mut done = Vec::new();
+/// loop {
+///     let idx = sel.ready();
+///     if idx == jobs_idx {
+///         match jobs.try_recv() {
+///             Ok(job) => done.push(job),
+///             // All the senders hung up.
+///             Err(_) => break,
+///         }
+///     } else if idx == stop_idx {
+///         break;
+///     }
+/// }
+/// // All the jobs were sent before the stop message.
+/// done.extend(jobs.try_iter());
+/// assert_eq!(done, [1, 2]);
+/// ```
+#[unstable(feature = "mpsc_select", issue = "none")]
+pub struct Select<'a> {
+    // `dyn Packet` isn't `Sync`, so a `Select` can't be sent to another thread
+    // either: the signal token it installs is the one of the current thread.
+    //
+    // Removed receivers are left as `None` so that the indices don't change.
+    receivers: Vec<Option<&'a (dyn Packet + 'a)>>,
+}
+
+impl<'a> Select<'a> {
+    /// Creates an empty set of receivers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::Select;
+    ///
+    /// let sel = Select::new();
+    /// # drop(sel);
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    #[must_use]
+    pub fn new() -> Select<'a> {
+        Select { receivers: Vec::new() }
+    }
+
+    /// Adds a receiver to the set, returning its index.
+    ///
+    /// The indices are assigned in order, starting at 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the receiver is already in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::{channel, Select};
+    ///
+    /// let (tx1, rx1) = channel::<i32>();
+    /// let (tx2, rx2) = channel::<String>();
+    ///
+    /// let mut sel = Select::new();
+    /// assert_eq!(sel.recv(&rx1), 0);
+    /// assert_eq!(sel.recv(&rx2), 1);
+    /// # drop((tx1, tx2));
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn recv<T>(&mut self, rx: &'a Receiver<T>) -> usize {
+        // Only one thread can be blocked on a channel at a time, even if it's
+        // the same thread twice.
+        let addr = rx as *const Receiver<T> as *const ();
+        assert!(
+            !self.iter().any(|(_, other)| other as *const dyn Packet as *const () == addr),
+            "the receiver is already in the set"
+        );
+        self.receivers.push(Some(rx));
+        self.receivers.len() - 1
+    }
+
+    /// Removes the receiver at `index` from the set.
+    ///
+    /// The indices of the other receivers don't change. This is typically used
+    /// once all the senders of a receiver have hung up, as it would otherwise
+    /// stay ready forever.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no receiver at `index`, because it wasn't added or
+    /// was already removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::{channel, Select};
+    ///
+    /// let (tx1, rx1) = channel::<i32>();
+    /// let (tx2, rx2) = channel::<i32>();
+    ///
+    /// let mut sel = Select::new();
+    /// let idx1 = sel.recv(&rx1);
+    /// let idx2 = sel.recv(&rx2);
+    ///
+    /// drop(tx1);
+    /// assert_eq!(sel.try_ready(), Some(idx1));
+    /// sel.remove(idx1);
+    /// assert_eq!(sel.try_ready(), None);
+    ///
+    /// tx2.send(2).unwrap();
+    /// assert_eq!(sel.try_ready(), Some(idx2));
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn remove(&mut self, index: usize) {
+        let removed = self.receivers.get_mut(index).and_then(Option::take);
+        assert!(removed.is_some(), "no receiver at index {index}");
+    }
+
+    /// Returns the index of a ready receiver, without blocking.
+    ///
+    /// Returns [`None`] if none of the receivers is ready. If several of them
+    /// are ready, the one with the lowest index is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::{channel, Select};
+    ///
+    /// let (tx1, rx1) = channel::<i32>();
+    /// let (tx2, rx2) = channel::<i32>();
+    ///
+    /// let mut sel = Select::new();
+    /// sel.recv(&rx1);
+    /// sel.recv(&rx2);
+    /// assert_eq!(sel.try_ready(), None);
+    ///
+    /// tx2.send(1).unwrap();
+    /// assert_eq!(sel.try_ready(), Some(1));
+    /// # drop(tx1);
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn try_ready(&mut self) -> Option<usize> {
+        self.iter().find(|(_, rx)| rx.can_recv()).map(|(idx, _)| idx)
+    }
+
+    /// Blocks until one of the receivers is ready, and returns its index.
+    ///
+    /// If several receivers are ready, there is no guarantee about which one
+    /// is returned. If the set is empty, this blocks forever.
+    ///
+    /// Note that a receiver whose senders have all hung up is always ready, it
+    /// should be [removed] from the set once it's been seen to be disconnected.
+    ///
+    /// [removed]: Select::remove
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::{channel, Select};
+    /// use std::thread;
+    ///
+    /// let (tx1, rx1) = channel::<i32>();
+    /// let (tx2, rx2) = channel::<i32>();
+    ///
+    /// thread::spawn(move || {
+    ///     tx2.send(2).unwrap();
+    ///     # drop(tx1);
+    /// });
+    ///
+    /// let mut sel = Select::new();
+    /// sel.recv(&rx1);
+    /// sel.recv(&rx2);
+    /// // The first receiver becomes ready too once `tx1` is dropped.
+    /// match sel.ready() {
+    ///     0 => assert!(rx1.try_recv().is_err()),
+    ///     _ => assert_eq!(rx2.try_recv(), Ok(2)),
+    /// }
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn ready(&mut self) -> usize {
+        self.wait(None).unwrap()
+    }
+
+    /// Blocks until one of the receivers is ready or `timeout` elapses.
+    ///
+    /// Returns the index of the ready receiver, or [`None`] if the timeout
+    /// elapsed first. If several receivers are ready, there is no guarantee
+    /// about which one is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::{channel, Select};
+    /// use std::time::Duration;
+    ///
+    /// let (tx, rx) = channel::<i32>();
+    ///
+    /// let mut sel = Select::new();
+    /// sel.recv(&rx);
+    /// assert_eq!(sel.ready_timeout(Duration::from_millis(100)), None);
+    /// # drop(tx);
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn ready_timeout(&mut self, timeout: Duration) -> Option<usize> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait(Some(deadline)),
+            // So far in the future that it's practically the same as waiting
+            // indefinitely.
+            None => Some(self.ready()),
+        }
+    }
+
+    /// Blocks until one of the receivers is ready or `deadline` is reached.
+    ///
+    /// Returns the index of the ready receiver, or [`None`] if the deadline
+    /// was reached first. If several receivers are ready, there is no
+    /// guarantee about which one is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::{channel, Select};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let (tx, rx) = channel::<i32>();
+    /// tx.send(1).unwrap();
+    ///
+    /// let mut sel = Select::new();
+    /// sel.recv(&rx);
+    /// assert_eq!(sel.ready_deadline(Instant::now() + Duration::from_millis(100)), Some(0));
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn ready_deadline(&mut self, deadline: Instant) -> Option<usize> {
+        self.wait(Some(deadline))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (usize, &'a (dyn Packet + 'a))> + '_ {
+        self.receivers.iter().enumerate().filter_map(|(idx, rx)| Some((idx, (*rx)?)))
+    }
+
+    fn wait(&self, deadline: Option<Instant>) -> Option<usize> {
+        // Stage 1: preflight checks, creating the tokens is a little expensive.
+        if let Some((idx, _)) = self.iter().find(|(_, rx)| rx.can_recv()) {
+            return Some(idx);
+        }
+
+        // Stage 2: install a token in every receiver, until one of them turns
+        // out to be ready. Its selection was never started, but the ones before
+        // it need to be aborted.
+        let (wait_token, signal_token) = blocking::tokens();
+        for (idx, rx) in self.iter() {
+            if rx.start_selection(signal_token.clone()) == StartResult::Abort {
+                for (_, rx) in self.iter().take_while(|&(i, _)| i < idx) {
+                    rx.abort_selection();
+                }
+                return Some(idx);
+            }
+        }
+        drop(signal_token);
+
+        // Stage 3: nothing is ready yet, actually block.
+        match deadline {
+            Some(deadline) => {
+                wait_token.wait_max_until(deadline);
+            }
+            None => wait_token.wait(),
+        }
+
+        // Stage 4: abort the selection on *all* of the receivers, even after
+        // finding a ready one. A sender may still be in the middle of waking us
+        // up through one of the other receivers, and aborting is what waits for
+        // it to be done with the token.
+        let mut ready = None;
+        for (idx, rx) in self.iter() {
+            if rx.abort_selection() && ready.is_none() {
+                ready = Some(idx);
+            }
+        }
+        debug_assert!(ready.is_some() || deadline.is_some());
+        ready
+    }
+}
+
+#[unstable(feature = "mpsc_select", issue = "none")]
+impl Default for Select<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[unstable(feature = "mpsc_select", issue = "none")]
+impl fmt::Debug for Select<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Select").field("len", &self.iter().count()).finish_non_exhaustive()
+    }
+}
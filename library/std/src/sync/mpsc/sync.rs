@@ -31,6 +31,7 @@
 
 use crate::sync::atomic::{AtomicUsize, Ordering};
 use crate::sync::mpsc::blocking::{self, SignalToken, WaitToken};
+use crate::sync::mpsc::select::StartResult::{self, *};
 use crate::sync::{Mutex, MutexGuard};
 use crate::time::Instant;
 
@@ -317,6 +318,38 @@ pub fn try_recv(&self) -> Result<T, Failure> {
         ret
     }
 
+    // Helper function for select, tests whether this port can receive without
+    // blocking (obviously not an atomic decision).
+    pub fn can_recv(&self) -> bool {
+        let guard = self.lock.lock().unwrap();
+        guard.disconnected || guard.buf.size() > 0
+    }
+
+    // Attempts to start selection on this port. This can either succeed or fail
+    // because there is data waiting.
+    pub fn start_selection(&self, token: SignalToken) -> StartResult {
+        let mut guard = self.lock.lock().unwrap();
+        if guard.disconnected || guard.buf.size() > 0 {
+            Abort
+        } else {
+            match mem::replace(&mut guard.blocker, BlockedReceiver(token)) {
+                NoneBlocked => {}
+                BlockedSender(..) => unreachable!(),
+                BlockedReceiver(..) => unreachable!(),
+            }
+            Installed
+        }
+    }
+
+    // Remove a previous selecting thread from this port. This ensures that the
+    // blocked thread will no longer be visible to any other threads.
+    //
+    // The return value indicates whether there's data on this port.
+    pub fn abort_selection(&self) -> bool {
+        let mut guard = self.lock.lock().unwrap();
+        abort_selection(&mut guard)
+    }
+
     // Wake up pending senders after some data has been received
     //
     // * `waited` - flag if the receiver blocked to receive some data, or if it
@@ -645,3 +645,67 @@ fn repro() {
         repro()
     }
 }
+
+#[test]
+fn select_sync() {
+    let (tx1, rx1) = sync_channel::<i32>(0);
+    let (tx2, rx2) = sync_channel::<i32>(1);
+    let mut sel = Select::new();
+    sel.recv(&rx1);
+    sel.recv(&rx2);
+    assert_eq!(sel.try_ready(), None);
+
+    tx2.send(2).unwrap();
+    assert_eq!(sel.ready(), 1);
+    assert_eq!(rx2.try_recv(), Ok(2));
+
+    // A rendezvous channel is ready once the sender is blocked sending.
+    let _t = thread::spawn(move || {
+        tx1.send(1).unwrap();
+    });
+    assert_eq!(sel.ready(), 0);
+    assert_eq!(rx1.recv(), Ok(1));
+    assert_eq!(sel.ready(), 0);
+    assert_eq!(rx1.recv(), Err(RecvError));
+}
+
+#[test]
+fn select_sync_stress() {
+    let amt = 10000;
+    let (tx1, rx1) = sync_channel::<i32>(0);
+    let (tx2, rx2) = sync_channel::<i32>(10);
+    let t1 = thread::spawn(move || {
+        for i in 0..amt {
+            tx1.send(i).unwrap();
+        }
+    });
+    let t2 = thread::spawn(move || {
+        for i in 0..amt {
+            tx2.send(i).unwrap();
+        }
+    });
+
+    let mut sel = Select::new();
+    sel.recv(&rx1);
+    sel.recv(&rx2);
+    let mut received = [0, 0];
+    let mut disconnected = 0;
+    while disconnected < 2 {
+        let idx = sel.ready();
+        let rx = if idx == 0 { &rx1 } else { &rx2 };
+        match rx.try_recv() {
+            Ok(i) => {
+                assert_eq!(i, received[idx]);
+                received[idx] += 1;
+            }
+            Err(TryRecvError::Disconnected) => {
+                sel.remove(idx);
+                disconnected += 1;
+            }
+            Err(TryRecvError::Empty) => unreachable!(),
+        }
+    }
+    assert_eq!(received, [amt, amt]);
+    t1.join().unwrap();
+    t2.join().unwrap();
+}
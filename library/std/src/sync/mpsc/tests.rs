@@ -704,3 +704,140 @@ fn issue_32114() {
     let _ = tx.send(123);
     assert_eq!(tx.send(123), Err(SendError(123)));
 }
+
+#[test]
+fn select_smoke() {
+    let (tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = channel::<i32>();
+    let mut sel = Select::new();
+    assert_eq!(sel.recv(&rx1), 0);
+    assert_eq!(sel.recv(&rx2), 1);
+    assert_eq!(sel.try_ready(), None);
+
+    tx2.send(2).unwrap();
+    assert_eq!(sel.ready(), 1);
+    assert_eq!(rx2.try_recv(), Ok(2));
+    tx1.send(1).unwrap();
+    assert_eq!(sel.ready(), 0);
+    assert_eq!(rx1.try_recv(), Ok(1));
+    assert_eq!(sel.try_ready(), None);
+}
+
+#[test]
+fn select_disconnected() {
+    let (tx1, rx1) = channel::<i32>();
+    let (_tx2, rx2) = channel::<i32>();
+    let mut sel = Select::new();
+    sel.recv(&rx1);
+    sel.recv(&rx2);
+
+    drop(tx1);
+    assert_eq!(sel.ready(), 0);
+    assert_eq!(rx1.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(sel.ready(), 0);
+    sel.remove(0);
+    assert_eq!(sel.try_ready(), None);
+}
+
+#[test]
+#[should_panic]
+fn select_same_receiver_twice() {
+    let (_tx, rx) = channel::<i32>();
+    let mut sel = Select::new();
+    sel.recv(&rx);
+    sel.recv(&rx);
+}
+
+#[test]
+fn select_timeout() {
+    let (_tx1, rx1) = channel::<i32>();
+    let (_tx2, rx2) = channel::<i32>();
+    let mut sel = Select::new();
+    sel.recv(&rx1);
+    sel.recv(&rx2);
+
+    let start = Instant::now();
+    assert_eq!(sel.ready_timeout(Duration::from_millis(10)), None);
+    assert!(start.elapsed() >= Duration::from_millis(10));
+    assert_eq!(sel.ready_deadline(Instant::now()), None);
+}
+
+#[test]
+fn select_wakes_up() {
+    let (_tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = channel::<i32>();
+    let _t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx2.send(2).unwrap();
+    });
+
+    let mut sel = Select::new();
+    sel.recv(&rx1);
+    sel.recv(&rx2);
+    assert_eq!(sel.ready_timeout(Duration::from_secs(60)), Some(1));
+    assert_eq!(rx2.recv(), Ok(2));
+}
+
+#[test]
+fn select_upgraded_channels() {
+    // One message stays on the oneshot flavor, two of them upgrade it to a
+    // stream, and cloning the sender upgrades it to a shared channel.
+    for upgrade in 0..3 {
+        let (tx, rx) = channel::<i32>();
+        let (_tx2, rx2) = channel::<i32>();
+        let _t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            match upgrade {
+                0 => tx.send(1).unwrap(),
+                1 => {
+                    tx.send(1).unwrap();
+                    tx.send(2).unwrap();
+                }
+                _ => tx.clone().send(1).unwrap(),
+            }
+        });
+
+        let mut sel = Select::new();
+        sel.recv(&rx2);
+        sel.recv(&rx);
+        assert_eq!(sel.ready(), 1);
+        assert_eq!(rx.recv(), Ok(1));
+    }
+}
+
+#[test]
+fn select_stress() {
+    let amt = 10000;
+    let (tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = channel::<i32>();
+    let t = thread::spawn(move || {
+        for i in 0..amt {
+            if i % 2 == 0 {
+                tx1.send(i).unwrap();
+            } else {
+                tx2.clone().send(i).unwrap();
+            }
+        }
+    });
+
+    let mut sel = Select::new();
+    sel.recv(&rx1);
+    sel.recv(&rx2);
+    let mut received = Vec::new();
+    let mut disconnected = 0;
+    while disconnected < 2 {
+        let idx = sel.ready();
+        let rx = if idx == 0 { &rx1 } else { &rx2 };
+        match rx.try_recv() {
+            Ok(i) => received.push(i),
+            Err(TryRecvError::Disconnected) => {
+                sel.remove(idx);
+                disconnected += 1;
+            }
+            Err(TryRecvError::Empty) => unreachable!(),
+        }
+    }
+    received.sort();
+    assert_eq!(received, (0..amt).collect::<Vec<_>>());
+    t.join().unwrap();
+}
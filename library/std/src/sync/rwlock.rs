@@ -3,8 +3,9 @@
 
 use crate::cell::UnsafeCell;
 use crate::fmt;
+use crate::mem;
 use crate::ops::{Deref, DerefMut};
-use crate::sync::{poison, LockResult, TryLockError, TryLockResult};
+use crate::sync::{poison, LockResult, PoisonError, TryLockError, TryLockResult};
 use crate::sys_common::rwlock as sys;
 
 /// A reader-writer lock
@@ -135,6 +136,37 @@ impl<T: ?Sized> !Send for RwLockWriteGuard<'_, T> {}
 #[stable(feature = "rwlock_guard_sync", since = "1.23.0")]
 unsafe impl<T: ?Sized + Sync> Sync for RwLockWriteGuard<'_, T> {}
 
+/// RAII structure used to release the upgradable read access of a lock when
+/// dropped.
+///
+/// Upgradable read access is shared with regular readers, but at most one
+/// upgradable reader can hold the lock at any point in time. It can be turned
+/// into write access with [`upgrade`] or [`try_upgrade`], without any other
+/// writer getting to modify the data in between.
+///
+/// This structure is created by the [`upgradable_read`] and
+/// [`try_upgradable_read`] methods on [`RwLock`].
+///
+/// [`upgrade`]: RwLockUpgradableReadGuard::upgrade
+/// [`try_upgrade`]: RwLockUpgradableReadGuard::try_upgrade
+/// [`upgradable_read`]: RwLock::upgradable_read
+/// [`try_upgradable_read`]: RwLock::try_upgradable_read
+#[must_use = "if unused the RwLock will immediately unlock"]
+#[must_not_suspend = "holding a RwLockUpgradableReadGuard across suspend \
+                      points can cause deadlocks, delays, \
+                      and cause Futures to not implement `Send`"]
+#[unstable(feature = "rwlock_upgradable", issue = "none")]
+#[clippy::has_significant_drop]
+pub struct RwLockUpgradableReadGuard<'a, T: ?Sized + 'a> {
+    lock: &'a RwLock<T>,
+}
+
+#[unstable(feature = "rwlock_upgradable", issue = "none")]
+impl<T: ?Sized> !Send for RwLockUpgradableReadGuard<'_, T> {}
+
+#[unstable(feature = "rwlock_upgradable", issue = "none")]
+unsafe impl<T: ?Sized + Sync> Sync for RwLockUpgradableReadGuard<'_, T> {}
+
 impl<T> RwLock<T> {
     /// Creates a new instance of an `RwLock<T>` which is unlocked.
     ///
@@ -341,6 +373,103 @@ pub fn try_write(&self) -> TryLockResult<RwLockWriteGuard<'_, T>> {
         }
     }
 
+    /// Locks this rwlock with upgradable read access, blocking the current
+    /// thread until it can be acquired.
+    ///
+    /// The calling thread will be blocked until there are no writers and no
+    /// other upgradable readers which hold the lock. Regular readers may be
+    /// inside the lock when this method returns, and more of them may acquire
+    /// it while the returned guard is held.
+    ///
+    /// Returns an RAII guard which will release this thread's upgradable read
+    /// access once it is dropped, or which can be turned into write access
+    /// with [`RwLockUpgradableReadGuard::upgrade`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the RwLock is poisoned. An RwLock
+    /// is poisoned whenever a writer panics while holding an exclusive lock.
+    /// The failure will occur immediately after the lock has been acquired.
+    ///
+    /// # Panics
+    ///
+    /// This function might panic when called if the lock is already held by the current thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(rwlock_upgradable)]
+    ///
+    /// use std::sync::{RwLock, RwLockUpgradableReadGuard};
+    ///
+    /// let lock = RwLock::new(vec![1, 2]);
+    ///
+    /// let v = lock.upgradable_read().unwrap();
+    /// if !v.contains(&3) {
+    ///     let mut v = RwLockUpgradableReadGuard::upgrade(v);
+    ///     v.push(3);
+    /// }
+    /// assert_eq!(*lock.read().unwrap(), [1, 2, 3]);
+    /// ```
+    #[inline]
+    #[unstable(feature = "rwlock_upgradable", issue = "none")]
+    pub fn upgradable_read(&self) -> LockResult<RwLockUpgradableReadGuard<'_, T>> {
+        unsafe {
+            self.inner.upgradable_read();
+            RwLockUpgradableReadGuard::new(self)
+        }
+    }
+
+    /// Attempts to acquire this rwlock with upgradable read access.
+    ///
+    /// If the access could not be granted at this time, then `Err` is returned.
+    /// Otherwise, an RAII guard is returned which will release the upgradable
+    /// read access when it is dropped.
+    ///
+    /// This function does not block.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the RwLock is poisoned.
+    /// An RwLock is poisoned whenever a writer panics while holding an exclusive
+    /// lock. `Poisoned` will only be returned if the lock would have otherwise been
+    /// acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the RwLock could not
+    /// be acquired because it was already locked exclusively or by another
+    /// upgradable reader.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(rwlock_upgradable)]
+    ///
+    /// use std::sync::RwLock;
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let u = lock.try_upgradable_read().unwrap();
+    /// assert_eq!(*u, 1);
+    ///
+    /// // Regular readers can still get in, but other upgradable readers can't.
+    /// assert!(lock.try_read().is_ok());
+    /// assert!(lock.try_upgradable_read().is_err());
+    /// ```
+    #[inline]
+    #[unstable(feature = "rwlock_upgradable", issue = "none")]
+    pub fn try_upgradable_read(&self) -> TryLockResult<RwLockUpgradableReadGuard<'_, T>> {
+        unsafe {
+            if self.inner.try_upgradable_read() {
+                Ok(RwLockUpgradableReadGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
     /// Determines whether the lock is poisoned.
     ///
     /// If another thread is active, the lock can still become poisoned at any
@@ -518,6 +647,143 @@ impl<'rwlock, T: ?Sized> RwLockWriteGuard<'rwlock, T> {
     unsafe fn new(lock: &'rwlock RwLock<T>) -> LockResult<RwLockWriteGuard<'rwlock, T>> {
         poison::map_result(lock.poison.borrow(), |guard| RwLockWriteGuard { lock, poison: guard })
     }
+
+    /// Turns this write lock into a read lock, without releasing the lock in
+    /// between.
+    ///
+    /// Other readers waiting for the lock may acquire it once this returns,
+    /// but no writer can modify the data before the returned guard is dropped.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `RwLockWriteGuard::downgrade(...)`, so that it doesn't conflict with
+    /// a method of the same name on the contents of the lock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(rwlock_upgradable)]
+    ///
+    /// use std::sync::{RwLock, RwLockWriteGuard};
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let mut w = lock.write().unwrap();
+    /// *w += 1;
+    ///
+    /// let r = RwLockWriteGuard::downgrade(w);
+    /// assert_eq!(*r, 2);
+    /// assert!(lock.try_read().is_ok());
+    /// assert!(lock.try_write().is_err());
+    /// ```
+    #[unstable(feature = "rwlock_upgradable", issue = "none")]
+    pub fn downgrade(s: Self) -> RwLockReadGuard<'rwlock, T> {
+        let lock = s.lock;
+        lock.poison.done(&s.poison);
+        mem::forget(s);
+        unsafe {
+            lock.inner.downgrade();
+        }
+        RwLockReadGuard { lock }
+    }
+}
+
+impl<'rwlock, T: ?Sized> RwLockUpgradableReadGuard<'rwlock, T> {
+    unsafe fn new(lock: &'rwlock RwLock<T>) -> LockResult<RwLockUpgradableReadGuard<'rwlock, T>> {
+        poison::map_result(lock.poison.borrow(), |_| RwLockUpgradableReadGuard { lock })
+    }
+
+    /// Turns this upgradable read lock into a write lock, blocking the current
+    /// thread until all other readers have released the lock.
+    ///
+    /// No other writer can acquire the lock in the meantime, so the data is
+    /// guaranteed not to have changed since it was read through this guard.
+    ///
+    /// Whether new readers can still acquire the lock while this waits for
+    /// the existing ones to leave depends on the platform, like the priority
+    /// policy of the lock. On some platforms, a steady stream of new readers
+    /// may keep the upgrade waiting indefinitely.
+    ///
+    /// Whether the lock is poisoned was already reported when this guard was
+    /// acquired, and can't change while it is held, so this returns the write
+    /// guard directly.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `RwLockUpgradableReadGuard::upgrade(...)`, so that it doesn't conflict
+    /// with a method of the same name on the contents of the lock.
+    ///
+    /// # Panics
+    ///
+    /// This function might panic when called if the current thread also holds
+    /// a regular read lock on the same `RwLock`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(rwlock_upgradable)]
+    ///
+    /// use std::sync::{RwLock, RwLockUpgradableReadGuard};
+    ///
+    /// let lock = RwLock::new(0);
+    ///
+    /// let u = lock.upgradable_read().unwrap();
+    /// if *u == 0 {
+    ///     let mut w = RwLockUpgradableReadGuard::upgrade(u);
+    ///     *w = 1;
+    /// }
+    /// assert_eq!(*lock.read().unwrap(), 1);
+    /// ```
+    #[unstable(feature = "rwlock_upgradable", issue = "none")]
+    pub fn upgrade(s: Self) -> RwLockWriteGuard<'rwlock, T> {
+        let lock = s.lock;
+        mem::forget(s);
+        unsafe {
+            lock.inner.upgrade();
+        }
+        let poison = lock.poison.borrow().unwrap_or_else(PoisonError::into_inner);
+        RwLockWriteGuard { lock, poison }
+    }
+
+    /// Attempts to turn this upgradable read lock into a write lock.
+    ///
+    /// This succeeds only if no other readers hold the lock. Otherwise, the
+    /// upgradable read guard is returned unchanged in the `Err` variant.
+    ///
+    /// This function does not block.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `RwLockUpgradableReadGuard::try_upgrade(...)`, so that it doesn't
+    /// conflict with a method of the same name on the contents of the lock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(rwlock_upgradable)]
+    ///
+    /// use std::sync::{RwLock, RwLockUpgradableReadGuard};
+    ///
+    /// let lock = RwLock::new(0);
+    ///
+    /// let u = lock.upgradable_read().unwrap();
+    /// let r = lock.read().unwrap();
+    ///
+    /// // Can't upgrade while another reader holds the lock.
+    /// let u = RwLockUpgradableReadGuard::try_upgrade(u).unwrap_err();
+    /// drop(r);
+    ///
+    /// let mut w = RwLockUpgradableReadGuard::try_upgrade(u).unwrap();
+    /// *w = 1;
+    /// ```
+    #[unstable(feature = "rwlock_upgradable", issue = "none")]
+    pub fn try_upgrade(s: Self) -> Result<RwLockWriteGuard<'rwlock, T>, Self> {
+        if unsafe { s.lock.inner.try_upgrade() } {
+            let lock = s.lock;
+            mem::forget(s);
+            let poison = lock.poison.borrow().unwrap_or_else(PoisonError::into_inner);
+            Ok(RwLockWriteGuard { lock, poison })
+        } else {
+            Err(s)
+        }
+    }
 }
 
 #[stable(feature = "std_debug", since = "1.16.0")]
@@ -534,6 +800,20 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+#[unstable(feature = "rwlock_upgradable", issue = "none")]
+impl<T: fmt::Debug> fmt::Debug for RwLockUpgradableReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[unstable(feature = "rwlock_upgradable", issue = "none")]
+impl<T: ?Sized + fmt::Display> fmt::Display for RwLockUpgradableReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
 #[stable(feature = "std_debug", since = "1.16.0")]
 impl<T: fmt::Debug> fmt::Debug for RwLockWriteGuard<'_, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -557,6 +837,15 @@ fn deref(&self) -> &T {
     }
 }
 
+#[unstable(feature = "rwlock_upgradable", issue = "none")]
+impl<T: ?Sized> Deref for RwLockUpgradableReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
     type Target = T;
@@ -582,6 +871,15 @@ fn drop(&mut self) {
     }
 }
 
+#[unstable(feature = "rwlock_upgradable", issue = "none")]
+impl<T: ?Sized> Drop for RwLockUpgradableReadGuard<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            self.lock.inner.upgradable_read_unlock();
+        }
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T: ?Sized> Drop for RwLockWriteGuard<'_, T> {
     fn drop(&mut self) {
@@ -1,7 +1,8 @@
 use crate::sync::atomic::{AtomicUsize, Ordering};
 use crate::sync::mpsc::channel;
-use crate::sync::{Arc, RwLock, TryLockError};
+use crate::sync::{Arc, RwLock, RwLockUpgradableReadGuard, RwLockWriteGuard, TryLockError};
 use crate::thread;
+use crate::time::Duration;
 use rand::{self, Rng};
 
 #[derive(Eq, PartialEq, Debug)]
@@ -245,3 +246,135 @@ fn test_get_mut_poison() {
         Ok(x) => panic!("get_mut of poisoned RwLock is Ok: {x:?}"),
     }
 }
+
+#[test]
+fn test_upgradable_read_shared() {
+    let lock = RwLock::new(0);
+    let u = lock.upgradable_read().unwrap();
+    assert!(lock.try_read().is_ok());
+    assert!(matches!(lock.try_upgradable_read(), Err(TryLockError::WouldBlock)));
+    assert!(matches!(lock.try_write(), Err(TryLockError::WouldBlock)));
+    drop(u);
+    assert!(lock.try_upgradable_read().is_ok());
+    assert!(lock.try_write().is_ok());
+}
+
+#[test]
+fn test_try_upgrade() {
+    let lock = RwLock::new(0);
+    let u = lock.upgradable_read().unwrap();
+    let r = lock.read().unwrap();
+    let u = RwLockUpgradableReadGuard::try_upgrade(u).unwrap_err();
+    drop(r);
+    let mut w = RwLockUpgradableReadGuard::try_upgrade(u).unwrap();
+    *w = 1;
+    assert!(lock.try_read().is_err());
+    drop(w);
+    assert_eq!(*lock.read().unwrap(), 1);
+}
+
+#[test]
+fn test_upgrade_waits_for_readers() {
+    let lock = Arc::new(RwLock::new(0));
+    let r = lock.read().unwrap();
+
+    let lock2 = lock.clone();
+    let (tx, rx) = channel();
+    let t = thread::spawn(move || {
+        let u = lock2.upgradable_read().unwrap();
+        tx.send(()).unwrap();
+        let mut w = RwLockUpgradableReadGuard::upgrade(u);
+        *w += 1;
+    });
+
+    rx.recv().unwrap();
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(*r, 0);
+    drop(r);
+    t.join().unwrap();
+    assert_eq!(*lock.read().unwrap(), 1);
+}
+
+#[test]
+fn test_downgrade() {
+    let lock = Arc::new(RwLock::new(0));
+    let mut w = lock.write().unwrap();
+    *w = 1;
+
+    // A reader waiting for the write lock gets in once it's downgraded.
+    let lock2 = lock.clone();
+    let t = thread::spawn(move || *lock2.read().unwrap());
+
+    let r = RwLockWriteGuard::downgrade(w);
+    assert_eq!(t.join().unwrap(), 1);
+    assert!(matches!(lock.try_write(), Err(TryLockError::WouldBlock)));
+    drop(r);
+    assert!(lock.try_write().is_ok());
+}
+
+#[test]
+fn test_upgrade_poison() {
+    let arc = Arc::new(RwLock::new(1));
+    let arc2 = arc.clone();
+    let _: Result<(), _> = thread::spawn(move || {
+        let _lock = RwLockUpgradableReadGuard::upgrade(arc2.upgradable_read().unwrap());
+        panic!();
+    })
+    .join();
+    assert!(arc.is_poisoned());
+    assert!(arc.upgradable_read().is_err());
+}
+
+#[test]
+fn test_upgradable_read_no_poison() {
+    let arc = Arc::new(RwLock::new(1));
+    let arc2 = arc.clone();
+    let _: Result<(), _> = thread::spawn(move || {
+        let _lock = arc2.upgradable_read().unwrap();
+        panic!();
+    })
+    .join();
+    assert!(!arc.is_poisoned());
+    assert_eq!(*arc.write().unwrap(), 1);
+}
+
+#[test]
+fn frob_upgradable() {
+    const N: u32 = 10;
+    const M: usize = 1000;
+
+    let r = Arc::new(RwLock::new(0));
+
+    let (tx, rx) = channel::<usize>();
+    for _ in 0..N {
+        let tx = tx.clone();
+        let r = r.clone();
+        thread::spawn(move || {
+            let mut rng = rand::thread_rng();
+            let mut increments = 0;
+            for _ in 0..M {
+                match rng.gen_range(0..4) {
+                    0 => drop(r.read().unwrap()),
+                    1 => {
+                        let w = r.write().unwrap();
+                        drop(RwLockWriteGuard::downgrade(w));
+                    }
+                    _ => {
+                        // Nobody else can change the value between reading
+                        // and upgrading.
+                        let u = r.upgradable_read().unwrap();
+                        let value = *u;
+                        let mut w = RwLockUpgradableReadGuard::upgrade(u);
+                        assert_eq!(*w, value);
+                        *w += 1;
+                        increments += 1;
+                    }
+                }
+            }
+            tx.send(increments).unwrap();
+        });
+    }
+    drop(tx);
+    let total: usize = rx.iter().sum();
+    assert_eq!(*r.read().unwrap(), total);
+}
@@ -5,6 +5,8 @@ pub struct RwLock {
     lock: Mutex,
     cond: Condvar,
     state: UnsafeCell<State>,
+    // Whether one of the readers holds an upgradable read lock.
+    upgradable: UnsafeCell<bool>,
 }
 
 pub type MovableRwLock = RwLock;
@@ -28,7 +30,12 @@ unsafe impl Sync for RwLock {}
 
 impl RwLock {
     pub const fn new() -> RwLock {
-        RwLock { lock: Mutex::new(), cond: Condvar::new(), state: UnsafeCell::new(State::Unlocked) }
+        RwLock {
+            lock: Mutex::new(),
+            cond: Condvar::new(),
+            state: UnsafeCell::new(State::Unlocked),
+            upgradable: UnsafeCell::new(false),
+        }
     }
 
     #[inline]
@@ -68,7 +75,11 @@ pub unsafe fn try_write(&self) -> bool {
     #[inline]
     pub unsafe fn read_unlock(&self) {
         self.lock.lock();
-        let notify = (*self.state.get()).dec_readers();
+        let mut notify = (*self.state.get()).dec_readers();
+        // An upgradable reader might be waiting for us to be the last one to leave.
+        if *self.upgradable.get() && (*self.state.get()).is_single_reader() {
+            notify = true;
+        }
         self.lock.unlock();
         if notify {
             // FIXME: should only wake up one of these some of the time
@@ -84,9 +95,78 @@ pub unsafe fn write_unlock(&self) {
         // FIXME: should only wake up one of these some of the time
         self.cond.notify_all();
     }
+
+    #[inline]
+    pub unsafe fn upgradable_read(&self) {
+        self.lock.lock();
+        while *self.upgradable.get() || !(*self.state.get()).inc_readers() {
+            self.cond.wait(&self.lock);
+        }
+        *self.upgradable.get() = true;
+        self.lock.unlock();
+    }
+
+    #[inline]
+    pub unsafe fn try_upgradable_read(&self) -> bool {
+        self.lock.lock();
+        let ok = !*self.upgradable.get() && (*self.state.get()).inc_readers();
+        if ok {
+            *self.upgradable.get() = true;
+        }
+        self.lock.unlock();
+        return ok;
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_read_unlock(&self) {
+        self.lock.lock();
+        *self.upgradable.get() = false;
+        (*self.state.get()).dec_readers();
+        self.lock.unlock();
+        // Wake up writers if we were the last reader, or other upgradable
+        // readers in any case.
+        self.cond.notify_all();
+    }
+
+    #[inline]
+    pub unsafe fn upgrade(&self) {
+        self.lock.lock();
+        while !(*self.state.get()).is_single_reader() {
+            self.cond.wait(&self.lock);
+        }
+        *self.upgradable.get() = false;
+        *self.state.get() = State::Writing;
+        self.lock.unlock();
+    }
+
+    #[inline]
+    pub unsafe fn try_upgrade(&self) -> bool {
+        self.lock.lock();
+        let ok = (*self.state.get()).is_single_reader();
+        if ok {
+            *self.upgradable.get() = false;
+            *self.state.get() = State::Writing;
+        }
+        self.lock.unlock();
+        return ok;
+    }
+
+    #[inline]
+    pub unsafe fn downgrade(&self) {
+        self.lock.lock();
+        (*self.state.get()).dec_writers();
+        (*self.state.get()).inc_readers();
+        self.lock.unlock();
+        // Let waiting readers join us.
+        self.cond.notify_all();
+    }
 }
 
 impl State {
+    fn is_single_reader(&self) -> bool {
+        matches!(*self, State::Reading(1))
+    }
+
     fn inc_readers(&mut self) -> bool {
         match *self {
             State::Unlocked => {
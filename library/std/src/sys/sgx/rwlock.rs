@@ -2,8 +2,10 @@
 mod tests;
 
 use crate::num::NonZeroUsize;
+use crate::sync::atomic::{AtomicBool, Ordering};
 use crate::sys_common::lazy_box::{LazyBox, LazyInit};
 
+use super::mutex::Mutex;
 use super::waitqueue::{
     try_lock_or_false, NotifiedTcs, SpinMutex, SpinMutexGuard, WaitQueue, WaitVariable,
 };
@@ -14,11 +16,164 @@ pub struct RwLock {
     writer: SpinMutex<WaitVariable<bool>>,
 }
 
-pub(crate) type MovableRwLock = LazyBox<RwLock>;
+pub(crate) type MovableRwLock = LazyBox<UpgradableRwLock>;
 
-impl LazyInit for RwLock {
+/// An `RwLock` that also supports upgradable read locks.
+///
+/// The layout of `RwLock` is shared with libunwind, so the extra state lives
+/// in this wrapper instead. Upgradable readers hold `upgrade_lock`, to keep
+/// out other upgradable readers. Upgrades and downgrades briefly unlock
+/// `inner`, while holding `handoff` and setting `handing_off`, and any writer
+/// that gets in between backs off until the handoff is done.
+pub struct UpgradableRwLock {
+    inner: RwLock,
+    upgrade_lock: Mutex,
+    handoff: Mutex,
+    handing_off: AtomicBool,
+}
+
+impl LazyInit for UpgradableRwLock {
     fn init() -> Box<Self> {
-        Box::new(Self::new())
+        Box::new(UpgradableRwLock {
+            inner: RwLock::new(),
+            upgrade_lock: Mutex::new(),
+            handoff: Mutex::new(),
+            handing_off: AtomicBool::new(false),
+        })
+    }
+}
+
+impl UpgradableRwLock {
+    #[inline]
+    pub unsafe fn read(&self) {
+        unsafe { self.inner.read() }
+    }
+
+    #[inline]
+    pub unsafe fn try_read(&self) -> bool {
+        unsafe { self.inner.try_read() }
+    }
+
+    #[inline]
+    pub unsafe fn write(&self) {
+        unsafe {
+            self.inner.write();
+            // `handing_off` is only changed while `inner` is locked, so we
+            // can't miss a handoff that we got in the middle of.
+            while self.handing_off.load(Ordering::Relaxed) {
+                self.back_off();
+                self.inner.write();
+            }
+        }
+    }
+
+    /// Lets the upgrade or downgrade we got in the middle of finish.
+    #[cold]
+    unsafe fn back_off(&self) {
+        unsafe {
+            self.inner.write_unlock();
+            self.handoff.lock();
+            self.handoff.unlock();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn try_write(&self) -> bool {
+        unsafe {
+            if !self.inner.try_write() {
+                false
+            } else if self.handing_off.load(Ordering::Relaxed) {
+                self.inner.write_unlock();
+                false
+            } else {
+                true
+            }
+        }
+    }
+
+    #[inline]
+    pub unsafe fn read_unlock(&self) {
+        unsafe { self.inner.read_unlock() }
+    }
+
+    #[inline]
+    pub unsafe fn write_unlock(&self) {
+        unsafe { self.inner.write_unlock() }
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_read(&self) {
+        unsafe {
+            self.upgrade_lock.lock();
+            self.inner.read();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn try_upgradable_read(&self) -> bool {
+        unsafe {
+            if !self.upgrade_lock.try_lock() {
+                return false;
+            }
+            if self.inner.try_read() {
+                true
+            } else {
+                self.upgrade_lock.unlock();
+                false
+            }
+        }
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_read_unlock(&self) {
+        unsafe {
+            self.inner.read_unlock();
+            self.upgrade_lock.unlock();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn upgrade(&self) {
+        unsafe {
+            self.handoff.lock();
+            self.handing_off.store(true, Ordering::Relaxed);
+            self.inner.read_unlock();
+            self.inner.write();
+            self.handing_off.store(false, Ordering::Relaxed);
+            self.handoff.unlock();
+            self.upgrade_lock.unlock();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn try_upgrade(&self) -> bool {
+        unsafe {
+            self.handoff.lock();
+            self.handing_off.store(true, Ordering::Relaxed);
+            self.inner.read_unlock();
+            let upgraded = self.inner.try_write();
+            if !upgraded {
+                self.inner.read();
+            }
+            self.handing_off.store(false, Ordering::Relaxed);
+            self.handoff.unlock();
+            if upgraded {
+                self.upgrade_lock.unlock();
+            }
+            upgraded
+        }
+    }
+
+    #[inline]
+    pub unsafe fn downgrade(&self) {
+        unsafe {
+            self.handoff.lock();
+            self.handing_off.store(true, Ordering::Relaxed);
+            self.inner.write_unlock();
+            self.inner.read();
+            self.handing_off.store(false, Ordering::Relaxed);
+            self.handoff.unlock();
+        }
     }
 }
 
@@ -3,13 +3,22 @@
     abi,
     itron::{
         error::{expect_success, expect_success_aborting, fail, ItronError},
+        mutex::Mutex,
         spin::SpinIdOnceCell,
     },
 };
+use crate::sync::atomic::{AtomicBool, Ordering};
 
 pub struct RwLock {
     /// The ID of the underlying mutex object
     rwl: SpinIdOnceCell<()>,
+    /// Held by upgradable readers, to keep out other upgradable readers
+    upgrade_lock: Mutex,
+    /// Held while `rwl` is briefly unlocked to upgrade or downgrade it. Any
+    /// writer that gets in between sees `handing_off` and backs off until
+    /// the handoff is done.
+    handoff: Mutex,
+    handing_off: AtomicBool,
 }
 
 pub type MovableRwLock = RwLock;
@@ -24,7 +33,12 @@ fn new_rwl() -> Result<abi::ID, ItronError> {
 
 impl RwLock {
     pub const fn new() -> RwLock {
-        RwLock { rwl: SpinIdOnceCell::new() }
+        RwLock {
+            rwl: SpinIdOnceCell::new(),
+            upgrade_lock: Mutex::new(),
+            handoff: Mutex::new(),
+            handing_off: AtomicBool::new(false),
+        }
     }
 
     /// Get the inner mutex's ID, which is lazily created.
@@ -55,12 +69,43 @@ pub unsafe fn try_read(&self) -> bool {
 
     #[inline]
     pub unsafe fn write(&self) {
+        unsafe { self.raw_write() };
+        // `handing_off` is only changed while `rwl` is locked, so we can't
+        // miss a handoff that we got in the middle of.
+        while self.handing_off.load(Ordering::Relaxed) {
+            unsafe { self.back_off() };
+            unsafe { self.raw_write() };
+        }
+    }
+
+    /// Let the upgrade or downgrade we got in the middle of finish.
+    #[cold]
+    unsafe fn back_off(&self) {
+        unsafe { self.write_unlock() };
+        unsafe { self.handoff.lock() };
+        unsafe { self.handoff.unlock() };
+    }
+
+    #[inline]
+    unsafe fn raw_write(&self) {
         let rwl = self.raw();
         expect_success(unsafe { abi::rwl_loc_wrl(rwl) }, &"rwl_loc_wrl");
     }
 
     #[inline]
     pub unsafe fn try_write(&self) -> bool {
+        if !unsafe { self.raw_try_write() } {
+            false
+        } else if self.handing_off.load(Ordering::Relaxed) {
+            unsafe { self.write_unlock() };
+            false
+        } else {
+            true
+        }
+    }
+
+    #[inline]
+    unsafe fn raw_try_write(&self) -> bool {
         let rwl = self.raw();
         match unsafe { abi::rwl_ploc_wrl(rwl) } {
             abi::E_TMOUT => false,
@@ -82,6 +127,69 @@ pub unsafe fn write_unlock(&self) {
         let rwl = self.raw();
         expect_success_aborting(unsafe { abi::rwl_unl_rwl(rwl) }, &"rwl_unl_rwl");
     }
+
+    #[inline]
+    pub unsafe fn upgradable_read(&self) {
+        unsafe { self.upgrade_lock.lock() };
+        unsafe { self.read() };
+    }
+
+    #[inline]
+    pub unsafe fn try_upgradable_read(&self) -> bool {
+        if !unsafe { self.upgrade_lock.try_lock() } {
+            return false;
+        }
+        if unsafe { self.try_read() } {
+            true
+        } else {
+            unsafe { self.upgrade_lock.unlock() };
+            false
+        }
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_read_unlock(&self) {
+        unsafe { self.read_unlock() };
+        unsafe { self.upgrade_lock.unlock() };
+    }
+
+    #[inline]
+    pub unsafe fn upgrade(&self) {
+        unsafe { self.handoff.lock() };
+        self.handing_off.store(true, Ordering::Relaxed);
+        unsafe { self.read_unlock() };
+        unsafe { self.raw_write() };
+        self.handing_off.store(false, Ordering::Relaxed);
+        unsafe { self.handoff.unlock() };
+        unsafe { self.upgrade_lock.unlock() };
+    }
+
+    #[inline]
+    pub unsafe fn try_upgrade(&self) -> bool {
+        unsafe { self.handoff.lock() };
+        self.handing_off.store(true, Ordering::Relaxed);
+        unsafe { self.read_unlock() };
+        let upgraded = unsafe { self.raw_try_write() };
+        if !upgraded {
+            unsafe { self.read() };
+        }
+        self.handing_off.store(false, Ordering::Relaxed);
+        unsafe { self.handoff.unlock() };
+        if upgraded {
+            unsafe { self.upgrade_lock.unlock() };
+        }
+        upgraded
+    }
+
+    #[inline]
+    pub unsafe fn downgrade(&self) {
+        unsafe { self.handoff.lock() };
+        self.handing_off.store(true, Ordering::Relaxed);
+        unsafe { self.write_unlock() };
+        unsafe { self.read() };
+        self.handing_off.store(false, Ordering::Relaxed);
+        unsafe { self.handoff.unlock() };
+    }
 }
 
 impl Drop for RwLock {
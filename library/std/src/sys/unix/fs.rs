@@ -1010,6 +1010,34 @@ pub fn set_permissions(&self, perm: FilePermissions) -> io::Result<()> {
         cvt_r(|| unsafe { libc::fchmod(self.as_raw_fd(), perm.mode) })?;
         Ok(())
     }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        flock_impl::flock(self.as_raw_fd(), flock_impl::LOCK_SH)
+    }
+
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        flock_impl::flock(self.as_raw_fd(), flock_impl::LOCK_EX)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.try_flock(flock_impl::LOCK_SH)
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        self.try_flock(flock_impl::LOCK_EX)
+    }
+
+    fn try_flock(&self, operation: c_int) -> io::Result<bool> {
+        match flock_impl::flock(self.as_raw_fd(), operation | flock_impl::LOCK_NB) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        flock_impl::flock(self.as_raw_fd(), flock_impl::LOCK_UN)
+    }
 }
 
 impl DirBuilder {
@@ -1751,3 +1779,60 @@ pub fn remove_dir_all(p: &Path) -> io::Result<()> {
         }
     }
 }
+
+// `flock` locks belong to the open file description, so they are shared by the `dup`ed and
+// inherited descriptors. POSIX `fcntl` locks are released as soon as the process closes
+// any descriptor to the file, and OFD locks are only available on Linux.
+mod flock_impl {
+    use crate::io;
+    use crate::sys::cvt_r;
+    use libc::c_int;
+
+    cfg_if::cfg_if! {
+        if #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "fuchsia",
+            target_os = "illumos",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "openbsd",
+        ))] {
+            pub use libc::{LOCK_EX, LOCK_NB, LOCK_SH, LOCK_UN};
+
+            pub fn flock(fd: c_int, operation: c_int) -> io::Result<()> {
+                cvt_r(|| unsafe { libc::flock(fd, operation) })?;
+                Ok(())
+            }
+        } else if #[cfg(target_os = "solaris")] {
+            // Solaris has `flock` since 11.4, but the libc crate does not bind it.
+            pub const LOCK_SH: c_int = 1;
+            pub const LOCK_EX: c_int = 2;
+            pub const LOCK_NB: c_int = 4;
+            pub const LOCK_UN: c_int = 8;
+
+            extern "C" {
+                #[link_name = "flock"]
+                fn solaris_flock(fd: c_int, operation: c_int) -> c_int;
+            }
+
+            pub fn flock(fd: c_int, operation: c_int) -> io::Result<()> {
+                cvt_r(|| unsafe { solaris_flock(fd, operation) })?;
+                Ok(())
+            }
+        } else {
+            // The operations are never performed, so their values do not matter.
+            pub const LOCK_SH: c_int = 0;
+            pub const LOCK_EX: c_int = 0;
+            pub const LOCK_NB: c_int = 0;
+            pub const LOCK_UN: c_int = 0;
+
+            pub fn flock(_fd: c_int, _operation: c_int) -> io::Result<()> {
+                Err(io::const_io_error!(io::ErrorKind::Unsupported, "flock function not available"))
+            }
+        }
+    }
+}
@@ -7,13 +7,16 @@
 pub type MovableRwLock = RwLock;
 
 pub struct RwLock {
-    // The state consists of a 30-bit reader counter, a 'readers waiting' flag, and a 'writers waiting' flag.
-    // Bits 0..30:
+    // The state consists of a 28-bit reader counter, an 'upgradable' flag, an 'upgrading' flag,
+    // a 'readers waiting' flag, and a 'writers waiting' flag.
+    // Bits 0..28:
     //   0: Unlocked
-    //   1..=0x3FFF_FFFE: Locked by N readers
-    //   0x3FFF_FFFF: Write locked
+    //   1..=0x0FFF_FFFE: Locked by N readers
+    //   0x0FFF_FFFF: Write locked
+    // Bit 28: One of the readers holds an upgradable read lock.
+    // Bit 29: The upgradable reader is waiting on this futex for the other readers to leave.
     // Bit 30: Readers are waiting on this futex.
-    // Bit 31: Writers are waiting on the writer_notify futex.
+    // Bit 31: Writers (and upgradable readers) are waiting on the writer_notify futex.
     state: AtomicU32,
     // The 'condition variable' to notify writers through.
     // Incremented on every signal.
@@ -21,9 +24,11 @@ pub struct RwLock {
 }
 
 const READ_LOCKED: u32 = 1;
-const MASK: u32 = (1 << 30) - 1;
+const MASK: u32 = (1 << 28) - 1;
 const WRITE_LOCKED: u32 = MASK;
 const MAX_READERS: u32 = MASK - 1;
+const UPGRADABLE: u32 = 1 << 28;
+const UPGRADING: u32 = 1 << 29;
 const READERS_WAITING: u32 = 1 << 30;
 const WRITERS_WAITING: u32 = 1 << 31;
 
@@ -35,6 +40,14 @@ fn is_write_locked(state: u32) -> bool {
     state & MASK == WRITE_LOCKED
 }
 
+fn is_upgradable(state: u32) -> bool {
+    state & UPGRADABLE != 0
+}
+
+fn is_upgrading(state: u32) -> bool {
+    state & UPGRADING != 0
+}
+
 fn has_readers_waiting(state: u32) -> bool {
     state & READERS_WAITING != 0
 }
@@ -50,7 +63,27 @@ fn is_read_lockable(state: u32) -> bool {
     // and there's no writers waiting. The only situation when this happens is after unlocking,
     // at which point the unlocking thread might be waking up writers, which have priority over readers.
     // The unlocking thread will clear the readers waiting bit and wake up readers, if necssary.
-    state & MASK < MAX_READERS && !has_readers_waiting(state) && !has_writers_waiting(state)
+    //
+    // We also don't allow it while an upgradable reader is waiting for the other readers to
+    // leave, as it would otherwise never get to upgrade.
+    state & MASK < MAX_READERS
+        && !has_readers_waiting(state)
+        && !has_writers_waiting(state)
+        && !is_upgrading(state)
+}
+
+fn is_upgradable_read_lockable(state: u32) -> bool {
+    is_read_lockable(state) && !is_upgradable(state)
+}
+
+fn is_upgradable_only(state: u32) -> bool {
+    // The upgradable reader counts as one of the readers.
+    state & MASK == READ_LOCKED
+}
+
+/// The state after upgrading or downgrading: only the waiting bits are preserved.
+fn waiting_bits(state: u32) -> u32 {
+    state & (READERS_WAITING | WRITERS_WAITING)
 }
 
 fn has_reached_max_readers(state: u32) -> bool {
@@ -88,12 +121,17 @@ pub unsafe fn read_unlock(&self) {
         let state = self.state.fetch_sub(READ_LOCKED, Release) - READ_LOCKED;
 
         // It's impossible for a reader to be waiting on a read-locked RwLock,
-        // except if there is also a writer waiting.
-        debug_assert!(!has_readers_waiting(state) || has_writers_waiting(state));
+        // except if there is also a writer waiting or an upgrade pending.
+        debug_assert!(
+            !has_readers_waiting(state) || has_writers_waiting(state) || is_upgrading(state)
+        );
 
-        // Wake up a writer if we were the last reader and there's a writer waiting.
         if is_unlocked(state) && has_writers_waiting(state) {
+            // Wake up a writer if we were the last reader and there's a writer waiting.
             self.wake_writer_or_readers(state);
+        } else if is_upgrading(state) && is_upgradable_only(state) {
+            // Wake up the upgradable reader if it's waiting for us to leave.
+            self.wake_upgrader();
         }
     }
 
@@ -218,6 +256,182 @@ fn write_contended(&self) {
         }
     }
 
+    #[inline]
+    pub unsafe fn try_upgradable_read(&self) -> bool {
+        self.state
+            .fetch_update(Acquire, Relaxed, |s| {
+                is_upgradable_read_lockable(s).then(|| s + READ_LOCKED + UPGRADABLE)
+            })
+            .is_ok()
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_read(&self) {
+        let state = self.state.load(Relaxed);
+        if !is_upgradable_read_lockable(state)
+            || self
+                .state
+                .compare_exchange_weak(state, state + READ_LOCKED + UPGRADABLE, Acquire, Relaxed)
+                .is_err()
+        {
+            self.upgradable_read_contended();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_read_unlock(&self) {
+        let state =
+            self.state.fetch_sub(READ_LOCKED + UPGRADABLE, Release) - (READ_LOCKED + UPGRADABLE);
+
+        // Unlike a plain reader, we might have taken an unlocked lock while
+        // readers were waiting (see `upgradable_read_contended`), so we need
+        // to wake those up too if we were the last reader.
+        if is_unlocked(state) && (has_writers_waiting(state) || has_readers_waiting(state)) {
+            self.wake_writer_or_readers(state);
+        }
+    }
+
+    #[cold]
+    fn upgradable_read_contended(&self) {
+        let mut state = self.spin_upgradable_read();
+
+        let mut other_writers_waiting = 0;
+
+        loop {
+            // Like a writer, we take an unlocked lock regardless of the waiting
+            // bits, but we only join other readers if nothing is waiting.
+            if is_unlocked(state) || is_upgradable_read_lockable(state) {
+                match self.state.compare_exchange_weak(
+                    state,
+                    (state + READ_LOCKED + UPGRADABLE) | other_writers_waiting,
+                    Acquire,
+                    Relaxed,
+                ) {
+                    Ok(_) => return, // Locked!
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+
+            // Check for overflow.
+            if has_reached_max_readers(state) {
+                panic!("too many active read locks on RwLock");
+            }
+
+            // Upgradable readers wait together with the writers, since they
+            // are excluded by the same things: a write lock or another
+            // upgradable reader. Setting the writers waiting bit also stops
+            // new readers from coming in, so we'll eventually get woken up.
+            if !has_writers_waiting(state) {
+                if let Err(s) =
+                    self.state.compare_exchange(state, state | WRITERS_WAITING, Relaxed, Relaxed)
+                {
+                    state = s;
+                    continue;
+                }
+            }
+
+            // Other writers might be waiting now too, so we should make sure
+            // we keep that bit on once we manage lock it.
+            other_writers_waiting = WRITERS_WAITING;
+
+            // Examine the notification counter before we check if `state` has changed,
+            // to make sure we don't miss any notifications.
+            let seq = self.writer_notify.load(Acquire);
+
+            // Don't go to sleep if the lock has become available,
+            // or if the writers waiting bit is no longer set.
+            state = self.state.load(Relaxed);
+            if is_unlocked(state) || !has_writers_waiting(state) {
+                continue;
+            }
+
+            // Wait for the state to change.
+            futex_wait(&self.writer_notify, seq, None);
+
+            // Spin again after waking up.
+            state = self.spin_upgradable_read();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn try_upgrade(&self) -> bool {
+        self.state
+            .fetch_update(Acquire, Relaxed, |s| {
+                is_upgradable_only(s).then(|| waiting_bits(s) | WRITE_LOCKED)
+            })
+            .is_ok()
+    }
+
+    #[inline]
+    pub unsafe fn upgrade(&self) {
+        if !self.try_upgrade() {
+            self.upgrade_contended();
+        }
+    }
+
+    #[cold]
+    fn upgrade_contended(&self) {
+        let mut state = self.state.load(Relaxed);
+
+        loop {
+            // If we're the only reader left, turn our read lock into a write lock.
+            if is_upgradable_only(state) {
+                match self.state.compare_exchange_weak(
+                    state,
+                    waiting_bits(state) | WRITE_LOCKED,
+                    Acquire,
+                    Relaxed,
+                ) {
+                    Ok(_) => return, // Upgraded!
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+
+            // Make sure the upgrading bit is set before we go to sleep. This
+            // stops new readers from coming in, and makes the last other
+            // reader wake us up.
+            if !is_upgrading(state) {
+                if let Err(s) =
+                    self.state.compare_exchange(state, state | UPGRADING, Relaxed, Relaxed)
+                {
+                    state = s;
+                    continue;
+                }
+            }
+
+            // Wait for the state to change.
+            futex_wait(&self.state, state | UPGRADING, None);
+
+            state = self.state.load(Relaxed);
+        }
+    }
+
+    #[inline]
+    pub unsafe fn downgrade(&self) {
+        // Turn the write lock into a single read lock. If only readers are
+        // waiting, they can now join us, so we clear their bit and wake them up.
+        // If a writer is waiting too, the readers keep waiting, just like they
+        // would for a writer after a regular unlock.
+        let state = self
+            .state
+            .fetch_update(Release, Relaxed, |s| {
+                debug_assert!(is_write_locked(s));
+                let s = s - WRITE_LOCKED + READ_LOCKED;
+                Some(if has_writers_waiting(s) { s } else { s & !READERS_WAITING })
+            })
+            .unwrap();
+
+        if has_readers_waiting(state) && !has_writers_waiting(state) {
+            futex_wake_all(&self.state);
+        }
+    }
+
     /// Wake up waiting threads after unlocking.
     ///
     /// If both are waiting, this will wake up only one writer, but will fall
@@ -286,6 +500,16 @@ fn wake_writer(&self) -> bool {
         // well in case both readers and writers were waiting.
     }
 
+    /// Wake up the upgradable reader after the last other reader has left.
+    ///
+    /// The upgradable reader waits on the same futex as the readers, so this
+    /// wakes them up as well. They'll go back to sleep, as the lock will be
+    /// write locked by then.
+    #[cold]
+    fn wake_upgrader(&self) {
+        futex_wake_all(&self.state);
+    }
+
     /// Spin for a while, but stop directly at the given condition.
     fn spin_until(&self, f: impl Fn(u32) -> bool) -> u32 {
         let mut spin = 100; // Chosen by fair dice roll.
@@ -304,6 +528,15 @@ fn spin_write(&self) -> u32 {
         self.spin_until(|state| is_unlocked(state) || has_writers_waiting(state))
     }
 
+    fn spin_upgradable_read(&self) -> u32 {
+        // Stop spinning when it's unlocked or lockable, or when there's waiting threads.
+        self.spin_until(|state| {
+            (!is_write_locked(state) && !is_upgradable(state))
+                || has_readers_waiting(state)
+                || has_writers_waiting(state)
+        })
+    }
+
     fn spin_read(&self) -> u32 {
         // Stop spinning when it's unlocked or read locked, or when there's waiting threads.
         self.spin_until(|state| {
@@ -1,11 +1,20 @@
 use crate::cell::UnsafeCell;
-use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::sys::locks::Mutex;
 use crate::sys_common::lazy_box::{LazyBox, LazyInit};
 
 pub struct RwLock {
     inner: UnsafeCell<libc::pthread_rwlock_t>,
     write_locked: UnsafeCell<bool>, // guarded by the `inner` RwLock
     num_readers: AtomicUsize,
+    // Held by upgradable readers, to keep out other upgradable readers.
+    upgrade_lock: Mutex,
+    // pthread rwlocks can't be upgraded or downgraded, so we do that by
+    // unlocking and relocking `inner`. Meanwhile, `handoff` is held and
+    // `handing_off` is set, and a writer that gets in between backs off
+    // until the handoff is done.
+    handoff: Mutex,
+    handing_off: AtomicBool,
 }
 
 pub(crate) type MovableRwLock = LazyBox<RwLock>;
@@ -15,7 +24,10 @@ unsafe impl Sync for RwLock {}
 
 impl LazyInit for RwLock {
     fn init() -> Box<Self> {
-        Box::new(Self::new())
+        let mut rwlock = Box::new(Self::new());
+        unsafe { rwlock.upgrade_lock.init() };
+        unsafe { rwlock.handoff.init() };
+        rwlock
     }
 }
 
@@ -25,6 +37,9 @@ pub const fn new() -> RwLock {
             inner: UnsafeCell::new(libc::PTHREAD_RWLOCK_INITIALIZER),
             write_locked: UnsafeCell::new(false),
             num_readers: AtomicUsize::new(0),
+            upgrade_lock: Mutex::new(),
+            handoff: Mutex::new(),
+            handing_off: AtomicBool::new(false),
         }
     }
     #[inline]
@@ -79,6 +94,23 @@ pub unsafe fn try_read(&self) -> bool {
     }
     #[inline]
     pub unsafe fn write(&self) {
+        self.raw_write();
+        // `handing_off` is only changed while `inner` is locked, so we can't
+        // miss a handoff that we got in the middle of.
+        while self.handing_off.load(Ordering::Relaxed) {
+            self.back_off();
+            self.raw_write();
+        }
+    }
+    /// Lets the upgrade or downgrade we got in the middle of finish.
+    #[cold]
+    unsafe fn back_off(&self) {
+        self.write_unlock();
+        self.handoff.lock();
+        self.handoff.unlock();
+    }
+    #[inline]
+    unsafe fn raw_write(&self) {
         let r = libc::pthread_rwlock_wrlock(self.inner.get());
         // See comments above for why we check for EDEADLK and write_locked. For the same reason,
         // we also need to check that there are no readers (tracked in `num_readers`).
@@ -102,6 +134,17 @@ pub unsafe fn write(&self) {
     }
     #[inline]
     pub unsafe fn try_write(&self) -> bool {
+        if !self.raw_try_write() {
+            false
+        } else if self.handing_off.load(Ordering::Relaxed) {
+            self.write_unlock();
+            false
+        } else {
+            true
+        }
+    }
+    #[inline]
+    unsafe fn raw_try_write(&self) -> bool {
         let r = libc::pthread_rwlock_trywrlock(self.inner.get());
         if r == 0 {
             if *self.write_locked.get() || self.num_readers.load(Ordering::Relaxed) != 0 {
@@ -135,6 +178,63 @@ pub unsafe fn write_unlock(&self) {
         self.raw_unlock();
     }
     #[inline]
+    pub unsafe fn upgradable_read(&self) {
+        self.upgrade_lock.lock();
+        self.read();
+    }
+    #[inline]
+    pub unsafe fn try_upgradable_read(&self) -> bool {
+        if !self.upgrade_lock.try_lock() {
+            return false;
+        }
+        if self.try_read() {
+            true
+        } else {
+            self.upgrade_lock.unlock();
+            false
+        }
+    }
+    #[inline]
+    pub unsafe fn upgradable_read_unlock(&self) {
+        self.read_unlock();
+        self.upgrade_lock.unlock();
+    }
+    #[inline]
+    pub unsafe fn upgrade(&self) {
+        self.handoff.lock();
+        self.handing_off.store(true, Ordering::Relaxed);
+        self.read_unlock();
+        self.raw_write();
+        self.handing_off.store(false, Ordering::Relaxed);
+        self.handoff.unlock();
+        self.upgrade_lock.unlock();
+    }
+    #[inline]
+    pub unsafe fn try_upgrade(&self) -> bool {
+        self.handoff.lock();
+        self.handing_off.store(true, Ordering::Relaxed);
+        self.read_unlock();
+        let upgraded = self.raw_try_write();
+        if !upgraded {
+            self.read();
+        }
+        self.handing_off.store(false, Ordering::Relaxed);
+        self.handoff.unlock();
+        if upgraded {
+            self.upgrade_lock.unlock();
+        }
+        upgraded
+    }
+    #[inline]
+    pub unsafe fn downgrade(&self) {
+        self.handoff.lock();
+        self.handing_off.store(true, Ordering::Relaxed);
+        self.write_unlock();
+        self.read();
+        self.handing_off.store(false, Ordering::Relaxed);
+        self.handoff.unlock();
+    }
+    #[inline]
     unsafe fn destroy(&mut self) {
         let r = libc::pthread_rwlock_destroy(self.inner.get());
         // On DragonFly pthread_rwlock_destroy() returns EINVAL if called on a
@@ -7,6 +7,7 @@
 use crate::ffi::{CStr, CString, OsStr, OsString};
 use crate::fmt;
 use crate::io;
+use crate::mem;
 use crate::path::Path;
 use crate::ptr;
 use crate::sys::fd::FileDesc;
@@ -14,6 +15,8 @@
 use crate::sys::pipe::{self, AnonPipe};
 use crate::sys_common::process::{CommandEnv, CommandEnvs};
 use crate::sys_common::IntoInner;
+#[cfg(not(any(target_os = "espidf", target_os = "fuchsia")))]
+use crate::time::{Duration, Instant};
 
 #[cfg(not(target_os = "fuchsia"))]
 use crate::sys::fs::OpenOptions;
@@ -297,6 +300,18 @@ pub fn stderr(&mut self, stderr: Stdio) {
         self.stderr = Some(stderr);
     }
 
+    pub fn replace_stdin(&mut self, stdin: Option<Stdio>) -> Option<Stdio> {
+        mem::replace(&mut self.stdin, stdin)
+    }
+
+    pub fn replace_stdout(&mut self, stdout: Option<Stdio>) -> Option<Stdio> {
+        mem::replace(&mut self.stdout, stdout)
+    }
+
+    pub fn replace_pgroup(&mut self, pgroup: Option<pid_t>) -> Option<pid_t> {
+        mem::replace(&mut self.pgroup, pgroup)
+    }
+
     pub fn env_mut(&mut self) -> &mut CommandEnv {
         &mut self.env
     }
@@ -521,3 +536,29 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.iter.clone()).finish()
     }
 }
+
+/// Calls `try_wait` until it returns a status or the deadline passes, sleeping
+/// for a growing delay in between. This is how processes are waited on with a
+/// timeout when the OS has no way to do it directly.
+#[cfg(not(any(target_os = "espidf", target_os = "fuchsia")))]
+pub fn poll_wait<T>(
+    deadline: Option<Instant>,
+    mut try_wait: impl FnMut() -> io::Result<Option<T>>,
+) -> io::Result<Option<T>> {
+    let mut delay = Duration::from_millis(1);
+    loop {
+        if let Some(status) = try_wait()? {
+            return Ok(Some(status));
+        }
+        let mut sleep = delay;
+        if let Some(deadline) = deadline {
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            sleep = crate::cmp::min(sleep, deadline - now);
+        }
+        crate::thread::sleep(sleep);
+        delay = crate::cmp::min(delay * 2, Duration::from_millis(100));
+    }
+}
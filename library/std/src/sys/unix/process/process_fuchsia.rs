@@ -6,6 +6,7 @@
 
 use crate::sys::process::process_common::*;
 use crate::sys::process::zircon::{zx_handle_t, Handle};
+use crate::time::Duration;
 
 use libc::{c_int, size_t};
 
@@ -230,6 +231,27 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         }
         Ok(Some(ExitStatus(proc_info.return_code)))
     }
+
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        use crate::sys::process::zircon::*;
+
+        // Overflows are rounded up to an infinite timeout.
+        let timeout = zx_duration_t::try_from(timeout.as_nanos()).unwrap_or(ZX_TIME_INFINITE);
+        let status = unsafe {
+            zx_object_wait_one(
+                self.handle.raw(),
+                ZX_TASK_TERMINATED,
+                zx_deadline_after(timeout),
+                ptr::null_mut(),
+            )
+        };
+        match status {
+            // The process has terminated, so this doesn't block.
+            0 => self.wait().map(Some),
+            x if x == ERR_TIMED_OUT => Ok(None),
+            _ => Err(io::Error::from_raw_os_error(status)),
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
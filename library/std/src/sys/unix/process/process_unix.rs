@@ -10,6 +10,7 @@
 
 #[cfg(target_os = "linux")]
 use crate::os::linux::process::PidFd;
+use crate::time::{Duration, Instant};
 
 #[cfg(target_os = "linux")]
 use crate::sys::weak::raw_syscall;
@@ -599,10 +600,71 @@ pub fn kill(&mut self) -> io::Result<()> {
                 "invalid argument: can't kill an exited process",
             ))
         } else {
-            cvt(unsafe { libc::kill(self.pid, libc::SIGKILL) }).map(drop)
+            self.raw_send_signal(libc::SIGKILL)
         }
     }
 
+    #[cfg(target_os = "linux")]
+    pub fn send_signal(&self, signal: c_int) -> io::Result<()> {
+        // See `kill` for why we refuse to signal a process we've waited on.
+        if self.status.is_some() {
+            Err(io::const_io_error!(
+                ErrorKind::InvalidInput,
+                "invalid argument: can't signal an exited process",
+            ))
+        } else {
+            self.raw_send_signal(signal)
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn raw_send_signal(&self, signal: c_int) -> io::Result<()> {
+        cvt(unsafe { libc::kill(self.pid, signal) }).map(drop)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn raw_send_signal(&self, signal: c_int) -> io::Result<()> {
+        use crate::os::unix::io::AsRawFd;
+        use crate::sync::atomic::{AtomicBool, Ordering};
+
+        static HAS_PIDFD_SEND_SIGNAL: AtomicBool = AtomicBool::new(true);
+
+        raw_syscall! {
+            fn pidfd_send_signal(
+                pidfd: c_int,
+                sig: c_int,
+                info: *const libc::siginfo_t,
+                flags: libc::c_uint
+            ) -> c_int
+        }
+
+        // Even if something else reaped our child behind our back and its pid
+        // got reused, a signal sent through the pidfd can only ever reach the
+        // process it was created for.
+        if let Some(pidfd) = &self.pidfd {
+            if HAS_PIDFD_SEND_SIGNAL.load(Ordering::Relaxed) {
+                let res =
+                    cvt(unsafe { pidfd_send_signal(pidfd.as_raw_fd(), signal, ptr::null(), 0) });
+                match res {
+                    Ok(_) => return Ok(()),
+                    // The child was reaped behind our back, so its pid may
+                    // have been reused: `kill` could reach another process.
+                    Err(e) if e.raw_os_error() == Some(libc::ESRCH) => return Err(e),
+                    // Other errors don't come from the child. The syscall might
+                    // be missing, or blocked (e.g. by seccomp, with `ENOSYS` or
+                    // `EPERM`), even though the kernel was able to create the
+                    // pidfd, and `kill` reports the errors it has in common.
+                    Err(e) => {
+                        if e.raw_os_error() == Some(libc::ENOSYS) {
+                            HAS_PIDFD_SEND_SIGNAL.store(false, Ordering::Relaxed)
+                        }
+                    }
+                }
+            }
+        }
+        cvt(unsafe { libc::kill(self.pid, signal) }).map(drop)
+    }
+
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
         use crate::sys::cvt_r;
         if let Some(status) = self.status {
@@ -627,6 +689,84 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    #[cfg(target_os = "linux")]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        use crate::os::unix::io::{AsRawFd, FromRawFd};
+        use crate::sync::atomic::{AtomicBool, Ordering};
+
+        static HAS_PIDFD_OPEN: AtomicBool = AtomicBool::new(true);
+
+        raw_syscall! {
+            fn pidfd_open(pid: pid_t, flags: libc::c_uint) -> c_int
+        }
+
+        if let Some(status) = self.try_wait()? {
+            return Ok(Some(status));
+        }
+
+        // Overflows are rounded up to an infinite timeout (None).
+        let deadline = Instant::now().checked_add(timeout);
+
+        // If no pidfd was created along with the child, try to open one now.
+        // As long as we haven't reaped the child, its pid can't be reused, so
+        // this is just as race-free. Whatever the reason it can't be opened
+        // (e.g. `ENOSYS` or `EPERM` from seccomp, or `EMFILE`), polling works.
+        let mut opened = None;
+        if self.pidfd.is_none() && HAS_PIDFD_OPEN.load(Ordering::Relaxed) {
+            match cvt(unsafe { pidfd_open(self.pid, 0) }) {
+                Ok(fd) => opened = Some(unsafe { sys::fd::FileDesc::from_raw_fd(fd) }),
+                Err(e) => {
+                    if e.raw_os_error() == Some(libc::ENOSYS) {
+                        HAS_PIDFD_OPEN.store(false, Ordering::Relaxed)
+                    }
+                }
+            }
+        }
+
+        let pidfd = match &self.pidfd {
+            Some(pidfd) => Some(pidfd.as_raw_fd()),
+            None => opened.as_ref().map(|fd| fd.as_raw_fd()),
+        };
+        if let Some(pidfd) = pidfd {
+            match wait_pidfd(pidfd, deadline) {
+                Ok(true) => return self.try_wait(),
+                Ok(false) => return Ok(None),
+                // `poll` failing says nothing about the child, so poll `waitpid` instead.
+                Err(_) => {}
+            }
+        }
+        poll_wait(deadline, || self.try_wait())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        poll_wait(Instant::now().checked_add(timeout), || self.try_wait())
+    }
+}
+
+// Waits for the pidfd to become readable, which happens once the process has
+// exited, and returns whether it did before the deadline.
+#[cfg(target_os = "linux")]
+fn wait_pidfd(pidfd: c_int, deadline: Option<Instant>) -> io::Result<bool> {
+    loop {
+        let timeout = match deadline {
+            None => -1,
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                // Round up, so we don't wake up just before the deadline.
+                let ms = (remaining.as_nanos() + 999_999) / 1_000_000;
+                crate::cmp::min(ms, c_int::MAX as u128) as c_int
+            }
+        };
+        let mut pollfd = libc::pollfd { fd: pidfd, events: libc::POLLIN, revents: 0 };
+        match cvt(unsafe { libc::poll(&mut pollfd, 1, timeout) }) {
+            Ok(0) => return Ok(false),
+            Ok(_) => return Ok(true),
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
 }
 
 /// Unix exit statuses
@@ -829,6 +969,10 @@ fn take_pidfd(&mut self) -> io::Result<PidFd> {
             .take()
             .ok_or_else(|| Error::new(ErrorKind::Other, "No pidfd was created."))
     }
+
+    fn send_signal(&self, signal: i32) -> io::Result<()> {
+        self.handle.send_signal(signal)
+    }
 }
 
 #[cfg(test)]
@@ -60,3 +60,35 @@ fn test_command_fork_no_unwind() {
             || signal == libc::SIGSEGV
     );
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_wait_timeout() {
+    use crate::os::linux::process::CommandExt;
+    use crate::time::Duration;
+
+    for create_pidfd in [false, true] {
+        let mut child =
+            Command::new("sleep").arg("1000").create_pidfd(create_pidfd).spawn().unwrap();
+        assert!(child.wait_timeout(Duration::from_millis(10)).unwrap().is_none());
+        child.kill().unwrap();
+        let status = child.wait_timeout(Duration::from_secs(60)).unwrap();
+        assert_eq!(status.and_then(|s| s.signal()), Some(libc::SIGKILL));
+        // Once reaped, the status is returned straight away.
+        assert_eq!(child.wait_timeout(Duration::ZERO).unwrap(), status);
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_send_signal() {
+    use crate::os::linux::process::{ChildExt, CommandExt};
+
+    for create_pidfd in [false, true] {
+        let mut child =
+            Command::new("sleep").arg("1000").create_pidfd(create_pidfd).spawn().unwrap();
+        child.send_signal(libc::SIGTERM).unwrap();
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGTERM));
+        assert!(child.send_signal(libc::SIGTERM).is_err());
+    }
+}
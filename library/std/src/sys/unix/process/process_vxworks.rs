@@ -5,6 +5,7 @@
 use crate::sys::cvt;
 use crate::sys::process::process_common::*;
 use crate::sys_common::thread;
+use crate::time::{Duration, Instant};
 use core::ffi::NonZero_c_int;
 use libc::RTP_ID;
 use libc::{self, c_char, c_int};
@@ -174,6 +175,10 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        poll_wait(Instant::now().checked_add(timeout), || self.try_wait())
+    }
 }
 
 /// Unix exit statuses
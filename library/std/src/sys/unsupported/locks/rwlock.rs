@@ -3,6 +3,7 @@
 pub struct RwLock {
     // This platform has no threads, so we can use a Cell here.
     mode: Cell<isize>,
+    upgradable: Cell<bool>,
 }
 
 pub type MovableRwLock = RwLock;
@@ -12,7 +13,7 @@ unsafe impl Sync for RwLock {} // no threads on this platform
 
 impl RwLock {
     pub const fn new() -> RwLock {
-        RwLock { mode: Cell::new(0) }
+        RwLock { mode: Cell::new(0), upgradable: Cell::new(false) }
     }
 
     #[inline]
@@ -62,4 +63,51 @@ pub unsafe fn read_unlock(&self) {
     pub unsafe fn write_unlock(&self) {
         assert_eq!(self.mode.replace(0), -1);
     }
+
+    #[inline]
+    pub unsafe fn upgradable_read(&self) {
+        if self.upgradable.replace(true) {
+            rtabort!("rwlock locked for upgradable reading");
+        }
+        unsafe { self.read() };
+    }
+
+    #[inline]
+    pub unsafe fn try_upgradable_read(&self) -> bool {
+        if self.upgradable.get() || !unsafe { self.try_read() } {
+            false
+        } else {
+            self.upgradable.set(true);
+            true
+        }
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_read_unlock(&self) {
+        self.upgradable.set(false);
+        unsafe { self.read_unlock() };
+    }
+
+    #[inline]
+    pub unsafe fn upgrade(&self) {
+        if !unsafe { self.try_upgrade() } {
+            rtabort!("rwlock locked for reading")
+        }
+    }
+
+    #[inline]
+    pub unsafe fn try_upgrade(&self) -> bool {
+        if self.mode.get() == 1 {
+            self.mode.set(-1);
+            self.upgradable.set(false);
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline]
+    pub unsafe fn downgrade(&self) {
+        assert_eq!(self.mode.replace(1), -1);
+    }
 }
@@ -550,6 +550,69 @@ pub fn set_permissions(&self, perm: FilePermissions) -> io::Result<()> {
         })?;
         Ok(())
     }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.lock_file(0)?;
+        Ok(())
+    }
+
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.lock_file(c::LOCKFILE_EXCLUSIVE_LOCK)?;
+        Ok(())
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.try_lock_file(0)
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        self.try_lock_file(c::LOCKFILE_EXCLUSIVE_LOCK)
+    }
+
+    fn try_lock_file(&self, flags: c::DWORD) -> io::Result<bool> {
+        match self.lock_file(flags | c::LOCKFILE_FAIL_IMMEDIATELY) {
+            Ok(_) => Ok(true),
+            Err(e)
+                if e.raw_os_error() == Some(c::ERROR_LOCK_VIOLATION as i32)
+                    || e.raw_os_error() == Some(c::ERROR_IO_PENDING as i32) =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Locks the whole file, including the bytes past its end.
+    fn lock_file(&self, flags: c::DWORD) -> io::Result<c::BOOL> {
+        unsafe {
+            let mut overlapped: c::OVERLAPPED = mem::zeroed();
+            cvt(c::LockFileEx(
+                self.handle.as_raw_handle(),
+                flags,
+                0,
+                u32::MAX,
+                u32::MAX,
+                &mut overlapped,
+            ))
+        }
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        // A handle may hold both a shared and an exclusive lock on the same range, and each call
+        // only releases one of them.
+        for _ in 0..2 {
+            let result = cvt(unsafe {
+                c::UnlockFile(self.handle.as_raw_handle(), 0, 0, u32::MAX, u32::MAX)
+            });
+            match result {
+                Ok(_) => {}
+                Err(e) if e.raw_os_error() == Some(c::ERROR_NOT_LOCKED as i32) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
     /// Get only basic file information such as attributes and file times.
     fn basic_info(&self) -> io::Result<c::FILE_BASIC_INFO> {
         unsafe {
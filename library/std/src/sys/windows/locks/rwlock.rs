@@ -1,8 +1,18 @@
 use crate::cell::UnsafeCell;
+use crate::sync::atomic::{AtomicBool, Ordering};
 use crate::sys::c;
+use crate::sys::locks::Mutex;
 
 pub struct RwLock {
     inner: UnsafeCell<c::SRWLOCK>,
+    // Held by upgradable readers, to keep out other upgradable readers.
+    upgrade_lock: Mutex,
+    // SRW locks can't be upgraded or downgraded, so we do that by unlocking
+    // and relocking `inner`. Meanwhile, `handoff` is held and `handing_off`
+    // is set, and a writer that gets in between backs off until the handoff
+    // is done.
+    handoff: Mutex,
+    handing_off: AtomicBool,
 }
 
 pub type MovableRwLock = RwLock;
@@ -12,7 +22,12 @@ unsafe impl Sync for RwLock {}
 
 impl RwLock {
     pub const fn new() -> RwLock {
-        RwLock { inner: UnsafeCell::new(c::SRWLOCK_INIT) }
+        RwLock {
+            inner: UnsafeCell::new(c::SRWLOCK_INIT),
+            upgrade_lock: Mutex::new(),
+            handoff: Mutex::new(),
+            handing_off: AtomicBool::new(false),
+        }
     }
     #[inline]
     pub unsafe fn read(&self) {
@@ -24,11 +39,31 @@ pub unsafe fn try_read(&self) -> bool {
     }
     #[inline]
     pub unsafe fn write(&self) {
-        c::AcquireSRWLockExclusive(self.inner.get())
+        c::AcquireSRWLockExclusive(self.inner.get());
+        // `handing_off` is only changed while `inner` is locked, so we can't
+        // miss a handoff that we got in the middle of.
+        while self.handing_off.load(Ordering::Relaxed) {
+            self.back_off();
+            c::AcquireSRWLockExclusive(self.inner.get());
+        }
+    }
+    /// Lets the upgrade or downgrade we got in the middle of finish.
+    #[cold]
+    unsafe fn back_off(&self) {
+        c::ReleaseSRWLockExclusive(self.inner.get());
+        self.handoff.lock();
+        self.handoff.unlock();
     }
     #[inline]
     pub unsafe fn try_write(&self) -> bool {
-        c::TryAcquireSRWLockExclusive(self.inner.get()) != 0
+        if c::TryAcquireSRWLockExclusive(self.inner.get()) == 0 {
+            false
+        } else if self.handing_off.load(Ordering::Relaxed) {
+            c::ReleaseSRWLockExclusive(self.inner.get());
+            false
+        } else {
+            true
+        }
     }
     #[inline]
     pub unsafe fn read_unlock(&self) {
@@ -38,4 +73,61 @@ pub unsafe fn read_unlock(&self) {
     pub unsafe fn write_unlock(&self) {
         c::ReleaseSRWLockExclusive(self.inner.get())
     }
+    #[inline]
+    pub unsafe fn upgradable_read(&self) {
+        self.upgrade_lock.lock();
+        c::AcquireSRWLockShared(self.inner.get())
+    }
+    #[inline]
+    pub unsafe fn try_upgradable_read(&self) -> bool {
+        if !self.upgrade_lock.try_lock() {
+            return false;
+        }
+        if c::TryAcquireSRWLockShared(self.inner.get()) != 0 {
+            true
+        } else {
+            self.upgrade_lock.unlock();
+            false
+        }
+    }
+    #[inline]
+    pub unsafe fn upgradable_read_unlock(&self) {
+        c::ReleaseSRWLockShared(self.inner.get());
+        self.upgrade_lock.unlock();
+    }
+    #[inline]
+    pub unsafe fn upgrade(&self) {
+        self.handoff.lock();
+        self.handing_off.store(true, Ordering::Relaxed);
+        c::ReleaseSRWLockShared(self.inner.get());
+        c::AcquireSRWLockExclusive(self.inner.get());
+        self.handing_off.store(false, Ordering::Relaxed);
+        self.handoff.unlock();
+        self.upgrade_lock.unlock();
+    }
+    #[inline]
+    pub unsafe fn try_upgrade(&self) -> bool {
+        self.handoff.lock();
+        self.handing_off.store(true, Ordering::Relaxed);
+        c::ReleaseSRWLockShared(self.inner.get());
+        let upgraded = c::TryAcquireSRWLockExclusive(self.inner.get()) != 0;
+        if !upgraded {
+            c::AcquireSRWLockShared(self.inner.get());
+        }
+        self.handing_off.store(false, Ordering::Relaxed);
+        self.handoff.unlock();
+        if upgraded {
+            self.upgrade_lock.unlock();
+        }
+        upgraded
+    }
+    #[inline]
+    pub unsafe fn downgrade(&self) {
+        self.handoff.lock();
+        self.handing_off.store(true, Ordering::Relaxed);
+        c::ReleaseSRWLockExclusive(self.inner.get());
+        c::AcquireSRWLockShared(self.inner.get());
+        self.handing_off.store(false, Ordering::Relaxed);
+        self.handoff.unlock();
+    }
 }
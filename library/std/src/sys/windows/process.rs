@@ -28,6 +28,7 @@
 use crate::sys_common::mutex::StaticMutex;
 use crate::sys_common::process::{CommandEnv, CommandEnvs};
 use crate::sys_common::IntoInner;
+use crate::time::Duration;
 
 use libc::{c_void, EXIT_FAILURE, EXIT_SUCCESS};
 
@@ -216,6 +217,12 @@ pub fn stdout(&mut self, stdout: Stdio) {
     pub fn stderr(&mut self, stderr: Stdio) {
         self.stderr = Some(stderr);
     }
+    pub fn replace_stdin(&mut self, stdin: Option<Stdio>) -> Option<Stdio> {
+        mem::replace(&mut self.stdin, stdin)
+    }
+    pub fn replace_stdout(&mut self, stdout: Option<Stdio>) -> Option<Stdio> {
+        mem::replace(&mut self.stdout, stdout)
+    }
     pub fn creation_flags(&mut self, flags: u32) {
         self.flags = flags;
     }
@@ -613,8 +620,13 @@ pub fn wait(&mut self) -> io::Result<ExitStatus> {
     }
 
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        self.wait_timeout(Duration::ZERO)
+    }
+
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
         unsafe {
-            match c::WaitForSingleObject(self.handle.as_raw_handle(), 0) {
+            let timeout = super::dur2timeout(timeout);
+            match c::WaitForSingleObject(self.handle.as_raw_handle(), timeout) {
                 c::WAIT_OBJECT_0 => {}
                 c::WAIT_TIMEOUT => {
                     return Ok(None);
@@ -125,4 +125,67 @@ pub unsafe fn read_unlock(&self) {
     pub unsafe fn write_unlock(&self) {
         self.0.write_unlock()
     }
+
+    /// Acquires upgradable shared access to the underlying lock, blocking the
+    /// current thread to do so.
+    ///
+    /// Upgradable shared access is compatible with regular shared access, but
+    /// not with other upgradable shared access.
+    #[inline]
+    pub fn upgradable_read(&self) {
+        unsafe { self.0.upgradable_read() }
+    }
+
+    /// Attempts to acquire upgradable shared access to this lock, returning
+    /// whether it succeeded or not.
+    ///
+    /// This function does not block the current thread.
+    #[inline]
+    pub fn try_upgradable_read(&self) -> bool {
+        unsafe { self.0.try_upgradable_read() }
+    }
+
+    /// Unlocks previously acquired upgradable shared access to this lock.
+    ///
+    /// Behavior is undefined if the current thread does not have upgradable
+    /// shared access.
+    #[inline]
+    pub unsafe fn upgradable_read_unlock(&self) {
+        self.0.upgradable_read_unlock()
+    }
+
+    /// Turns upgradable shared access into exclusive access, blocking the
+    /// current thread until all other readers have left.
+    ///
+    /// No other writer can acquire the lock in the meantime.
+    ///
+    /// Behavior is undefined if the current thread does not have upgradable
+    /// shared access.
+    #[inline]
+    pub unsafe fn upgrade(&self) {
+        self.0.upgrade()
+    }
+
+    /// Attempts to turn upgradable shared access into exclusive access,
+    /// returning whether it succeeded or not. On failure, the current thread
+    /// keeps its upgradable shared access.
+    ///
+    /// This function does not block the current thread.
+    ///
+    /// Behavior is undefined if the current thread does not have upgradable
+    /// shared access.
+    #[inline]
+    pub unsafe fn try_upgrade(&self) -> bool {
+        self.0.try_upgrade()
+    }
+
+    /// Turns exclusive access into shared access, without letting any other
+    /// writer acquire the lock in between.
+    ///
+    /// Behavior is undefined if the current thread does not currently have
+    /// exclusive access.
+    #[inline]
+    pub unsafe fn downgrade(&self) {
+        self.0.downgrade()
+    }
 }
@@ -0,0 +1,480 @@
+//! Saving benchmark results as named baselines and comparing later runs
+//! against them, for the `--save-baseline` and `--baseline` options.
+//!
+//! A baseline is a JSON file holding the full `Summary` of every benchmark,
+//! keyed by the benchmark name. Comparisons use Welch's t-test on the means
+//! of the samples to decide whether a change of the median is significant.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use super::bench::{fmt_thousands_sep, BenchSamples, SAMPLE_COUNT};
+use super::formatters::EscapedString;
+use super::stats::Summary;
+use super::types::TestDesc;
+
+#[cfg(test)]
+mod tests;
+
+/// Environment variable overriding the directory the baselines are stored in.
+pub const BASELINE_DIR_ENV_NAME: &str = "RUST_TEST_BASELINE_DIR";
+
+/// Significance level below which a change is not attributed to noise.
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// Relative change of the median below which a change is ignored, even if
+/// it is statistically significant.
+const NOISE_THRESHOLD: f64 = 0.02;
+
+/// Result of comparing a benchmark against its baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchComparison {
+    pub name: String,
+    pub baseline_median: f64,
+    pub median: f64,
+    /// Relative change of the median, `0.1` meaning 10% slower.
+    pub change: f64,
+    /// Two-sided p-value of the difference between the means.
+    pub p_value: f64,
+    pub verdict: BenchVerdict,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BenchVerdict {
+    Regressed,
+    Improved,
+    NoChange,
+}
+
+impl BenchVerdict {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BenchVerdict::Regressed => "regressed",
+            BenchVerdict::Improved => "improved",
+            BenchVerdict::NoChange => "no change",
+        }
+    }
+}
+
+/// A benchmark result as stored in a baseline.
+#[derive(Debug, Clone, PartialEq)]
+struct BaselineEntry {
+    summary: Summary,
+    samples: usize,
+    mb_s: usize,
+}
+
+type Baseline = BTreeMap<String, BaselineEntry>;
+
+pub fn fmt_bench_comparison(comparison: &BenchComparison) -> String {
+    format!(
+        "{}: {} -> {} ns/iter ({:+.2}%, p = {:.3}): {}",
+        comparison.name,
+        fmt_thousands_sep(comparison.baseline_median as usize, ','),
+        fmt_thousands_sep(comparison.median as usize, ','),
+        comparison.change * 100.0,
+        comparison.p_value,
+        comparison.verdict.as_str()
+    )
+}
+
+/// Checks that a baseline name can be used as a file name.
+pub fn validate_baseline_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "baseline name `{name}` must only contain ASCII letters, digits, `-`, `_` and `.`, \
+             and must not start with `.`"
+        ))
+    }
+}
+
+/// Returns the file the baseline with the given name is stored in.
+///
+/// Unless overridden with `RUST_TEST_BASELINE_DIR`, baselines are stored in a
+/// directory next to the test executable, i.e. inside the target directory
+/// when run by cargo.
+fn get_baseline_file(name: &str) -> io::Result<PathBuf> {
+    let dir = match env::var_os(BASELINE_DIR_ENV_NAME) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let exe = env::current_exe()?;
+            let mut dir_name = exe.file_name().unwrap_or_default().to_owned();
+            dir_name.push(".baselines");
+            exe.with_file_name(dir_name)
+        }
+    };
+    Ok(dir.join(format!("{name}.json")))
+}
+
+/// Reads the baseline with the given name, which is empty if it doesn't exist.
+fn read_baseline(name: &str) -> io::Result<Baseline> {
+    let path = get_baseline_file(name)?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Baseline::new()),
+        Err(e) => return Err(e),
+    };
+    parse_baseline(&contents).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed benchmark baseline {}: {e}", path.display()),
+        )
+    })
+}
+
+/// Stores the results of the benchmarks that ran in the baseline with the
+/// given name, keeping the results of the benchmarks that didn't run.
+pub fn save_baseline(name: &str, results: &[(TestDesc, BenchSamples)]) -> io::Result<()> {
+    let mut baseline = read_baseline(name)?;
+    for (desc, bs) in results {
+        let entry =
+            BaselineEntry { summary: bs.ns_iter_summ, samples: SAMPLE_COUNT, mb_s: bs.mb_s };
+        baseline.insert(desc.name.as_slice().to_owned(), entry);
+    }
+
+    let path = get_baseline_file(name)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, format_baseline(&baseline))
+}
+
+/// Compares the benchmarks that ran against the baseline with the given name.
+/// Benchmarks missing from the baseline, and benchmarks that never called
+/// `Bencher::iter`, are skipped.
+pub fn compare_with_baseline(
+    name: &str,
+    results: &[(TestDesc, BenchSamples)],
+) -> io::Result<Vec<BenchComparison>> {
+    let baseline = read_baseline(name)?;
+    let mut comparisons: Vec<_> = results
+        .iter()
+        .filter(|(_, bs)| bs.ns_iter_summ.median > 0.0)
+        .filter_map(|(desc, bs)| {
+            let entry = baseline.get(desc.name.as_slice())?;
+            Some(compare(desc.name.as_slice(), entry, &bs.ns_iter_summ, SAMPLE_COUNT))
+        })
+        .collect();
+    comparisons.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(comparisons)
+}
+
+fn compare(name: &str, old: &BaselineEntry, new: &Summary, samples: usize) -> BenchComparison {
+    let change = (new.median - old.summary.median) / old.summary.median;
+    let p_value = welch_t_test(&old.summary, old.samples, new, samples);
+
+    let verdict = if p_value >= SIGNIFICANCE_LEVEL || change.abs() < NOISE_THRESHOLD {
+        BenchVerdict::NoChange
+    } else if change > 0.0 {
+        BenchVerdict::Regressed
+    } else {
+        BenchVerdict::Improved
+    };
+
+    BenchComparison {
+        name: name.to_owned(),
+        baseline_median: old.summary.median,
+        median: new.median,
+        change,
+        p_value,
+        verdict,
+    }
+}
+
+/// Two-sided p-value of Welch's t-test for the difference between the means
+/// of two sample sets.
+///
+/// The t distribution is approximated by the normal distribution, which is
+/// accurate enough for the degrees of freedom of benchmark samples.
+fn welch_t_test(a: &Summary, a_samples: usize, b: &Summary, b_samples: usize) -> f64 {
+    let standard_error = (a.var / a_samples as f64 + b.var / b_samples as f64).sqrt();
+    if !(standard_error > 0.0) {
+        return if a.mean == b.mean { 1.0 } else { 0.0 };
+    }
+    let t = (b.mean - a.mean) / standard_error;
+    erfc(t.abs() / std::f64::consts::SQRT_2)
+}
+
+/// Complementary error function, with a fractional error below 1.2e-7.
+///
+/// See "Numerical Recipes in C", 2nd edition, section 6.2.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let r = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+fn format_baseline(baseline: &Baseline) -> String {
+    // JSON has no representation for NaN and infinities.
+    fn number(n: f64) -> String {
+        if n.is_finite() {
+            n.to_string()
+        } else {
+            "null".to_owned()
+        }
+    }
+
+    let mut out = String::from("{\n");
+    for (i, (name, entry)) in baseline.iter().enumerate() {
+        let s = &entry.summary;
+        write!(
+            out,
+            "  \"{}\": {{ \"samples\": {}, \"mb_s\": {}, \"sum\": {}, \"min\": {}, \"max\": {}, \
+             \"mean\": {}, \"median\": {}, \"var\": {}, \"std_dev\": {}, \"std_dev_pct\": {}, \
+             \"median_abs_dev\": {}, \"median_abs_dev_pct\": {}, \"quartiles\": [{}, {}, {}], \
+             \"iqr\": {} }}",
+            EscapedString(name),
+            entry.samples,
+            entry.mb_s,
+            number(s.sum),
+            number(s.min),
+            number(s.max),
+            number(s.mean),
+            number(s.median),
+            number(s.var),
+            number(s.std_dev),
+            number(s.std_dev_pct),
+            number(s.median_abs_dev),
+            number(s.median_abs_dev_pct),
+            number(s.quartiles.0),
+            number(s.quartiles.1),
+            number(s.quartiles.2),
+            number(s.iqr),
+        )
+        .unwrap();
+        out.push_str(if i + 1 == baseline.len() { "\n" } else { ",\n" });
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn parse_baseline(s: &str) -> Result<Baseline, String> {
+    let mut parser = JsonParser { s, pos: 0 };
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.pos != s.len() {
+        return Err(format!("trailing characters at offset {}", parser.pos));
+    }
+
+    let benches = match value {
+        JsonValue::Object(benches) => benches,
+        _ => return Err("expected an object of benchmarks".to_owned()),
+    };
+    let mut baseline = Baseline::new();
+    for (name, bench) in benches {
+        let fields = match bench {
+            JsonValue::Object(fields) => fields,
+            _ => return Err(format!("expected an object for benchmark `{name}`")),
+        };
+        let field = |key: &str| -> Result<&JsonValue, String> {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v)
+                .ok_or_else(|| format!("missing `{key}` for benchmark `{name}`"))
+        };
+        let number = |key: &str| -> Result<f64, String> {
+            field(key)?.as_f64().ok_or_else(|| format!("expected a number for `{key}`"))
+        };
+        let quartiles = match field("quartiles")? {
+            JsonValue::Array(q) if q.len() == 3 => {
+                let q = |i: usize| q[i].as_f64().ok_or("expected numbers for `quartiles`");
+                (q(0)?, q(1)?, q(2)?)
+            }
+            _ => return Err("expected an array of 3 numbers for `quartiles`".to_owned()),
+        };
+        let summary = Summary {
+            sum: number("sum")?,
+            min: number("min")?,
+            max: number("max")?,
+            mean: number("mean")?,
+            median: number("median")?,
+            var: number("var")?,
+            std_dev: number("std_dev")?,
+            std_dev_pct: number("std_dev_pct")?,
+            median_abs_dev: number("median_abs_dev")?,
+            median_abs_dev_pct: number("median_abs_dev_pct")?,
+            quartiles,
+            iqr: number("iqr")?,
+        };
+        let entry = BaselineEntry {
+            summary,
+            samples: number("samples")? as usize,
+            mb_s: number("mb_s")? as usize,
+        };
+        baseline.insert(name, entry);
+    }
+    Ok(baseline)
+}
+
+enum JsonValue {
+    Null,
+    Number(f64),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the value of a number, `null` standing for NaN.
+    fn as_f64(&self) -> Option<f64> {
+        match *self {
+            JsonValue::Number(n) => Some(n),
+            JsonValue::Null => Some(f64::NAN),
+            _ => None,
+        }
+    }
+}
+
+/// A minimal JSON parser, sufficient for reading back the baselines written
+/// by `format_baseline`: strings are only supported as object keys, and
+/// booleans not at all.
+struct JsonParser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.s[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("expected `{}` at offset {}", c as char, self.pos))
+        }
+    }
+
+    fn eat_null(&mut self) -> bool {
+        if self.s[self.pos..].starts_with("null") {
+            self.pos += "null".len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.parse_string()?;
+                    self.expect(b':')?;
+                    fields.push((key, self.parse_value()?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        _ => break,
+                    }
+                }
+                self.expect(b'}')?;
+                Ok(JsonValue::Object(fields))
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                loop {
+                    items.push(self.parse_value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        _ => break,
+                    }
+                }
+                self.expect(b']')?;
+                Ok(JsonValue::Array(items))
+            }
+            _ if self.eat_null() => Ok(JsonValue::Null),
+            _ => {
+                let rest = &self.s[self.pos..];
+                let len = rest
+                    .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+                    .unwrap_or(rest.len());
+                let n = rest[..len]
+                    .parse()
+                    .map_err(|_| format!("expected a value at offset {}", self.pos))?;
+                self.pos += len;
+                Ok(JsonValue::Number(n))
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        let mut chars = self.s[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    Some('/') => out.push('/'),
+                    Some('b') => out.push('\u{8}'),
+                    Some('f') => out.push('\u{c}'),
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let c = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("invalid escape `\\u{hex}`"))?;
+                        out.push(c);
+                    }
+                    _ => return Err(format!("invalid escape in string at offset {}", self.pos)),
+                },
+                c => out.push(c),
+            }
+        }
+        Err("unterminated string".to_owned())
+    }
+}
@@ -0,0 +1,78 @@
+use super::*;
+
+fn summary(samples: &[f64]) -> Summary {
+    Summary::new(samples)
+}
+
+fn entry(samples: &[f64]) -> BaselineEntry {
+    BaselineEntry { summary: summary(samples), samples: samples.len(), mb_s: 0 }
+}
+
+#[test]
+fn baseline_roundtrip() {
+    let mut baseline = Baseline::new();
+    baseline.insert("bench::a".to_owned(), entry(&[1.0, 2.0, 3.0, 4.0]));
+    baseline.insert("\"quoted\" \\ bench".to_owned(), entry(&[10.5, 10.75, 11.0]));
+    // A single sample has no deviation percentages, which JSON stores as null.
+    baseline.insert("bench::single".to_owned(), entry(&[0.0]));
+
+    let parsed = parse_baseline(&format_baseline(&baseline)).unwrap();
+    assert_eq!(parsed.len(), 3);
+    for (name, entry) in &baseline {
+        let parsed_entry = &parsed[name];
+        assert_eq!(parsed_entry.samples, entry.samples);
+        assert_eq!(parsed_entry.summary.median, entry.summary.median);
+        assert_eq!(parsed_entry.summary.quartiles, entry.summary.quartiles);
+        assert_eq!(
+            parsed_entry.summary.std_dev_pct.is_nan(),
+            entry.summary.std_dev_pct.is_nan()
+        );
+    }
+}
+
+#[test]
+fn malformed_baseline() {
+    assert!(parse_baseline("").is_err());
+    assert!(parse_baseline("[]").is_err());
+    assert!(parse_baseline("{ \"a\": { \"samples\": 1 } }").is_err());
+    assert!(parse_baseline("{} trailing").is_err());
+    assert_eq!(parse_baseline("{}").unwrap().len(), 0);
+}
+
+#[test]
+fn baseline_names() {
+    assert!(validate_baseline_name("main").is_ok());
+    assert!(validate_baseline_name("v1.2_rc-3").is_ok());
+    assert!(validate_baseline_name("").is_err());
+    assert!(validate_baseline_name("..").is_err());
+    assert!(validate_baseline_name("a/b").is_err());
+}
+
+#[test]
+fn erfc_values() {
+    let assert_approx_eq = |a: f64, b: f64| assert!((a - b).abs() < 1.0e-6, "{a} != {b}");
+    assert_approx_eq(erfc(0.0), 1.0);
+    assert_approx_eq(erfc(1.0), 0.157299207);
+    assert_approx_eq(erfc(-1.0), 1.842700793);
+    assert_approx_eq(erfc(3.0), 0.000022090);
+}
+
+#[test]
+fn compare_verdicts() {
+    let old: Vec<f64> = (0..50).map(|i| 100.0 + (i % 5) as f64).collect();
+    let same: Vec<f64> = (0..50).map(|i| 100.0 + ((i + 2) % 5) as f64).collect();
+    let slower: Vec<f64> = old.iter().map(|x| x * 1.2).collect();
+    let faster: Vec<f64> = old.iter().map(|x| x * 0.8).collect();
+    // Significant, but below the noise threshold.
+    let barely_slower: Vec<f64> = old.iter().map(|x| x + 1.0).collect();
+
+    let verdict = |new: &[f64]| compare("b", &entry(&old), &summary(new), new.len()).verdict;
+    assert_eq!(verdict(&same), BenchVerdict::NoChange);
+    assert_eq!(verdict(&slower), BenchVerdict::Regressed);
+    assert_eq!(verdict(&faster), BenchVerdict::Improved);
+    assert_eq!(verdict(&barely_slower), BenchVerdict::NoChange);
+
+    let comparison = compare("b", &entry(&old), &summary(&slower), slower.len());
+    assert!((comparison.change - 0.2).abs() < 1.0e-9);
+    assert!(comparison.p_value < 1.0e-6);
+}
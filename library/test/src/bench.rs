@@ -58,6 +58,9 @@ pub fn bench<F>(&mut self, mut f: F) -> Option<stats::Summary>
     }
 }
 
+/// Number of samples each benchmark `Summary` is computed from.
+pub(crate) const SAMPLE_COUNT: usize = 50;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BenchSamples {
     pub ns_iter_summ: stats::Summary,
@@ -85,7 +88,7 @@ pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
 }
 
 // Format a number with thousands separators
-fn fmt_thousands_sep(mut n: usize, sep: char) -> String {
+pub(crate) fn fmt_thousands_sep(mut n: usize, sep: char) -> String {
     use std::fmt::Write;
     let mut output = String::new();
     let mut trailing = false;
@@ -139,7 +142,7 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
     n = cmp::max(1, n);
 
     let mut total_run = Duration::new(0, 0);
-    let samples: &mut [f64] = &mut [0.0_f64; 50];
+    let samples: &mut [f64] = &mut [0.0_f64; SAMPLE_COUNT];
     loop {
         let loop_start = Instant::now();
 
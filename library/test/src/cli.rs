@@ -2,9 +2,12 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use super::baseline::validate_baseline_name;
 use super::helpers::isatty;
-use super::options::{ColorConfig, Options, OutputFormat, RunIgnored};
+use super::helpers::rerun::get_rerun_file;
+use super::options::{ColorConfig, Options, OutputFormat, RunIgnored, Shard};
 use super::time::TestTimeOptions;
 
 #[derive(Debug)]
@@ -17,15 +20,25 @@ pub struct TestOpts {
     pub run_ignored: RunIgnored,
     pub run_tests: bool,
     pub bench_benchmarks: bool,
+    /// Name of the baseline to store the benchmark results in.
+    pub save_baseline: Option<String>,
+    /// Name of the baseline to compare the benchmark results against.
+    pub baseline: Option<String>,
     pub logfile: Option<PathBuf>,
     pub nocapture: bool,
     pub color: ColorConfig,
     pub format: OutputFormat,
     pub shuffle: bool,
     pub shuffle_seed: Option<u64>,
+    pub shard: Option<Shard>,
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
     pub time_options: Option<TestTimeOptions>,
+    /// Time after which a running test is considered hung and fails.
+    pub test_timeout: Option<Duration>,
+    /// File recording the tests that failed in the previous run, set when only
+    /// those should be run again.
+    pub rerun_failed: Option<PathBuf>,
     pub options: Options,
 }
 
@@ -53,6 +66,20 @@ fn optgroups() -> getopts::Options {
         .optflag("", "test", "Run tests and not benchmarks")
         .optflag("", "bench", "Run benchmarks instead of tests")
         .optflag("", "list", "List all tests and benchmarks")
+        .optopt(
+            "",
+            "save-baseline",
+            "Save the benchmark results as the baseline NAME, to compare later
+            runs against with --baseline",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline",
+            "Compare the benchmark results against the baseline NAME and fail
+            if any benchmark regressed significantly",
+            "NAME",
+        )
         .optflag("h", "help", "Display this message")
         .optopt("", "logfile", "Write logs to the specified file", "PATH")
         .optflag(
@@ -98,8 +125,9 @@ fn optgroups() -> getopts::Options {
             pretty = Print verbose output;
             terse  = Display one character per test;
             json   = Output a json document;
-            junit  = Output a JUnit document",
-            "pretty|terse|json|junit",
+            junit  = Output a JUnit document;
+            tap    = Output a TAP (Test Anything Protocol) stream",
+            "pretty|terse|json|junit|tap",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -138,12 +166,49 @@ fn optgroups() -> getopts::Options {
             `CRITICAL_TIME` here means the limit that should not be exceeded by test.
             ",
         )
+        .optopt(
+            "",
+            "test-timeout",
+            "Fail tests that run for longer than SECONDS.
+
+            Tests running in a subprocess (with `-Z panic-abort-tests`) are killed
+            and reported as failed. Tests running in-process cannot be stopped, so
+            they are reported as failed and the whole test run is aborted.
+
+            The timeout can also be set with the `RUST_TEST_TIMEOUT` environment
+            variable.",
+            "SECONDS",
+        )
+        .optflag(
+            "",
+            "rerun-failed",
+            "Only run the tests that failed the last time this flag was given.
+
+            The names of the failed tests are recorded in a file next to the test
+            executable, or in the file given by the `RUST_TEST_RERUN_FILE`
+            environment variable. If no test failed, the record is removed and
+            the next run executes all tests again.",
+        )
         .optflag("", "shuffle", "Run tests in random order")
         .optopt(
             "",
             "shuffle-seed",
             "Run tests in random order; seed the random number generator with SEED",
             "SEED",
+        )
+        .optopt(
+            "",
+            "shard-index",
+            "Only run the tests of shard INDEX (starting at 0) out of the
+            number of shards given by --shard-count",
+            "INDEX",
+        )
+        .optopt(
+            "",
+            "shard-count",
+            "Split the tests into COUNT disjoint shards, assigned by hashing
+            the test names; requires --shard-index",
+            "COUNT",
         );
     opts
 }
@@ -254,8 +319,13 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
     let force_run_in_process = unstable_optflag!(matches, allow_unstable, "force-run-in-process");
     let exclude_should_panic = unstable_optflag!(matches, allow_unstable, "exclude-should-panic");
     let time_options = get_time_options(&matches, allow_unstable)?;
+    let test_timeout = get_test_timeout(&matches, allow_unstable)?;
+    let rerun_failed = get_rerun_failed(&matches, allow_unstable)?;
     let shuffle = get_shuffle(&matches, allow_unstable)?;
     let shuffle_seed = get_shuffle_seed(&matches, allow_unstable)?;
+    let shard = get_shard(&matches, allow_unstable)?;
+    let save_baseline = get_baseline(unstable_optopt!(matches, allow_unstable, "save-baseline"))?;
+    let baseline = get_baseline(unstable_optopt!(matches, allow_unstable, "baseline"))?;
 
     let include_ignored = matches.opt_present("include-ignored");
     let quiet = matches.opt_present("quiet");
@@ -285,15 +355,20 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         run_ignored,
         run_tests,
         bench_benchmarks,
+        save_baseline,
+        baseline,
         logfile,
         nocapture,
         color,
         format,
         shuffle,
         shuffle_seed,
+        shard,
         test_threads,
         skip,
         time_options,
+        test_timeout,
+        rerun_failed,
         options,
     };
 
@@ -329,6 +404,50 @@ fn get_time_options(
     Ok(options)
 }
 
+fn get_test_timeout(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<Duration>> {
+    let mut test_timeout = match unstable_optopt!(matches, allow_unstable, "test-timeout") {
+        Some(n_str) => match n_str.parse::<u64>() {
+            Ok(0) => return Err("argument for --test-timeout must not be 0".to_string()),
+            Ok(n) => Some(Duration::from_secs(n)),
+            Err(e) => {
+                return Err(format!(
+                    "argument for --test-timeout must be a number > 0 \
+                     (error: {})",
+                    e
+                ));
+            }
+        },
+        None => None,
+    };
+
+    if test_timeout.is_none() && allow_unstable {
+        test_timeout = match env::var("RUST_TEST_TIMEOUT") {
+            Ok(val) => match val.parse::<u64>() {
+                Ok(n) if n > 0 => Some(Duration::from_secs(n)),
+                _ => panic!("RUST_TEST_TIMEOUT is `{val}`, should be a number > 0."),
+            },
+            Err(_) => None,
+        };
+    }
+
+    Ok(test_timeout)
+}
+
+fn get_rerun_failed(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<PathBuf>> {
+    let rerun_failed = unstable_optflag!(matches, allow_unstable, "rerun-failed");
+    if !rerun_failed {
+        return Ok(None);
+    }
+
+    get_rerun_file().map(Some)
+}
+
 fn get_shuffle(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<bool> {
     let mut shuffle = unstable_optflag!(matches, allow_unstable, "shuffle");
     if !shuffle && allow_unstable {
@@ -369,6 +488,45 @@ fn get_shuffle_seed(matches: &getopts::Matches, allow_unstable: bool) -> OptPart
     Ok(shuffle_seed)
 }
 
+fn get_shard(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<Option<Shard>> {
+    let parse = |option_name: &str, value: Option<String>| -> OptPartRes<Option<usize>> {
+        match value.map(|n_str| n_str.parse::<usize>()) {
+            Some(Ok(n)) => Ok(Some(n)),
+            Some(Err(e)) => {
+                Err(format!("argument for --{option_name} must be a number (error: {e})"))
+            }
+            None => Ok(None),
+        }
+    };
+    let index = parse("shard-index", unstable_optopt!(matches, allow_unstable, "shard-index"))?;
+    let count = parse("shard-count", unstable_optopt!(matches, allow_unstable, "shard-count"))?;
+
+    let shard = match (index, count) {
+        (Some(_), Some(0)) => return Err("argument for --shard-count must not be 0".to_string()),
+        (Some(index), Some(count)) if index >= count => {
+            return Err(format!(
+                "argument for --shard-index must be less than --shard-count \
+                 (was {index}, with {count} shards)"
+            ));
+        }
+        (Some(index), Some(count)) => Some(Shard { index, count }),
+        (None, None) => None,
+        _ => {
+            return Err("the options --shard-index and --shard-count must be used together".into());
+        }
+    };
+
+    Ok(shard)
+}
+
+fn get_baseline(name: Option<String>) -> OptPartRes<Option<String>> {
+    if let Some(ref name) = name {
+        validate_baseline_name(name)?;
+    }
+
+    Ok(name)
+}
+
 fn get_test_threads(matches: &getopts::Matches) -> OptPartRes<Option<usize>> {
     let test_threads = match matches.opt_str("test-threads") {
         Some(n_str) => match n_str.parse::<usize>() {
@@ -409,9 +567,15 @@ fn get_format(
             }
             OutputFormat::Junit
         }
+        Some("tap") => {
+            if !allow_unstable {
+                return Err("The \"tap\" format is only accepted on the nightly compiler".into());
+            }
+            OutputFormat::Tap
+        }
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, json or junit (was \
+                "argument for --format must be pretty, terse, json, junit or tap (was \
                  {})",
                 v
             ));
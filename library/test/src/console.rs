@@ -1,17 +1,26 @@
 //! Module providing interface for running tests in the console.
 
+use std::collections::HashSet;
 use std::fs::File;
 use std::io;
 use std::io::prelude::Write;
 use std::time::Instant;
 
 use super::{
-    bench::fmt_bench_samples,
+    baseline::{compare_with_baseline, save_baseline, BenchVerdict},
+    bench::{fmt_bench_samples, BenchSamples},
     cli::TestOpts,
     event::{CompletedTest, TestEvent},
     filter_tests,
-    formatters::{JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter},
-    helpers::{concurrency::get_concurrency, metrics::MetricMap},
+    formatters::{
+        JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TapFormatter,
+        TerseFormatter,
+    },
+    helpers::{
+        concurrency::get_concurrency,
+        metrics::MetricMap,
+        rerun::{retain_failed_tests, write_failed_tests},
+    },
     options::{Options, OutputFormat},
     run_tests, term,
     test_result::TestResult,
@@ -54,6 +63,7 @@ pub struct ConsoleTestState {
     pub failures: Vec<(TestDesc, Vec<u8>)>,
     pub not_failures: Vec<(TestDesc, Vec<u8>)>,
     pub time_failures: Vec<(TestDesc, Vec<u8>)>,
+    pub bench_results: Vec<(TestDesc, BenchSamples)>,
     pub options: Options,
 }
 
@@ -77,6 +87,7 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             failures: Vec::new(),
             not_failures: Vec::new(),
             time_failures: Vec::new(),
+            bench_results: Vec::new(),
             options: opts.options,
         })
     }
@@ -147,7 +158,12 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
     let mut ntest = 0;
     let mut nbench = 0;
 
-    for test in filter_tests(&opts, tests) {
+    let mut filtered_tests = filter_tests(&opts, tests);
+    if let Some(ref rerun_file) = opts.rerun_failed {
+        retain_failed_tests(rerun_file, &mut filtered_tests)?;
+    }
+
+    for test in filtered_tests {
         use crate::TestFn::*;
 
         let TestDescAndFn { desc: TestDesc { name, .. }, testfn } = test;
@@ -201,6 +217,7 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
                 bs.ns_iter_summ.median,
                 bs.ns_iter_summ.max - bs.ns_iter_summ.min,
             );
+            st.bench_results.push((test, bs));
             st.measured += 1
         }
         TestResult::TrFailed => {
@@ -247,6 +264,9 @@ fn on_test_event(
             out.write_result(test, result, exec_time.as_ref(), &*stdout, st)?;
             handle_test_result(st, completed_test);
         }
+        TestEvent::TeAborted => {
+            out.write_run_finish(st)?;
+        }
     }
 
     Ok(())
@@ -281,6 +301,7 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         }
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
         OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
+        OutputFormat::Tap => Box::new(TapFormatter::new(output)),
     };
     let mut st = ConsoleTestState::new(opts)?;
 
@@ -289,13 +310,32 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
     // - We disable it for miri because it's not available when isolation is enabled.
     let is_instant_supported = !cfg!(target_family = "wasm") && !cfg!(miri);
 
+    let all_tests: HashSet<_> = tests.iter().map(|test| test.desc.name.to_string()).collect();
+
     let start_time = is_instant_supported.then(Instant::now);
     run_tests(opts, tests, |x| on_test_event(&x, &mut st, &mut *out))?;
     st.exec_time = start_time.map(|t| TestSuiteExecTime(t.elapsed()));
 
     assert!(st.current_test_count() == st.total);
 
-    out.write_run_finish(&st)
+    if let Some(ref rerun_file) = opts.rerun_failed {
+        write_failed_tests(rerun_file, &st, &all_tests)?;
+    }
+
+    let mut regressed = false;
+    if let Some(ref baseline) = opts.baseline {
+        let comparisons = compare_with_baseline(baseline, &st.bench_results)?;
+        if !comparisons.is_empty() {
+            out.write_bench_comparisons(&comparisons)?;
+        }
+        regressed = comparisons.iter().any(|c| c.verdict == BenchVerdict::Regressed);
+    }
+    if let Some(ref baseline) = opts.save_baseline {
+        save_baseline(baseline, &st.bench_results)?;
+    }
+
+    let success = out.write_run_finish(&st)?;
+    Ok(success && !regressed)
 }
 
 // Calculates padding for given test description.
@@ -33,4 +33,6 @@ pub enum TestEvent {
     TeResult(CompletedTest),
     TeTimeout(TestDesc),
     TeFilteredOut(usize),
+    /// The run is being aborted before all tests completed.
+    TeAborted,
 }
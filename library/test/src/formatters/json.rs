@@ -2,6 +2,7 @@
 
 use super::OutputFormatter;
 use crate::{
+    baseline::BenchComparison,
     console::{ConsoleTestState, OutputLocation},
     test_result::TestResult,
     time,
@@ -164,6 +165,29 @@ fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
         ))
     }
 
+    fn write_bench_comparisons(&mut self, comparisons: &[BenchComparison]) -> io::Result<()> {
+        for comparison in comparisons {
+            let line = format!(
+                "{{ \"type\": \"bench\", \
+                 \"event\": \"compared\", \
+                 \"name\": \"{}\", \
+                 \"baseline_median\": {}, \
+                 \"median\": {}, \
+                 \"change\": {}, \
+                 \"p_value\": {}, \
+                 \"verdict\": \"{}\" }}",
+                EscapedString(&comparison.name),
+                comparison.baseline_median as usize,
+                comparison.median as usize,
+                comparison.change,
+                comparison.p_value,
+                comparison.verdict.as_str()
+            );
+            self.writeln_message(&line)?;
+        }
+        Ok(())
+    }
+
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         self.write_message(&*format!(
             "{{ \"type\": \"suite\", \
@@ -194,7 +218,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
 
 /// A formatting utility used to print strings with characters in need of escaping.
 /// Base code taken form `libserialize::json::escape_str`
-struct EscapedString<S: AsRef<str>>(S);
+pub(crate) struct EscapedString<S: AsRef<str>>(pub(crate) S);
 
 impl<S: AsRef<str>> std::fmt::Display for EscapedString<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> ::std::fmt::Result {
@@ -3,6 +3,7 @@
 
 use super::OutputFormatter;
 use crate::{
+    baseline::BenchComparison,
     console::{ConsoleTestState, OutputLocation},
     test_result::TestResult,
     time,
@@ -61,6 +62,12 @@ fn write_result(
         self.results.push((desc.clone(), result.clone(), duration));
         Ok(())
     }
+
+    fn write_bench_comparisons(&mut self, _comparisons: &[BenchComparison]) -> io::Result<()> {
+        // JUnit has no representation for benchmark comparisons.
+        Ok(())
+    }
+
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         self.write_message("<testsuites>")?;
 
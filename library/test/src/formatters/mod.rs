@@ -1,7 +1,9 @@
 use std::{io, io::prelude::Write};
 
 use crate::{
+    baseline::{fmt_bench_comparison, BenchComparison, BenchVerdict},
     console::ConsoleTestState,
+    term,
     test_result::TestResult,
     time,
     types::{TestDesc, TestName},
@@ -10,11 +12,13 @@
 mod json;
 mod junit;
 mod pretty;
+mod tap;
 mod terse;
 
-pub(crate) use self::json::JsonFormatter;
+pub(crate) use self::json::{EscapedString, JsonFormatter};
 pub(crate) use self::junit::JunitFormatter;
 pub(crate) use self::pretty::PrettyFormatter;
+pub(crate) use self::tap::TapFormatter;
 pub(crate) use self::terse::TerseFormatter;
 
 pub(crate) trait OutputFormatter {
@@ -29,6 +33,7 @@ fn write_result(
         stdout: &[u8],
         state: &ConsoleTestState,
     ) -> io::Result<()>;
+    fn write_bench_comparisons(&mut self, comparisons: &[BenchComparison]) -> io::Result<()>;
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool>;
 }
 
@@ -40,3 +45,23 @@ pub(crate) fn write_stderr_delimiter(test_output: &mut Vec<u8>, test_name: &Test
     }
     writeln!(test_output, "---- {} stderr ----", test_name).unwrap();
 }
+
+/// Writes the comparisons with the baseline for the human-readable formats,
+/// highlighting regressions in red and improvements in green.
+pub(crate) fn write_colored_bench_comparisons(
+    comparisons: &[BenchComparison],
+    mut write: impl FnMut(&str, Option<term::color::Color>) -> io::Result<()>,
+) -> io::Result<()> {
+    write("\ncomparison with baseline:\n", None)?;
+    for comparison in comparisons {
+        let color = match comparison.verdict {
+            BenchVerdict::Regressed => Some(term::color::RED),
+            BenchVerdict::Improved => Some(term::color::GREEN),
+            BenchVerdict::NoChange => None,
+        };
+        write("    ", None)?;
+        write(&fmt_bench_comparison(comparison), color)?;
+        write("\n", None)?;
+    }
+    Ok(())
+}
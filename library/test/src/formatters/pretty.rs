@@ -2,6 +2,7 @@
 
 use super::OutputFormatter;
 use crate::{
+    baseline::BenchComparison,
     bench::fmt_bench_samples,
     console::{ConsoleTestState, OutputLocation},
     term,
@@ -238,6 +239,13 @@ fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
         ))
     }
 
+    fn write_bench_comparisons(&mut self, comparisons: &[BenchComparison]) -> io::Result<()> {
+        super::write_colored_bench_comparisons(comparisons, |s, color| match color {
+            Some(color) => self.write_pretty(s, color),
+            None => self.write_plain(s),
+        })
+    }
+
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         if state.options.display_output {
             self.write_successes(state)?;
@@ -0,0 +1,263 @@
+use std::{io, io::prelude::Write};
+
+use super::OutputFormatter;
+use crate::{
+    baseline::{fmt_bench_comparison, BenchComparison},
+    bench::BenchSamples,
+    console::{ConsoleTestState, OutputLocation},
+    test_result::TestResult,
+    time,
+    types::TestDesc,
+};
+
+/// Formatter emitting results in the Test Anything Protocol, version 14.
+///
+/// Failures carry a YAML diagnostic block with the failure message, the
+/// execution time and the captured output. Benchmarks are reported as
+/// subtests whose single test point holds the full `Summary` of the samples.
+pub(crate) struct TapFormatter<T> {
+    out: OutputLocation<T>,
+    /// Number of the next test point, TAP numbering starts at 1.
+    test_number: usize,
+}
+
+impl<T: Write> TapFormatter<T> {
+    pub fn new(out: OutputLocation<T>) -> Self {
+        Self { out, test_number: 0 }
+    }
+
+    fn writeln_message(&mut self, s: &str) -> io::Result<()> {
+        assert!(!s.contains('\n'));
+
+        self.out.write_all(s.as_ref())?;
+        self.out.write_all(b"\n")
+    }
+
+    fn write_test_point(
+        &mut self,
+        ok: bool,
+        name: &str,
+        directive: Option<&str>,
+    ) -> io::Result<()> {
+        self.test_number += 1;
+        let mut line = format!(
+            "{} {} - {}",
+            if ok { "ok" } else { "not ok" },
+            self.test_number,
+            EscapedDescription(name)
+        );
+        if let Some(directive) = directive {
+            line.push_str(" # ");
+            line.push_str(&EscapedDescription(directive).to_string());
+        }
+        self.writeln_message(&line)
+    }
+
+    /// Writes a YAML diagnostic block, `indent` is the indentation of the
+    /// test point the block belongs to.
+    fn write_diagnostics(
+        &mut self,
+        indent: &str,
+        fields: &[(&str, YamlValue<'_>)],
+    ) -> io::Result<()> {
+        if fields.is_empty() {
+            return Ok(());
+        }
+
+        self.writeln_message(&format!("{indent}  ---"))?;
+        for (key, value) in fields {
+            match value {
+                YamlValue::Number(n) if n.is_nan() => {
+                    self.writeln_message(&format!("{indent}  {key}: .nan"))?
+                }
+                YamlValue::Number(n) if n.is_infinite() => {
+                    let sign = if n.is_sign_negative() { "-" } else { "" };
+                    self.writeln_message(&format!("{indent}  {key}: {sign}.inf"))?
+                }
+                YamlValue::Number(n) => self.writeln_message(&format!("{indent}  {key}: {n}"))?,
+                YamlValue::Str(s) if !s.contains('\n') => {
+                    self.writeln_message(&format!("{indent}  {key}: '{}'", s.replace('\'', "''")))?
+                }
+                YamlValue::Str(s) => {
+                    // Multi-line strings are written as literal blocks, keeping
+                    // the trailing newline only if the original text had one.
+                    // Leading whitespace would be mistaken for indentation, so
+                    // the indentation is given explicitly in that case.
+                    let indentation = if s.starts_with(' ') { "2" } else { "" };
+                    let chomp = if s.ends_with('\n') { "" } else { "-" };
+                    self.writeln_message(&format!("{indent}  {key}: |{indentation}{chomp}"))?;
+                    for line in s.lines() {
+                        if line.is_empty() {
+                            self.writeln_message("")?;
+                        } else {
+                            self.writeln_message(&format!("{indent}    {line}"))?;
+                        }
+                    }
+                }
+            }
+        }
+        self.writeln_message(&format!("{indent}  ..."))
+    }
+
+    fn write_bench_subtest(&mut self, name: &str, bs: &BenchSamples) -> io::Result<()> {
+        let summ = &bs.ns_iter_summ;
+        let mut fields = vec![
+            ("median", YamlValue::Number(summ.median)),
+            ("deviation", YamlValue::Number(summ.max - summ.min)),
+            ("min", YamlValue::Number(summ.min)),
+            ("max", YamlValue::Number(summ.max)),
+            ("mean", YamlValue::Number(summ.mean)),
+            ("std_dev", YamlValue::Number(summ.std_dev)),
+            ("std_dev_pct", YamlValue::Number(summ.std_dev_pct)),
+            ("median_abs_dev", YamlValue::Number(summ.median_abs_dev)),
+            ("median_abs_dev_pct", YamlValue::Number(summ.median_abs_dev_pct)),
+            ("q1", YamlValue::Number(summ.quartiles.0)),
+            ("q3", YamlValue::Number(summ.quartiles.2)),
+            ("iqr", YamlValue::Number(summ.iqr)),
+        ];
+        if bs.mb_s != 0 {
+            fields.push(("mib_per_second", YamlValue::Number(bs.mb_s as f64)));
+        }
+
+        self.writeln_message(&format!("# Subtest: {}", EscapedDescription(name)))?;
+        self.writeln_message("    1..1")?;
+        self.writeln_message("    ok 1 - ns/iter")?;
+        self.write_diagnostics("    ", &fields)?;
+        self.write_test_point(true, name, None)
+    }
+}
+
+impl<T: Write> OutputFormatter for TapFormatter<T> {
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        self.writeln_message("TAP version 14")?;
+        self.writeln_message(&format!("1..{test_count}"))?;
+        if let Some(shuffle_seed) = shuffle_seed {
+            self.writeln_message(&format!("# shuffle seed: {shuffle_seed}"))?;
+        }
+        Ok(())
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // Test points are only written once the result is known.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.writeln_message(&format!(
+            "# {} has been running for over {} seconds",
+            EscapedDescription(desc.name.as_slice()),
+            time::TEST_WARN_TIMEOUT_S
+        ))
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let name = desc.name.as_slice();
+        let display_stdout = state.options.display_output || *result != TestResult::TrOk;
+        let stdout = String::from_utf8_lossy(stdout);
+
+        let message = match *result {
+            TestResult::TrOk => None,
+            TestResult::TrFailed => {
+                Some(panic_message(&stdout).unwrap_or("test failed").to_owned())
+            }
+            TestResult::TrFailedMsg(ref m) => Some(m.clone()),
+            TestResult::TrTimedFail => Some("time limit exceeded".to_owned()),
+            TestResult::TrIgnored => {
+                return self.write_test_point(
+                    true,
+                    name,
+                    Some(&match desc.ignore_message {
+                        Some(msg) => format!("SKIP {msg}"),
+                        None => "SKIP".to_owned(),
+                    }),
+                );
+            }
+            TestResult::TrBench(ref bs) => return self.write_bench_subtest(name, bs),
+        };
+
+        let mut fields = Vec::new();
+        if let Some(ref message) = message {
+            fields.push(("message", YamlValue::Str(message)));
+            fields.push(("severity", YamlValue::Str("fail")));
+        }
+        if let Some(exec_time) = exec_time {
+            fields.push(("duration_ms", YamlValue::Number(exec_time.0.as_secs_f64() * 1000.0)));
+        }
+        if display_stdout && !stdout.is_empty() {
+            fields.push(("stdout", YamlValue::Str(&stdout)));
+        }
+
+        self.write_test_point(message.is_none(), name, None)?;
+        self.write_diagnostics("", &fields)
+    }
+
+    fn write_bench_comparisons(&mut self, comparisons: &[BenchComparison]) -> io::Result<()> {
+        self.writeln_message("# comparison with baseline:")?;
+        for comparison in comparisons {
+            self.writeln_message(&format!("#     {}", fmt_bench_comparison(comparison)))?;
+        }
+        Ok(())
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let mut summary = format!(
+            "# {}. {} passed; {} failed; {} ignored; {} measured; {} filtered out",
+            if state.failed == 0 { "ok" } else { "FAILED" },
+            state.passed,
+            state.failed,
+            state.ignored,
+            state.measured,
+            state.filtered_out,
+        );
+        if let Some(ref exec_time) = state.exec_time {
+            summary.push_str(&format!("; finished in {exec_time}"));
+        }
+        self.writeln_message(&summary)?;
+
+        Ok(state.failed == 0)
+    }
+}
+
+enum YamlValue<'a> {
+    Number(f64),
+    Str(&'a str),
+}
+
+/// Extracts the panic report from the captured output of a failed test,
+/// starting at the `panicked at` line and stopping before the backtrace note.
+fn panic_message(stdout: &str) -> Option<&str> {
+    let panicked_at = stdout.find(" panicked at ")?;
+    let start = stdout[..panicked_at].rfind('\n').map_or(0, |i| i + 1);
+    let report = &stdout[start..];
+    let end = ["\nnote: ", "\nstack backtrace:"]
+        .iter()
+        .filter_map(|terminator| report.find(terminator))
+        .min()
+        .unwrap_or(report.len());
+    Some(report[..end].trim_end())
+}
+
+/// A formatting utility used to print test descriptions and directives,
+/// escaping the characters TAP gives a special meaning to.
+struct EscapedDescription<S: AsRef<str>>(S);
+
+impl<S: AsRef<str>> std::fmt::Display for EscapedDescription<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.as_ref().chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '#' => f.write_str("\\#")?,
+                '\n' | '\r' => f.write_str(" ")?,
+                c => std::fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -5,4 +5,6 @@
 pub mod exit_code;
 pub mod isatty;
 pub mod metrics;
+pub mod rerun;
+pub mod shard;
 pub mod shuffle;
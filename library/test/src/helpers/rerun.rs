@@ -0,0 +1,91 @@
+//! Helpers for the `--rerun-failed` option, which records the names of the
+//! tests that failed in a run so that the next run only executes those.
+
+use crate::console::ConsoleTestState;
+use crate::types::TestDescAndFn;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the file the failed tests are recorded in.
+pub const RERUN_FILE_ENV_NAME: &str = "RUST_TEST_RERUN_FILE";
+
+/// Returns the file the failed tests of this test binary are recorded in.
+///
+/// Unless overridden with `RUST_TEST_RERUN_FILE`, this is a file next to the
+/// test executable, i.e. inside the target directory when run by cargo.
+pub fn get_rerun_file() -> Result<PathBuf, String> {
+    if let Some(path) = env::var_os(RERUN_FILE_ENV_NAME) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let exe = env::current_exe()
+        .map_err(|e| format!("failed to locate the test executable for --rerun-failed: {e}"))?;
+    let mut file_name = exe.file_name().unwrap_or_default().to_owned();
+    file_name.push(".failed-tests");
+    Ok(exe.with_file_name(file_name))
+}
+
+/// Reads the names of the tests recorded as failed.
+///
+/// Returns `None` if nothing was recorded, which happens on the first run and
+/// after the recorded tests all passed.
+pub fn read_failed_tests(path: &Path) -> io::Result<Option<HashSet<String>>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents.lines().map(str::to_owned).collect())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(io::Error::new(
+            e.kind(),
+            format!("failed to read the failed tests recorded in {}: {e}", path.display()),
+        )),
+    }
+}
+
+/// Only keeps the tests recorded as failed, if anything was recorded.
+pub fn retain_failed_tests(path: &Path, tests: &mut Vec<TestDescAndFn>) -> io::Result<()> {
+    if let Some(failed_tests) = read_failed_tests(path)? {
+        tests.retain(|test| failed_tests.contains(test.desc.name.as_slice()));
+    }
+    Ok(())
+}
+
+/// Updates the record of the failed tests with the results of this run, one
+/// name per line.
+///
+/// The tests which passed are removed from the record and the ones which
+/// failed are added. The tests which didn't run, because of a filter, `--skip`
+/// or `--shard-index`, keep their entry, while the entries of tests which are
+/// no longer part of `all_tests` are dropped. If no entry is left the record is
+/// removed, so that the next run executes the whole test suite again.
+pub fn write_failed_tests(
+    path: &Path,
+    state: &ConsoleTestState,
+    all_tests: &HashSet<String>,
+) -> io::Result<()> {
+    let mut names = read_failed_tests(path)?.unwrap_or_default();
+    names.retain(|name| all_tests.contains(name));
+    for (desc, _) in &state.not_failures {
+        names.remove(desc.name.as_slice());
+    }
+    for (desc, _) in &state.bench_results {
+        names.remove(desc.name.as_slice());
+    }
+    names.extend(
+        state.failures.iter().chain(&state.time_failures).map(|(desc, _)| desc.name.to_string()),
+    );
+
+    if names.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+
+    let mut names: Vec<_> = names.into_iter().collect();
+    names.sort();
+    let mut contents = names.join("\n");
+    contents.push('\n');
+    fs::write(path, contents)
+}
@@ -0,0 +1,23 @@
+//! Helpers for splitting the tests into disjoint shards, so that a test run
+//! can be spread across several machines.
+
+use crate::options::Shard;
+use crate::types::TestName;
+
+/// Returns whether the test with the given name belongs to `shard`.
+///
+/// Tests are assigned by hashing their name, so that the assignment doesn't
+/// depend on which other tests exist, on the filters or on the test order.
+pub fn is_in_shard(name: &TestName, shard: Shard) -> bool {
+    stable_hash(name.as_slice()) % shard.count as u64 == shard.index as u64
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is guaranteed to produce the
+/// same value on every platform and Rust version, so that workers built by
+/// different toolchains still agree on the shards.
+fn stable_hash(s: &str) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    s.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+}
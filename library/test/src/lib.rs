@@ -24,7 +24,7 @@
 // Public reexports
 pub use self::bench::{black_box, Bencher};
 pub use self::console::run_tests_console;
-pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic};
+pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, Shard, ShouldPanic};
 pub use self::types::TestName::*;
 pub use self::types::*;
 pub use self::ColorConfig::*;
@@ -50,9 +50,10 @@ pub mod test {
 }
 
 use std::{
+    cmp,
     collections::VecDeque,
     env, io,
-    io::prelude::Write,
+    io::prelude::{Read, Write},
     panic::{self, catch_unwind, AssertUnwindSafe, PanicInfo},
     process::{self, Command, Termination},
     sync::mpsc::{channel, Sender},
@@ -61,6 +62,7 @@ pub mod test {
     time::{Duration, Instant},
 };
 
+mod baseline;
 pub mod bench;
 mod cli;
 mod console;
@@ -80,6 +82,8 @@ pub mod test {
 use event::{CompletedTest, TestEvent};
 use helpers::concurrency::get_concurrency;
 use helpers::exit_code::get_exit_code;
+use helpers::rerun::retain_failed_tests;
+use helpers::shard::is_in_shard;
 use helpers::shuffle::{get_shuffle_seed, shuffle_tests};
 use options::{Concurrent, RunStrategy};
 use test_result::*;
@@ -218,6 +222,9 @@ struct TimeoutEntry {
     let tests_len = tests.len();
 
     let mut filtered_tests = filter_tests(opts, tests);
+    if let Some(ref rerun_file) = opts.rerun_failed {
+        retain_failed_tests(rerun_file, &mut filtered_tests)?;
+    }
     if !opts.bench_benchmarks {
         filtered_tests = convert_benchmarks_to_tests(filtered_tests);
     }
@@ -268,10 +275,19 @@ struct TimeoutEntry {
     let mut running_tests: TestMap = HashMap::default();
     let mut timeout_queue: VecDeque<TimeoutEntry> = VecDeque::new();
 
+    // Tests running in-process can't be killed, so when they exceed the hard
+    // timeout the whole run is aborted. Tests in subprocesses are killed by
+    // `spawn_test_subprocess` itself.
+    let hard_timeout = match run_strategy {
+        RunStrategy::InProcess => opts.test_timeout,
+        RunStrategy::SpawnPrimary => None,
+    };
+    let mut hard_timeout_queue: VecDeque<TimeoutEntry> = VecDeque::new();
+
     fn get_timed_out_tests(
         running_tests: &TestMap,
         timeout_queue: &mut VecDeque<TimeoutEntry>,
-    ) -> Vec<TestDesc> {
+    ) -> Vec<TimeoutEntry> {
         let now = Instant::now();
         let mut timed_out = Vec::new();
         while let Some(timeout_entry) = timeout_queue.front() {
@@ -280,20 +296,59 @@ fn get_timed_out_tests(
             }
             let timeout_entry = timeout_queue.pop_front().unwrap();
             if running_tests.contains_key(&timeout_entry.id) {
-                timed_out.push(timeout_entry.desc);
+                timed_out.push(timeout_entry);
             }
         }
         timed_out
     }
 
-    fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
-        timeout_queue.front().map(|&TimeoutEntry { timeout: next_timeout, .. }| {
-            let now = Instant::now();
-            if next_timeout >= now { next_timeout - now } else { Duration::new(0, 0) }
-        })
+    fn calc_timeout(timeout_queues: &[&VecDeque<TimeoutEntry>]) -> Option<Duration> {
+        let next_timeout = timeout_queues
+            .iter()
+            .filter_map(|timeout_queue| timeout_queue.front())
+            .map(|timeout_entry| timeout_entry.timeout)
+            .min()?;
+        let now = Instant::now();
+        Some(if next_timeout >= now { next_timeout - now } else { Duration::new(0, 0) })
     }
 
-    if concurrency == 1 {
+    /// Reports tests that ran in-process past the hard timeout as failed,
+    /// finishes the output and exits, since there is no way to stop the
+    /// threads running them.
+    fn abort_stuck_tests<F>(
+        stuck_tests: Vec<TimeoutEntry>,
+        hard_timeout: Duration,
+        notify_about_test_event: &mut F,
+    ) -> !
+    where
+        F: FnMut(TestEvent) -> io::Result<()>,
+    {
+        let names: Vec<_> = stuck_tests.iter().map(|entry| entry.desc.name.to_string()).collect();
+
+        // We're exiting with an error either way, so failing to report the
+        // stuck tests must not keep us from exiting.
+        for TimeoutEntry { id, desc, .. } in stuck_tests {
+            let event =
+                TestEvent::TeResult(CompletedTest::new(id, desc, TrTimedFail, None, Vec::new()));
+            let _ = notify_about_test_event(event);
+        }
+
+        eprintln!(
+            "error: the following tests have been running for over {} seconds and cannot be \
+             stopped, aborting the test run:",
+            hard_timeout.as_secs_f64()
+        );
+        for name in names {
+            eprintln!("    {name}");
+        }
+
+        let _ = notify_about_test_event(TestEvent::TeAborted);
+        process::exit(ERROR_EXIT_CODE);
+    }
+
+    // A hung test can only be detected while it runs on a separate thread,
+    // so the serial fast path is only taken when there is no hard timeout.
+    if concurrency == 1 && hard_timeout.is_none() {
         while !remaining.is_empty() {
             let (id, test) = remaining.pop().unwrap();
             let event = TestEvent::TeWait(test.desc.clone());
@@ -325,22 +380,36 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
                     Concurrent::Yes,
                 );
                 running_tests.insert(id, RunningTest { join_handle });
+                if let Some(hard_timeout) = hard_timeout {
+                    let timeout = Instant::now() + hard_timeout;
+                    hard_timeout_queue.push_back(TimeoutEntry { id, desc: desc.clone(), timeout });
+                }
                 timeout_queue.push_back(TimeoutEntry { id, desc, timeout });
                 pending += 1;
             }
 
             let mut res;
             loop {
-                if let Some(timeout) = calc_timeout(&timeout_queue) {
+                if let Some(timeout) = calc_timeout(&[&timeout_queue, &hard_timeout_queue]) {
                     res = rx.recv_timeout(timeout);
                     for test in get_timed_out_tests(&running_tests, &mut timeout_queue) {
-                        let event = TestEvent::TeTimeout(test);
+                        let event = TestEvent::TeTimeout(test.desc);
                         notify_about_test_event(event)?;
                     }
 
                     match res {
                         Err(RecvTimeoutError::Timeout) => {
-                            // Result is not yet ready, continue waiting.
+                            // Result is not yet ready, check whether any test
+                            // is stuck and continue waiting otherwise.
+                            let stuck_tests =
+                                get_timed_out_tests(&running_tests, &mut hard_timeout_queue);
+                            if !stuck_tests.is_empty() {
+                                abort_stuck_tests(
+                                    stuck_tests,
+                                    hard_timeout.unwrap(),
+                                    &mut notify_about_test_event,
+                                );
+                            }
                         }
                         _ => {
                             // We've got a result, stop the loop.
@@ -404,6 +473,11 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     // Skip tests that match any of the skip filters
     filtered.retain(|test| !opts.skip.iter().any(|sf| matches_filter(test, sf)));
 
+    // Only keep the tests assigned to this shard
+    if let Some(shard) = opts.shard {
+        filtered.retain(|test| is_in_shard(&test.desc.name, shard));
+    }
+
     // Excludes #[should_panic] tests
     if opts.exclude_should_panic {
         filtered.retain(|test| test.desc.should_panic == ShouldPanic::No);
@@ -473,6 +547,7 @@ struct TestRunOpts {
         pub nocapture: bool,
         pub concurrency: Concurrent,
         pub time: Option<time::TestTimeOptions>,
+        pub timeout: Option<Duration>,
     }
 
     fn run_test_inner(
@@ -502,6 +577,7 @@ fn run_test_inner(
                 opts.time.is_some(),
                 monitor_ch,
                 opts.time,
+                opts.timeout,
             ),
         };
 
@@ -529,8 +605,13 @@ fn run_test_inner(
         }
     }
 
-    let test_run_opts =
-        TestRunOpts { strategy, nocapture: opts.nocapture, concurrency, time: opts.time_options };
+    let test_run_opts = TestRunOpts {
+        strategy,
+        nocapture: opts.nocapture,
+        concurrency,
+        time: opts.time_options,
+        timeout: opts.test_timeout,
+    };
 
     match testfn {
         DynBenchFn(benchfn) => {
@@ -616,6 +697,7 @@ fn spawn_test_subprocess(
     report_time: bool,
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
+    timeout: Option<Duration>,
 ) {
     let (result, test_output, exec_time) = (|| {
         let args = env::args().collect::<Vec<_>>();
@@ -629,7 +711,11 @@ fn spawn_test_subprocess(
         }
 
         let start = report_time.then(Instant::now);
-        let output = match command.output() {
+        let output = match timeout {
+            Some(timeout) => output_with_timeout(&mut command, nocapture, timeout),
+            None => command.output().map(|output| (output, false)),
+        };
+        let (output, timed_out) = match output {
             Ok(out) => out,
             Err(e) => {
                 let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
@@ -646,6 +732,13 @@ fn spawn_test_subprocess(
         formatters::write_stderr_delimiter(&mut test_output, &desc.name);
         test_output.extend_from_slice(&stderr);
 
+        if timed_out {
+            let timeout = timeout.unwrap().as_secs_f64();
+            write!(&mut test_output, "note: test killed after running for over {timeout} seconds")
+                .unwrap();
+            return (TrTimedFail, test_output, exec_time);
+        }
+
         let result = match (|| -> Result<TestResult, String> {
             let exit_code = get_exit_code(status)?;
             Ok(get_result_from_exit_code(&desc, exit_code, &time_opts, &exec_time))
@@ -664,6 +757,57 @@ fn spawn_test_subprocess(
     monitor_ch.send(message).unwrap();
 }
 
+/// Like `Command::output`, but kills the child once `timeout` has elapsed.
+/// The returned flag tells whether the child had to be killed.
+fn output_with_timeout(
+    command: &mut Command,
+    nocapture: bool,
+    timeout: Duration,
+) -> io::Result<(process::Output, bool)> {
+    fn read_pipe<R: Read + Send + 'static>(
+        pipe: Option<R>,
+    ) -> Option<thread::JoinHandle<io::Result<Vec<u8>>>> {
+        pipe.map(|mut pipe| {
+            thread::spawn(move || {
+                let mut buf = Vec::new();
+                pipe.read_to_end(&mut buf).map(|_| buf)
+            })
+        })
+    }
+
+    if !nocapture {
+        command.stdout(process::Stdio::piped());
+        command.stderr(process::Stdio::piped());
+    }
+    let mut child = command.spawn()?;
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            timed_out = true;
+            // The child may have exited since `try_wait`, in which case
+            // there is nothing left to kill.
+            let _ = child.kill();
+            break child.wait()?;
+        }
+        thread::sleep(cmp::min(deadline - now, Duration::from_millis(10)));
+    };
+
+    let join = |reader: Option<thread::JoinHandle<io::Result<Vec<u8>>>>| match reader {
+        Some(reader) => reader.join().unwrap(),
+        None => Ok(Vec::new()),
+    };
+    let output = process::Output { status, stdout: join(stdout)?, stderr: join(stderr)? };
+    Ok((output, timed_out))
+}
+
 fn run_test_in_spawned_subprocess(desc: TestDesc, testfn: Box<dyn FnOnce() + Send>) -> ! {
     let builtin_panic_hook = panic::take_hook();
     let record_result = Arc::new(move |panic_info: Option<&'_ PanicInfo<'_>>| {
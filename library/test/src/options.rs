@@ -41,6 +41,8 @@ pub enum OutputFormat {
     Json,
     /// JUnit output
     Junit,
+    /// TAP (Test Anything Protocol) output
+    Tap,
 }
 
 /// Whether ignored test should be run or not
@@ -52,6 +54,16 @@ pub enum RunIgnored {
     Only,
 }
 
+/// The subset of the tests to run when a test run is split across several
+/// machines
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Shard {
+    /// Zero-based index of this shard
+    pub index: usize,
+    /// Total number of shards
+    pub count: usize,
+}
+
 #[derive(Clone, Copy)]
 pub enum RunStrategy {
     /// Runs the test in the current process, and sends the result back over the
@@ -2,8 +2,9 @@
 
 use crate::{
     bench::Bencher,
-    console::OutputLocation,
+    console::{ConsoleTestState, OutputLocation},
     formatters::PrettyFormatter,
+    helpers::rerun::{retain_failed_tests, write_failed_tests},
     options::OutputFormat,
     test::{
         filter_tests,
@@ -41,15 +42,20 @@ fn new() -> TestOpts {
             run_ignored: RunIgnored::No,
             run_tests: false,
             bench_benchmarks: false,
+            save_baseline: None,
+            baseline: None,
             logfile: None,
             nocapture: false,
             color: AutoColor,
             format: OutputFormat::Pretty,
             shuffle: false,
             shuffle_seed: None,
+            shard: None,
             test_threads: None,
             skip: vec![],
             time_options: None,
+            test_timeout: None,
+            rerun_failed: None,
             options: Options::new(),
         }
     }
@@ -422,6 +428,27 @@ fn parse_show_output_flag() {
     assert!(opts.options.display_output);
 }
 
+#[test]
+fn parse_test_timeout_option() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--test-timeout=5".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.test_timeout, Some(Duration::from_secs(5)));
+
+    let args = vec!["progname".to_string(), "--test-timeout=5".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--test-timeout=0".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
 #[test]
 fn parse_include_ignored_flag() {
     let args = vec!["progname".to_string(), "filter".to_string(), "--include-ignored".to_string()];
@@ -489,6 +516,102 @@ pub fn exclude_should_panic_option() {
     assert!(filtered.iter().all(|test| test.desc.should_panic == ShouldPanic::No));
 }
 
+#[test]
+pub fn rerun_failed_option() {
+    let rerun_file =
+        std::env::temp_dir().join(format!("libtest-rerun-failed-{}", std::process::id()));
+    let _ = std::fs::remove_file(&rerun_file);
+
+    // Nothing recorded yet, so all tests are run.
+    let mut tests = sample_tests();
+    retain_failed_tests(&rerun_file, &mut tests).unwrap();
+    assert_eq!(tests.len(), sample_tests().len());
+
+    std::fs::write(&rerun_file, "isize::test_pow\nsha1::test\nremoved::test\n").unwrap();
+    let mut tests = sample_tests();
+    retain_failed_tests(&rerun_file, &mut tests).unwrap();
+    std::fs::remove_file(&rerun_file).unwrap();
+
+    let names: Vec<_> = tests.iter().map(|test| test.desc.name.as_slice()).collect();
+    assert_eq!(names, ["sha1::test", "isize::test_pow"]);
+
+    // Anything but a missing record is an error.
+    std::fs::write(&rerun_file, b"\xff\n").unwrap();
+    let mut tests = sample_tests();
+    assert!(retain_failed_tests(&rerun_file, &mut tests).is_err());
+    std::fs::remove_file(&rerun_file).unwrap();
+}
+
+#[test]
+pub fn rerun_failed_record_is_merged() {
+    let rerun_file =
+        std::env::temp_dir().join(format!("libtest-rerun-merged-{}", std::process::id()));
+    std::fs::write(&rerun_file, "isize::test_pow\nsha1::test\nremoved::test\n").unwrap();
+    let all_tests = sample_tests().iter().map(|test| test.desc.name.to_string()).collect();
+
+    // `sha1::test` passed and `isize::test_to_str` failed, `isize::test_pow` didn't run.
+    let mut st = ConsoleTestState::new(&TestOpts::new()).unwrap();
+    let desc = |name: &str| {
+        sample_tests().into_iter().find(|test| test.desc.name.as_slice() == name).unwrap().desc
+    };
+    st.not_failures.push((desc("sha1::test"), Vec::new()));
+    st.failures.push((desc("isize::test_to_str"), Vec::new()));
+    write_failed_tests(&rerun_file, &st, &all_tests).unwrap();
+    let record = std::fs::read_to_string(&rerun_file).unwrap();
+    assert_eq!(record, "isize::test_pow\nisize::test_to_str\n");
+
+    // Once all of them passed, the record is removed.
+    let mut st = ConsoleTestState::new(&TestOpts::new()).unwrap();
+    st.not_failures.push((desc("isize::test_pow"), Vec::new()));
+    st.not_failures.push((desc("isize::test_to_str"), Vec::new()));
+    write_failed_tests(&rerun_file, &st, &all_tests).unwrap();
+    assert!(!rerun_file.exists());
+}
+
+#[test]
+pub fn shard_option() {
+    let mut opts = TestOpts::new();
+    opts.run_tests = true;
+
+    let mut names = Vec::new();
+    for index in 0..3 {
+        opts.shard = Some(Shard { index, count: 3 });
+        let filtered = filter_tests(&opts, sample_tests());
+        assert!(filtered.len() < sample_tests().len());
+        names.extend(filtered.into_iter().map(|test| test.desc.name.to_string()));
+    }
+
+    // Every test ends up in exactly one shard.
+    names.sort();
+    let expected: Vec<_> = filter_tests(&TestOpts::new(), sample_tests())
+        .into_iter()
+        .map(|test| test.desc.name.to_string())
+        .collect();
+    assert_eq!(names, expected);
+}
+
+#[test]
+fn parse_shard_options() {
+    let parse = |args: &[&str]| {
+        let args: Vec<_> = ["progname", "-Zunstable-options"]
+            .iter()
+            .chain(args)
+            .map(|arg| arg.to_string())
+            .collect();
+        parse_opts(&args).unwrap().map(|opts| opts.shard)
+    };
+
+    assert_eq!(
+        parse(&["--shard-index=1", "--shard-count=4"]),
+        Ok(Some(Shard { index: 1, count: 4 }))
+    );
+    assert_eq!(parse(&[]), Ok(None));
+    assert!(parse(&["--shard-index=4", "--shard-count=4"]).is_err());
+    assert!(parse(&["--shard-index=0", "--shard-count=0"]).is_err());
+    assert!(parse(&["--shard-index=0"]).is_err());
+    assert!(parse(&["--shard-count=2"]).is_err());
+}
+
 #[test]
 pub fn exact_filter_match() {
     fn tests() -> Vec<TestDescAndFn> {
@@ -809,6 +932,7 @@ fn should_sort_failures_before_printing_them() {
         options: Options::new(),
         not_failures: Vec::new(),
         time_failures: Vec::new(),
+        bench_results: Vec::new(),
     };
 
     out.write_failures(&st).unwrap();
@@ -34,6 +34,8 @@
 pub(crate) enum OutputFormat {
     Json,
     Html,
+    Markdown,
+    Man,
 }
 
 impl Default for OutputFormat {
@@ -55,6 +57,8 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "json" => Ok(OutputFormat::Json),
             "html" => Ok(OutputFormat::Html),
+            "markdown" => Ok(OutputFormat::Markdown),
+            "man" => Ok(OutputFormat::Man),
             _ => Err(format!("unknown output format `{}`", value)),
         }
     }
@@ -126,6 +130,9 @@ pub(crate) struct Options {
     pub(crate) enable_per_target_ignores: bool,
     /// Do not run doctests, compile them if should_test is active.
     pub(crate) no_run: bool,
+    /// Whether to compile the doctests which allow it into a single test harness per edition,
+    /// instead of compiling each doctest on its own.
+    pub(crate) merge_doctests: bool,
 
     /// The path to a rustc-like binary to build tests with. If not set, we
     /// default to loading from `$sysroot/bin/rustc`.
@@ -135,6 +142,12 @@ pub(crate) struct Options {
     /// Whether to run the `calculate-doc-coverage` pass, which counts the number of public items
     /// with and without documentation.
     pub(crate) show_coverage: bool,
+    /// The minimum coverage required by `--show-coverage`, before the `#[doc(coverage(...))]`
+    /// attributes of the crate.
+    pub(crate) coverage_thresholds: CoverageThresholds,
+    /// The JSON output of an older version of the crate, to compare the JSON output given as
+    /// `input` against instead of documenting anything.
+    pub(crate) api_diff: Option<PathBuf>,
 
     // Options that alter generated documentation pages
     /// Crate version to note on the sidebar of generated docs.
@@ -190,6 +203,8 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("test_run_directory", &self.test_run_directory)
             .field("persist_doctests", &self.persist_doctests)
             .field("show_coverage", &self.show_coverage)
+            .field("coverage_thresholds", &self.coverage_thresholds)
+            .field("api_diff", &self.api_diff)
             .field("crate_version", &self.crate_version)
             .field("render_options", &self.render_options)
             .field("runtool", &self.runtool)
@@ -197,6 +212,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("enable-per-target-ignores", &self.enable_per_target_ignores)
             .field("run_check", &self.run_check)
             .field("no_run", &self.no_run)
+            .field("merge_doctests", &self.merge_doctests)
             .field("nocapture", &self.nocapture)
             .field("scrape_examples_options", &self.scrape_examples_options)
             .finish()
@@ -278,6 +294,30 @@ pub(crate) struct RenderOptions {
     pub(crate) call_locations: AllCallLocations,
     /// If `true`, Context::init will not emit shared files.
     pub(crate) no_emit_shared: bool,
+    /// If present, the output directory is packed into a zip archive written to this path once
+    /// the documentation is rendered.
+    pub(crate) bundle: Option<PathBuf>,
+    /// Show the functions calling and called by each function in the docs, and write the call
+    /// graph of the crate to `call-graph.json`.
+    pub(crate) show_call_graph: bool,
+}
+
+/// The minimum percentages of documented items and of items with code examples which
+/// `--show-coverage` requires, if any.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct CoverageThresholds {
+    pub(crate) docs: Option<f64>,
+    pub(crate) examples: Option<f64>,
+}
+
+impl CoverageThresholds {
+    /// The thresholds of `self`, falling back to those of `parent` for the ones it doesn't set.
+    pub(crate) fn or(self, parent: CoverageThresholds) -> CoverageThresholds {
+        CoverageThresholds {
+            docs: self.docs.or(parent.docs),
+            examples: self.examples.or(parent.examples),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -498,6 +538,12 @@ fn println_condition(condition: Condition) {
             return Err(1);
         }
 
+        let merge_doctests = matches.opt_present("merge-doctests");
+        if !should_test && merge_doctests {
+            diag.err("the `--test` flag must be passed to enable `--merge-doctests`");
+            return Err(1);
+        }
+
         let out_dir = matches.opt_str("out-dir").map(|s| PathBuf::from(&s));
         let output = matches.opt_str("output").map(|s| PathBuf::from(&s));
         let output = match (out_dir, output) {
@@ -598,6 +644,16 @@ fn println_condition(condition: Condition) {
         let target = parse_target_triple(matches, error_format);
 
         let show_coverage = matches.opt_present("show-coverage");
+        let coverage_thresholds = CoverageThresholds {
+            docs: parse_coverage_threshold(matches, "coverage-min-docs", show_coverage, &diag)?,
+            examples: parse_coverage_threshold(
+                matches,
+                "coverage-min-examples",
+                show_coverage,
+                &diag,
+            )?,
+        };
+        let api_diff = matches.opt_str("api-diff").map(PathBuf::from);
 
         let crate_types = match parse_crate_types_from_list(matches.opt_strs("crate-type")) {
             Ok(types) => types,
@@ -611,9 +667,10 @@ fn println_condition(condition: Condition) {
             Some(s) => match OutputFormat::try_from(s.as_str()) {
                 Ok(out_fmt) => {
                     if !out_fmt.is_json() && show_coverage {
-                        diag.struct_err(
-                            "html output format isn't supported for the --show-coverage option",
-                        )
+                        diag.struct_err(&format!(
+                            "{} output format isn't supported for the --show-coverage option",
+                            s
+                        ))
                         .emit();
                         return Err(1);
                     }
@@ -667,6 +724,19 @@ fn println_condition(condition: Condition) {
             return Err(1);
         }
 
+        let bundle = matches.opt_str("bundle").map(PathBuf::from);
+        if bundle.is_some() && (show_coverage || output_format != OutputFormat::Html) {
+            diag.struct_err("--bundle option can only be used with HTML output format").emit();
+            return Err(1);
+        }
+
+        let show_call_graph = matches.opt_present("show-call-graph");
+        if show_call_graph && (show_coverage || output_format != OutputFormat::Html) {
+            diag.struct_err("--show-call-graph option can only be used with HTML output format")
+                .emit();
+            return Err(1);
+        }
+
         let scrape_examples_options = ScrapeExamplesOptions::new(matches, &diag)?;
         let with_examples = matches.opt_strs("with-examples");
         let call_locations = crate::scrape_examples::load_call_locations(with_examples, &diag)?;
@@ -696,6 +766,8 @@ fn println_condition(condition: Condition) {
             should_test,
             test_args,
             show_coverage,
+            coverage_thresholds,
+            api_diff,
             crate_version,
             test_run_directory,
             persist_doctests,
@@ -705,6 +777,7 @@ fn println_condition(condition: Condition) {
             test_builder,
             run_check,
             no_run,
+            merge_doctests,
             nocapture,
             render_options: RenderOptions {
                 output,
@@ -736,6 +809,8 @@ fn println_condition(condition: Condition) {
                 generate_link_to_definition,
                 call_locations,
                 no_emit_shared: false,
+                bundle,
+                show_call_graph,
             },
             crate_name,
             output_format,
@@ -786,6 +861,31 @@ fn check_deprecated_options(matches: &getopts::Matches, diag: &rustc_errors::Han
     }
 }
 
+/// Parses the percentage given to `--{name}`, an option which requires `--show-coverage`.
+fn parse_coverage_threshold(
+    matches: &getopts::Matches,
+    name: &str,
+    show_coverage: bool,
+    diag: &rustc_errors::Handler,
+) -> Result<Option<f64>, i32> {
+    let Some(arg) = matches.opt_str(name) else { return Ok(None) };
+    if !show_coverage {
+        diag.struct_err(&format!("the --{} option requires --show-coverage", name)).emit();
+        return Err(1);
+    }
+    match arg.parse::<f64>() {
+        Ok(percentage) if (0.0..=100.0).contains(&percentage) => Ok(Some(percentage)),
+        _ => {
+            diag.struct_err(&format!(
+                "the --{} option takes a percentage between 0 and 100, not `{}`",
+                name, arg
+            ))
+            .emit();
+            Err(1)
+        }
+    }
+}
+
 /// Extracts `--extern-html-root-url` arguments from `matches` and returns a map of crate names to
 /// the given URLs. If an `--extern-html-root-url` argument was ill-formed, returns an error
 /// describing the issue.
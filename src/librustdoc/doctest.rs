@@ -1,3 +1,7 @@
+mod merged;
+
+use self::merged::{make_merged_test, MergedDoctests};
+
 use rustc_ast as ast;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lrc;
@@ -26,7 +30,7 @@
 use std::env;
 use std::io::{self, Write};
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 use std::str;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -316,25 +320,7 @@ fn run_test(
 
     let output_file = outdir.path().join("rust_out");
 
-    let rustc_binary = rustdoc_options
-        .test_builder
-        .as_deref()
-        .unwrap_or_else(|| rustc_interface::util::rustc_path().expect("found rustc"));
-    let mut compiler = Command::new(&rustc_binary);
-    compiler.arg("--crate-type").arg("bin");
-    for cfg in &rustdoc_options.cfgs {
-        compiler.arg("--cfg").arg(&cfg);
-    }
-    if !rustdoc_options.check_cfgs.is_empty() {
-        compiler.arg("-Z").arg("unstable-options");
-        for check_cfg in &rustdoc_options.check_cfgs {
-            compiler.arg("--check-cfg").arg(&check_cfg);
-        }
-    }
-    if let Some(sysroot) = rustdoc_options.maybe_sysroot {
-        compiler.arg("--sysroot").arg(sysroot);
-    }
-    compiler.arg("--edition").arg(&edition.to_string());
+    let mut compiler = rustc_command(&rustdoc_options, &target, edition, supports_color);
     compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
     compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
     compiler.arg("-o").arg(&output_file);
@@ -347,47 +333,9 @@ fn run_test(
         compiler.arg("-Z").arg("unstable-options");
         compiler.arg("-W").arg("unused_crate_dependencies");
     }
-    for lib_str in &rustdoc_options.lib_strs {
-        compiler.arg("-L").arg(&lib_str);
-    }
-    for extern_str in &rustdoc_options.extern_strs {
-        compiler.arg("--extern").arg(&extern_str);
-    }
-    compiler.arg("-Ccodegen-units=1");
-    for codegen_options_str in &rustdoc_options.codegen_options_strs {
-        compiler.arg("-C").arg(&codegen_options_str);
-    }
-    for debugging_option_str in &rustdoc_options.debugging_opts_strs {
-        compiler.arg("-Z").arg(&debugging_option_str);
-    }
     if no_run && !lang_string.compile_fail && rustdoc_options.persist_doctests.is_none() {
         compiler.arg("--emit=metadata");
     }
-    compiler.arg("--target").arg(match target {
-        TargetTriple::TargetTriple(s) => s,
-        TargetTriple::TargetPath(path) => {
-            path.to_str().expect("target path must be valid unicode").to_string()
-        }
-    });
-    if let ErrorOutputType::HumanReadable(kind) = rustdoc_options.error_format {
-        let (short, color_config) = kind.unzip();
-
-        if short {
-            compiler.arg("--error-format").arg("short");
-        }
-
-        match color_config {
-            ColorConfig::Never => {
-                compiler.arg("--color").arg("never");
-            }
-            ColorConfig::Always => {
-                compiler.arg("--color").arg("always");
-            }
-            ColorConfig::Auto => {
-                compiler.arg("--color").arg(if supports_color { "always" } else { "never" });
-            }
-        }
-    }
 
     compiler.arg("-");
     compiler.stdin(Stdio::piped());
@@ -453,6 +401,108 @@ fn drop(&mut self) {
         return Ok(());
     }
 
+    run_executable(&output_file, &[], &lang_string, &rustdoc_options, runtool, runtool_args)
+}
+
+/// Runs a doctest compiled into the harness `binary` of a [`MergedDoctests`], as the only test of
+/// the harness to run.
+fn run_merged_test(
+    binary: &Path,
+    test_path: &str,
+    lang_string: &LangString,
+    no_run: bool,
+    rustdoc_options: &RustdocOptions,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+) -> Result<(), TestFailure> {
+    if no_run {
+        return Ok(());
+    }
+
+    let mut args = vec!["--exact", test_path];
+    if rustdoc_options.nocapture {
+        args.push("--nocapture");
+    }
+    run_executable(binary, &args, lang_string, rustdoc_options, runtool, runtool_args)
+}
+
+/// Creates the command compiling a doctest into a binary, with the options shared by all
+/// doctests of the crate.
+fn rustc_command(
+    rustdoc_options: &RustdocOptions,
+    target: &TargetTriple,
+    edition: Edition,
+    supports_color: bool,
+) -> Command {
+    let rustc_binary = rustdoc_options
+        .test_builder
+        .as_deref()
+        .unwrap_or_else(|| rustc_interface::util::rustc_path().expect("found rustc"));
+    let mut compiler = Command::new(&rustc_binary);
+    compiler.arg("--crate-type").arg("bin");
+    for cfg in &rustdoc_options.cfgs {
+        compiler.arg("--cfg").arg(&cfg);
+    }
+    if !rustdoc_options.check_cfgs.is_empty() {
+        compiler.arg("-Z").arg("unstable-options");
+        for check_cfg in &rustdoc_options.check_cfgs {
+            compiler.arg("--check-cfg").arg(&check_cfg);
+        }
+    }
+    if let Some(ref sysroot) = rustdoc_options.maybe_sysroot {
+        compiler.arg("--sysroot").arg(sysroot);
+    }
+    compiler.arg("--edition").arg(&edition.to_string());
+    for lib_str in &rustdoc_options.lib_strs {
+        compiler.arg("-L").arg(&lib_str);
+    }
+    for extern_str in &rustdoc_options.extern_strs {
+        compiler.arg("--extern").arg(&extern_str);
+    }
+    compiler.arg("-Ccodegen-units=1");
+    for codegen_options_str in &rustdoc_options.codegen_options_strs {
+        compiler.arg("-C").arg(&codegen_options_str);
+    }
+    for debugging_option_str in &rustdoc_options.debugging_opts_strs {
+        compiler.arg("-Z").arg(&debugging_option_str);
+    }
+    compiler.arg("--target").arg(match target {
+        TargetTriple::TargetTriple(s) => s.clone(),
+        TargetTriple::TargetPath(path) => {
+            path.to_str().expect("target path must be valid unicode").to_string()
+        }
+    });
+    if let ErrorOutputType::HumanReadable(kind) = rustdoc_options.error_format {
+        let (short, color_config) = kind.unzip();
+
+        if short {
+            compiler.arg("--error-format").arg("short");
+        }
+
+        match color_config {
+            ColorConfig::Never => {
+                compiler.arg("--color").arg("never");
+            }
+            ColorConfig::Always => {
+                compiler.arg("--color").arg("always");
+            }
+            ColorConfig::Auto => {
+                compiler.arg("--color").arg(if supports_color { "always" } else { "never" });
+            }
+        }
+    }
+    compiler
+}
+
+/// Runs a compiled doctest, passing it `args`, and checks how it exited.
+fn run_executable(
+    output_file: &Path,
+    args: &[&str],
+    lang_string: &LangString,
+    rustdoc_options: &RustdocOptions,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+) -> Result<(), TestFailure> {
     // Run the code!
     let mut cmd;
 
@@ -463,7 +513,8 @@ fn drop(&mut self) {
     } else {
         cmd = Command::new(output_file);
     }
-    if let Some(run_directory) = rustdoc_options.test_run_directory {
+    cmd.args(args);
+    if let Some(ref run_directory) = rustdoc_options.test_run_directory {
         cmd.current_dir(run_directory);
     }
 
@@ -504,7 +555,6 @@ pub(crate) fn make_test(
     let everything_else = everything_else.trim();
     let mut line_offset = 0;
     let mut prog = String::new();
-    let mut supports_color = false;
 
     if opts.attrs.is_empty() {
         // If there aren't any attributes supplied by #![doc(test(attr(...)))], then allow some
@@ -528,6 +578,98 @@ pub(crate) fn make_test(
 
     // Uses librustc_ast to parse the doctest and find if there's a main fn and the extern
     // crate already is included.
+    let Some(ParsedSource {
+        has_main: already_has_main,
+        has_extern_crate: already_has_extern_crate,
+        supports_color,
+    }) = parse_source(s, crates + everything_else, crate_name, edition)
+    else {
+        // If the parser panicked due to a fatal error, pass the test code through unchanged.
+        // The error will be reported during compilation.
+        return (s.to_owned(), 0, false);
+    };
+
+    // Don't inject `extern crate std` because it's already injected by the
+    // compiler.
+    if !already_has_extern_crate && !opts.no_crate_inject && crate_name != Some("std") {
+        if let Some(crate_name) = crate_name {
+            // Don't inject `extern crate` if the crate is never used.
+            // NOTE: this is terribly inaccurate because it doesn't actually
+            // parse the source, but only has false positives, not false
+            // negatives.
+            if s.contains(crate_name) {
+                prog.push_str(&format!("extern crate r#{crate_name};\n"));
+                line_offset += 1;
+            }
+        }
+    }
+
+    // FIXME: This code cannot yet handle no_std test cases yet
+    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
+        prog.push_str(everything_else);
+    } else {
+        let returns_result = everything_else.trim_end().ends_with("(())");
+        // Give each doctest main function a unique name.
+        // This is for example needed for the tooling around `-C instrument-coverage`.
+        let inner_fn_name = if let Some(test_id) = test_id {
+            format!("_doctest_main_{test_id}")
+        } else {
+            "_inner".into()
+        };
+        let inner_attr = if test_id.is_some() { "#[allow(non_snake_case)] " } else { "" };
+        let (main_pre, main_post) = if returns_result {
+            (
+                format!(
+                    "fn main() {{ {inner_attr}fn {inner_fn_name}() -> Result<(), impl core::fmt::Debug> {{\n",
+                ),
+                format!("\n}} {inner_fn_name}().unwrap() }}"),
+            )
+        } else if test_id.is_some() {
+            (
+                format!("fn main() {{ {inner_attr}fn {inner_fn_name}() {{\n",),
+                format!("\n}} {inner_fn_name}() }}"),
+            )
+        } else {
+            ("fn main() {\n".into(), "\n}".into())
+        };
+        // Note on newlines: We insert a line/newline *before*, and *after*
+        // the doctest and adjust the `line_offset` accordingly.
+        // In the case of `-C instrument-coverage`, this means that the generated
+        // inner `main` function spans from the doctest opening codeblock to the
+        // closing one. For example
+        // /// ``` <- start of the inner main
+        // /// <- code under doctest
+        // /// ``` <- end of the inner main
+        line_offset += 1;
+
+        prog.extend([&main_pre, everything_else, &main_post].iter().cloned());
+    }
+
+    debug!("final doctest:\n{prog}");
+
+    (prog, line_offset, supports_color)
+}
+
+/// What [`parse_source`] found out about a doctest.
+struct ParsedSource {
+    /// Whether the doctest has its own `fn main`.
+    has_main: bool,
+    /// Whether the doctest already has an `extern crate` of the documented crate.
+    has_extern_crate: bool,
+    /// Whether the output stream supports colors.
+    supports_color: bool,
+}
+
+/// Uses librustc_ast to parse the doctest `s`, given without its crate attributes as `source`,
+/// and find if there's a main fn and the extern crate already is included. Returns `None` if the
+/// parser panicked due to a fatal error.
+fn parse_source(
+    s: &str,
+    source: String,
+    crate_name: Option<&str>,
+    edition: Edition,
+) -> Option<ParsedSource> {
+    let mut supports_color = false;
     let result = rustc_driver::catch_fatal_errors(|| {
         rustc_span::create_session_if_not_set_then(edition, |_| {
             use rustc_errors::emitter::{Emitter, EmitterWriter};
@@ -536,7 +678,6 @@ pub(crate) fn make_test(
             use rustc_span::source_map::FilePathMapping;
 
             let filename = FileName::anon_source_code(s);
-            let source = crates + everything_else;
 
             // Any errors in parsing should also appear when the doctest is compiled for real, so just
             // send all the errors that librustc_ast emits directly into a `Sink` instead of stderr.
@@ -638,12 +779,7 @@ pub(crate) fn make_test(
             (found_main, found_extern_crate, found_macro)
         })
     });
-    let Ok((already_has_main, already_has_extern_crate, found_macro)) = result
-    else {
-        // If the parser panicked due to a fatal error, pass the test code through unchanged.
-        // The error will be reported during compilation.
-        return (s.to_owned(), 0, false);
-    };
+    let Ok((already_has_main, already_has_extern_crate, found_macro)) = result else { return None };
 
     // If a doctest's `fn main` is being masked by a wrapper macro, the parsing loop above won't
     // see it. In that case, run the old text-based scan to see if they at least have a main
@@ -660,65 +796,11 @@ pub(crate) fn make_test(
         already_has_main
     };
 
-    // Don't inject `extern crate std` because it's already injected by the
-    // compiler.
-    if !already_has_extern_crate && !opts.no_crate_inject && crate_name != Some("std") {
-        if let Some(crate_name) = crate_name {
-            // Don't inject `extern crate` if the crate is never used.
-            // NOTE: this is terribly inaccurate because it doesn't actually
-            // parse the source, but only has false positives, not false
-            // negatives.
-            if s.contains(crate_name) {
-                prog.push_str(&format!("extern crate r#{crate_name};\n"));
-                line_offset += 1;
-            }
-        }
-    }
-
-    // FIXME: This code cannot yet handle no_std test cases yet
-    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
-        prog.push_str(everything_else);
-    } else {
-        let returns_result = everything_else.trim_end().ends_with("(())");
-        // Give each doctest main function a unique name.
-        // This is for example needed for the tooling around `-C instrument-coverage`.
-        let inner_fn_name = if let Some(test_id) = test_id {
-            format!("_doctest_main_{test_id}")
-        } else {
-            "_inner".into()
-        };
-        let inner_attr = if test_id.is_some() { "#[allow(non_snake_case)] " } else { "" };
-        let (main_pre, main_post) = if returns_result {
-            (
-                format!(
-                    "fn main() {{ {inner_attr}fn {inner_fn_name}() -> Result<(), impl core::fmt::Debug> {{\n",
-                ),
-                format!("\n}} {inner_fn_name}().unwrap() }}"),
-            )
-        } else if test_id.is_some() {
-            (
-                format!("fn main() {{ {inner_attr}fn {inner_fn_name}() {{\n",),
-                format!("\n}} {inner_fn_name}() }}"),
-            )
-        } else {
-            ("fn main() {\n".into(), "\n}".into())
-        };
-        // Note on newlines: We insert a line/newline *before*, and *after*
-        // the doctest and adjust the `line_offset` accordingly.
-        // In the case of `-C instrument-coverage`, this means that the generated
-        // inner `main` function spans from the doctest opening codeblock to the
-        // closing one. For example
-        // /// ``` <- start of the inner main
-        // /// <- code under doctest
-        // /// ``` <- end of the inner main
-        line_offset += 1;
-
-        prog.extend([&main_pre, everything_else, &main_post].iter().cloned());
-    }
-
-    debug!("final doctest:\n{prog}");
-
-    (prog, line_offset, supports_color)
+    Some(ParsedSource {
+        has_main: already_has_main,
+        has_extern_crate: already_has_extern_crate,
+        supports_color,
+    })
 }
 
 fn check_if_attr_is_complete(source: &str, edition: Edition) -> bool {
@@ -890,6 +972,8 @@ pub(crate) struct Collector {
     visited_tests: FxHashMap<(String, usize), usize>,
     unused_extern_reports: Arc<Mutex<Vec<UnusedExterns>>>,
     compiling_test_count: AtomicUsize,
+    /// The harnesses the doctests are merged into with `--merge-doctests`, one per edition.
+    merged_doctests: FxHashMap<Edition, Arc<MergedDoctests>>,
 }
 
 impl Collector {
@@ -916,6 +1000,7 @@ pub(crate) fn new(
             visited_tests: FxHashMap::default(),
             unused_extern_reports: Default::default(),
             compiling_test_count: AtomicUsize::new(0),
+            merged_doctests: FxHashMap::default(),
         }
     }
 
@@ -1016,15 +1101,41 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
             )
         };
 
+        let ignore = match config.ignore {
+            Ignore::All => true,
+            Ignore::None => false,
+            Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
+        };
+        // The unused externs are reported per compilation, so merging doctests would lose track
+        // of them.
+        let merged = if self.rustdoc_options.merge_doctests
+            && !ignore
+            && !config.compile_fail
+            && !config.test_harness
+            && !self.rustdoc_options.json_unused_externs.is_enabled()
+        {
+            make_merged_test(&test, &crate_name, &opts, edition, &test_id).map(|merged_test| {
+                let merged_doctests = self.merged_doctests.entry(edition).or_insert_with(|| {
+                    Arc::new(MergedDoctests::new(
+                        crate_name.clone(),
+                        edition,
+                        opts.clone(),
+                        rustdoc_options.clone(),
+                    ))
+                });
+                let test_path = merged_test.test_path.clone();
+                merged_doctests.add(merged_test, &path);
+                (Arc::clone(merged_doctests), test_path)
+            })
+        } else {
+            None
+        };
+
         debug!("creating test {name}: {test}");
         self.tests.push(test::TestDescAndFn {
             desc: test::TestDesc {
                 name: test::DynTestName(name),
-                ignore: match config.ignore {
-                    Ignore::All => true,
-                    Ignore::None => false,
-                    Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
-                },
+                ignore,
                 ignore_message: None,
                 // compiler failures are test failures
                 should_panic: test::ShouldPanic::No,
@@ -1036,23 +1147,39 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
                 let report_unused_externs = |uext| {
                     unused_externs.lock().unwrap().push(uext);
                 };
-                let res = run_test(
-                    &test,
-                    &crate_name,
-                    line,
-                    rustdoc_options,
-                    config,
-                    no_run,
-                    runtool,
-                    runtool_args,
-                    target,
-                    &opts,
-                    edition,
-                    outdir,
-                    path,
-                    &test_id,
-                    report_unused_externs,
-                );
+                // Doctests whose harness failed to compile are compiled on their own instead.
+                let merged_binary = merged.as_ref().and_then(|(merged_doctests, test_path)| {
+                    merged_doctests.binary().map(|binary| (binary, test_path))
+                });
+                let res = if let Some((binary, test_path)) = merged_binary {
+                    run_merged_test(
+                        &binary,
+                        test_path,
+                        &config,
+                        no_run,
+                        &rustdoc_options,
+                        runtool,
+                        runtool_args,
+                    )
+                } else {
+                    run_test(
+                        &test,
+                        &crate_name,
+                        line,
+                        rustdoc_options,
+                        config,
+                        no_run,
+                        runtool,
+                        runtool_args,
+                        target,
+                        &opts,
+                        edition,
+                        outdir,
+                        path,
+                        &test_id,
+                        report_unused_externs,
+                    )
+                };
 
                 if let Err(err) = res {
                     match err {
@@ -0,0 +1,228 @@
+//! Compilation of several doctests into a single test harness, for `--merge-doctests`.
+//!
+//! Invoking rustc once per doctest is what makes running the doctests of a large crate slow. With
+//! `--merge-doctests`, the doctests which allow it are put in a module of their own, as the
+//! `#[test]` function of a single harness per edition. This harness is compiled the first time
+//! one of its doctests runs, and each doctest then runs as its own process, filtering the tests of
+//! the harness with `--exact` so that a failing doctest cannot take down the others.
+//!
+//! Each module is written to a file of its own, with the code of the doctest on the same lines as
+//! in the program `make_test` generates, and the path of the file is remapped to the path of the
+//! documented source. The panic messages of a merged doctest then point at the same locations as
+//! when it is compiled on its own.
+//!
+//! Doctests which cannot be merged, and the doctests of a harness which fails to compile, are
+//! compiled on their own as usual.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+
+use rustc_span::edition::Edition;
+use tempfile::Builder as TempFileBuilder;
+
+use super::{
+    parse_source, partition_source, rustc_command, DirState, GlobalTestOptions, ParsedSource,
+};
+use crate::config::Options as RustdocOptions;
+
+/// A doctest turned into a module of a merged harness by [`make_merged_test`].
+#[derive(Debug, PartialEq)]
+pub(crate) struct MergedTest {
+    /// The name of the module.
+    pub(crate) module_name: String,
+    /// The source of the module, which goes in a file of its own.
+    pub(crate) module: String,
+    /// The path of the `#[test]` function in the harness.
+    pub(crate) test_path: String,
+    /// Whether the doctest needs the documented crate to be injected with `extern crate`.
+    pub(crate) inject_crate: bool,
+}
+
+/// Turns a doctest into a module of a merged harness, or returns `None` if the doctest has to be
+/// compiled on its own: because it has crate attributes or its own `fn main`, or because of
+/// `extern crate`s which cannot be moved out of the crate root.
+pub(crate) fn make_merged_test(
+    s: &str,
+    crate_name: &str,
+    opts: &GlobalTestOptions,
+    edition: Edition,
+    test_id: &str,
+) -> Option<MergedTest> {
+    let (crate_attrs, everything_else, crates) = partition_source(s, edition);
+    if !crate_attrs.trim().is_empty() {
+        return None;
+    }
+    // `#[macro_use]` only works at the crate root, and, in the 2015 edition, `use` paths are
+    // relative to the crate root, so the crates would not be found from the module either.
+    if !crates.is_empty() && (crates.contains("macro_use") || edition == Edition::Edition2015) {
+        return None;
+    }
+    let everything_else = everything_else.trim();
+    let ParsedSource { has_main, has_extern_crate, .. } =
+        parse_source(s, crates.clone() + everything_else, Some(crate_name), edition)?;
+    if has_main {
+        return None;
+    }
+    let inject_crate =
+        !has_extern_crate && !opts.no_crate_inject && crate_name != "std" && s.contains(crate_name);
+
+    // Same wrapping as `make_test`, with the `#[test]` function in place of `fn main`. The lines
+    // `make_test` adds before the code, for the `#![doc(test(attr(...)))]` attributes and the
+    // `extern crate` of the documented crate, are left empty so that the code is on the same lines.
+    let module_name = format!("__doctest_{test_id}");
+    let inner_fn_name = format!("_doctest_main_{test_id}");
+    let (main_pre, main_post) = if everything_else.trim_end().ends_with("(())") {
+        (
+            format!(
+                "#[test] fn doctest() {{ #[allow(non_snake_case)] \
+                 fn {inner_fn_name}() -> Result<(), impl core::fmt::Debug> {{\n",
+            ),
+            format!("\n}} {inner_fn_name}().unwrap() }}"),
+        )
+    } else {
+        (
+            format!("#[test] fn doctest() {{ #[allow(non_snake_case)] fn {inner_fn_name}() {{\n"),
+            format!("\n}} {inner_fn_name}() }}"),
+        )
+    };
+    let attr_lines = "\n".repeat(opts.attrs.len().max(1));
+    let crate_line = if inject_crate { "\n" } else { "" };
+    let module =
+        format!("{attr_lines}{crates}{crate_line}{main_pre}{everything_else}{main_post}\n");
+    let test_path = format!("{module_name}::doctest");
+    Some(MergedTest { module_name, module, test_path, inject_crate })
+}
+
+/// The doctests of one edition merged into a single test harness.
+pub(crate) struct MergedDoctests {
+    crate_name: String,
+    edition: Edition,
+    opts: GlobalTestOptions,
+    rustdoc_options: RustdocOptions,
+    outdir: DirState,
+    state: Mutex<State>,
+}
+
+enum State {
+    /// The doctests are still being collected, with the path of the file each one comes from.
+    Collecting { tests: Vec<(MergedTest, PathBuf)>, inject_crate: bool },
+    /// The harness was compiled, into the given binary if it succeeded.
+    Compiled(Option<PathBuf>),
+}
+
+impl MergedDoctests {
+    pub(crate) fn new(
+        crate_name: String,
+        edition: Edition,
+        opts: GlobalTestOptions,
+        rustdoc_options: RustdocOptions,
+    ) -> MergedDoctests {
+        let outdir = if let Some(mut path) = rustdoc_options.persist_doctests.clone() {
+            path.push(format!("merged_doctests_{edition}"));
+
+            fs::create_dir_all(&path).expect("Couldn't create directory for doctest executables");
+
+            DirState::Perm(path)
+        } else {
+            DirState::Temp(
+                TempFileBuilder::new()
+                    .prefix("rustdoctest")
+                    .tempdir()
+                    .expect("rustdoc needs a tempdir"),
+            )
+        };
+        let state = Mutex::new(State::Collecting { tests: Vec::new(), inject_crate: false });
+        MergedDoctests { crate_name, edition, opts, rustdoc_options, outdir, state }
+    }
+
+    /// Adds a doctest from the file at `path` to the harness. This must happen before any of them
+    /// runs.
+    pub(crate) fn add(&self, test: MergedTest, path: &Path) {
+        match *self.state.lock().unwrap() {
+            State::Collecting { ref mut tests, ref mut inject_crate } => {
+                *inject_crate |= test.inject_crate;
+                tests.push((test, path.to_owned()));
+            }
+            State::Compiled(_) => panic!("doctest added to an already compiled harness"),
+        }
+    }
+
+    /// Returns the path of the harness binary, compiling it if this wasn't done yet, or `None` if
+    /// it failed to compile.
+    pub(crate) fn binary(&self) -> Option<PathBuf> {
+        let mut state = self.state.lock().unwrap();
+        if let State::Collecting { ref tests, inject_crate } = *state {
+            *state = State::Compiled(self.compile(tests, inject_crate));
+        }
+        match *state {
+            State::Compiled(ref binary) => binary.clone(),
+            State::Collecting { .. } => unreachable!(),
+        }
+    }
+
+    fn compile(&self, tests: &[(MergedTest, PathBuf)], inject_crate: bool) -> Option<PathBuf> {
+        let mut source = String::new();
+        if self.opts.attrs.is_empty() {
+            // See `make_test`.
+            source.push_str("#![allow(unused)]\n");
+        }
+        for attr in &self.opts.attrs {
+            source.push_str(&format!("#![{attr}]\n"));
+        }
+        if inject_crate {
+            source.push_str(&format!("extern crate r#{};\n", self.crate_name));
+        }
+
+        // There can be a lot of doctests, so the remappings are passed in an argument file rather
+        // than on the command line.
+        let mut remaps = String::new();
+        for (test, path) in tests {
+            let module_file = self.outdir.path().join(format!("{}.rs", test.module_name));
+            write_file(&module_file, &test.module)?;
+            source.push_str(&format!("#[path = \"{0}.rs\"]\nmod {0};\n", test.module_name));
+            remaps.push_str(&format!(
+                "--remap-path-prefix={}={}\n",
+                module_file.display(),
+                path.display()
+            ));
+        }
+
+        // The sources are written next to the binary rather than piped to rustc, so that they can
+        // be looked at with `--persist-doctests`.
+        let source_file = self.outdir.path().join("doctests.rs");
+        let remaps_file = self.outdir.path().join("remap-path-prefix.args");
+        let output_file = self.outdir.path().join("rust_out");
+        write_file(&source_file, &source)?;
+        write_file(&remaps_file, &remaps)?;
+
+        let target = &self.rustdoc_options.target;
+        let mut compiler = rustc_command(&self.rustdoc_options, target, self.edition, false);
+        compiler.arg("--test").arg("-o").arg(&output_file);
+        if self.rustdoc_options.no_run && self.rustdoc_options.persist_doctests.is_none() {
+            compiler.arg("--emit=metadata");
+        }
+        compiler.arg(format!("@{}", remaps_file.display()));
+        compiler.arg(&source_file);
+        compiler.stdout(Stdio::null());
+        let output = compiler.output().expect("Failed to spawn rustc process");
+        if output.status.success() {
+            Some(output_file)
+        } else {
+            // The doctests are then compiled on their own, which reports the errors.
+            debug!(
+                "merged doctests failed to compile:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            None
+        }
+    }
+}
+
+/// Writes one of the files of the harness, or returns `None` if this fails.
+fn write_file(path: &Path, contents: &str) -> Option<()> {
+    fs::write(path, contents)
+        .map_err(|err| debug!("couldn't write {} for the merged doctests: {err}", path.display()))
+        .ok()
+}
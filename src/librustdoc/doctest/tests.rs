@@ -1,5 +1,6 @@
+use super::merged::{make_merged_test, MergedTest};
 use super::{make_test, GlobalTestOptions};
-use rustc_span::edition::DEFAULT_EDITION;
+use rustc_span::edition::{Edition, DEFAULT_EDITION};
 
 #[test]
 fn make_test_basic() {
@@ -298,3 +299,91 @@ fn main() { #[allow(non_snake_case)] fn _doctest_main__some_unique_name() {
         make_test(input, None, false, &opts, DEFAULT_EDITION, Some("_some_unique_name"));
     assert_eq!((output, len), (expected, 2));
 }
+
+#[test]
+fn make_merged_test_basic() {
+    // wraps the test in a `#[test]` function of its own module, and asks for the crate to be
+    // injected at the root of the harness
+    let opts = GlobalTestOptions::default();
+    let input = "use asdf::qwop;
+assert_eq!(2+2, 4);";
+    let expected = MergedTest {
+        module_name: "__doctest_id".to_string(),
+        module: "
+
+#[test] fn doctest() { #[allow(non_snake_case)] fn _doctest_main_id() {
+use asdf::qwop;
+assert_eq!(2+2, 4);
+} _doctest_main_id() }
+"
+        .to_string(),
+        test_path: "__doctest_id::doctest".to_string(),
+        inject_crate: true,
+    };
+    let output = make_merged_test(input, "asdf", &opts, DEFAULT_EDITION, "id");
+    assert_eq!(output, Some(expected));
+}
+
+#[test]
+fn make_merged_test_result() {
+    // keeps the `Result` returning wrapper of `make_test`
+    let opts = GlobalTestOptions::default();
+    let input = "Ok::<(), ()>(())";
+    let expected = "
+#[test] fn doctest() { #[allow(non_snake_case)] fn _doctest_main_id() -> Result<(), impl core::fmt::Debug> {
+Ok::<(), ()>(())
+} _doctest_main_id().unwrap() }
+";
+    let output = make_merged_test(input, "asdf", &opts, DEFAULT_EDITION, "id").unwrap();
+    assert_eq!((output.module.as_str(), output.inject_crate), (expected, false));
+}
+
+#[test]
+fn make_merged_test_extern_crate() {
+    // since the 2018 edition, `extern crate` can be moved into the module of the test
+    let opts = GlobalTestOptions::default();
+    let input = "extern crate asdf;
+use asdf::qwop;";
+    let expected = "
+extern crate asdf;
+#[test] fn doctest() { #[allow(non_snake_case)] fn _doctest_main_id() {
+use asdf::qwop;
+} _doctest_main_id() }
+";
+    let output = make_merged_test(input, "asdf", &opts, Edition::Edition2018, "id").unwrap();
+    assert_eq!((output.module.as_str(), output.inject_crate), (expected, false));
+}
+
+#[test]
+fn make_merged_test_same_lines() {
+    // the code is on the same lines as in the test `make_test` generates, so that the locations
+    // of panics are the same
+    let inputs = ["assert_eq!(2+2, 4);", "use asdf::qwop;\nqwop();", "extern crate asdf;\nqwop();"];
+    let mut opts = GlobalTestOptions::default();
+    for input in inputs {
+        for attrs in [vec![], vec!["deny(warnings)".to_string(), "allow(dead_code)".to_string()]] {
+            opts.attrs = attrs;
+            let (test, ..) =
+                make_test(input, Some("asdf"), false, &opts, Edition::Edition2018, Some("id"));
+            let merged =
+                make_merged_test(input, "asdf", &opts, Edition::Edition2018, "id").unwrap();
+            let code = input.lines().last().unwrap();
+            let line = |source: &str| source.lines().position(|line| line == code);
+            assert_eq!(line(&merged.module), line(&test), "{input}");
+        }
+    }
+}
+
+#[test]
+fn make_merged_test_unmergeable() {
+    // tests which have to stay at the root of a crate of their own are left alone
+    let opts = GlobalTestOptions::default();
+    for (input, edition) in [
+        ("fn main() {}", Edition::Edition2018),
+        ("#![feature(sick_rad)]\nassert_eq!(2+2, 4);", Edition::Edition2018),
+        ("#[macro_use] extern crate asdf;\nqwop!();", Edition::Edition2018),
+        ("extern crate asdf;\nuse asdf::qwop;", Edition::Edition2015),
+    ] {
+        assert_eq!(make_merged_test(input, "asdf", &opts, edition, "id"), None, "{input}");
+    }
+}
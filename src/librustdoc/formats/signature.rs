@@ -0,0 +1,589 @@
+//! Signatures of items, as they are written in Rust, for the backends which print them as text.
+
+use std::fmt::Write;
+
+use rustc_hir as hir;
+use rustc_hir::def::CtorKind;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::hygiene::MacroKind;
+use rustc_span::symbol::kw;
+use rustc_target::spec::abi::Abi;
+
+use crate::clean::utils::find_nearest_parent_module;
+use crate::clean::{self, ItemId};
+use crate::formats::item_type::ItemType;
+use crate::html::format::{print_abi_with_space, PrintWithSpace};
+
+/// Prints the signatures of items, with the paths of types as they are written in the source.
+pub(crate) struct Signatures<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> Signatures<'tcx> {
+    pub(crate) fn new(tcx: TyCtxt<'tcx>) -> Signatures<'tcx> {
+        Signatures { tcx }
+    }
+
+    /// The signature of `item`, or `None` for the items which have none, like modules.
+    ///
+    /// Imports and `extern crate`s are printed as they are written, with their visibility.
+    pub(crate) fn item(&self, item: &clean::Item) -> Option<String> {
+        let tcx = self.tcx;
+        let vis = self.visibility(item);
+        if let clean::ImportItem(ref import) = *item.kind {
+            let path = import.source.path.whole_name();
+            return Some(match import.kind {
+                clean::ImportKind::Simple(name) if name == import.source.path.last() => {
+                    format!("{}use {};", vis, path)
+                }
+                clean::ImportKind::Simple(name) => format!("{}use {} as {};", vis, path, name),
+                clean::ImportKind::Glob => format!("{}use {}::*;", vis, path),
+            });
+        }
+        let name = item.name?;
+        let signature = match *item.kind {
+            clean::ExternCrateItem { src: Some(src) } => {
+                format!("{}extern crate {} as {};", vis, src, name)
+            }
+            clean::ExternCrateItem { src: None } => format!("{}extern crate {};", vis, name),
+            clean::StructItem(ref struct_) => {
+                let generics = &struct_.generics;
+                format!(
+                    "{}struct {}{}{}",
+                    vis,
+                    name,
+                    self.generic_params(&generics.params),
+                    self.struct_body(struct_.struct_type, generics, &struct_.fields),
+                )
+            }
+            clean::UnionItem(ref union_) => {
+                let generics = &union_.generics;
+                format!(
+                    "{}union {}{}{}",
+                    vis,
+                    name,
+                    self.generic_params(&generics.params),
+                    self.struct_body(CtorKind::Fictive, generics, &union_.fields),
+                )
+            }
+            clean::EnumItem(ref enum_) => {
+                let generics = &enum_.generics;
+                let mut signature = format!(
+                    "{}enum {}{}{} {{\n",
+                    vis,
+                    name,
+                    self.generic_params(&generics.params),
+                    self.where_clause(generics),
+                );
+                for variant in enum_.variants() {
+                    let _ = writeln!(signature, "    {},", self.variant(variant));
+                }
+                if enum_.has_stripped_entries() {
+                    signature.push_str("    // some variants omitted\n");
+                }
+                signature.push('}');
+                signature
+            }
+            clean::FunctionItem(ref function) | clean::ForeignFunctionItem(ref function) => {
+                format!("{}{}", vis, self.function(item, function))
+            }
+            clean::TraitItem(ref trait_) => {
+                let generics = &trait_.generics;
+                let mut signature = format!(
+                    "{}{}{}trait {}{}",
+                    vis,
+                    trait_.unsafety.print_with_space(),
+                    if trait_.is_auto { "auto " } else { "" },
+                    name,
+                    self.generic_params(&generics.params),
+                );
+                if !trait_.bounds.is_empty() {
+                    let _ = write!(signature, ": {}", self.bounds(&trait_.bounds));
+                }
+                signature.push_str(&self.where_clause(generics));
+                let items = trait_.items.iter().filter(|item| !item.is_stripped());
+                let items = items
+                    .filter_map(|item| {
+                        let body = if item.type_() == ItemType::Method { " { ... }" } else { ";" };
+                        Some(format!("    {}{}\n", self.assoc_item(item)?, body))
+                    })
+                    .collect::<String>();
+                if items.is_empty() {
+                    signature.push_str(" {}");
+                } else {
+                    let _ = write!(signature, " {{\n{}}}", items);
+                }
+                signature
+            }
+            clean::TraitAliasItem(ref alias) => {
+                let generics = &alias.generics;
+                format!(
+                    "{}trait {}{} = {}{};",
+                    vis,
+                    name,
+                    self.generic_params(&generics.params),
+                    self.bounds(&alias.bounds),
+                    self.where_clause(generics),
+                )
+            }
+            clean::TypedefItem(ref typedef) => {
+                let generics = &typedef.generics;
+                format!(
+                    "{}type {}{}{} = {};",
+                    vis,
+                    name,
+                    self.generic_params(&generics.params),
+                    self.where_clause(generics),
+                    self.ty(&typedef.type_),
+                )
+            }
+            clean::OpaqueTyItem(ref opaque) => {
+                let generics = &opaque.generics;
+                format!(
+                    "{}type {}{}{} = impl {};",
+                    vis,
+                    name,
+                    self.generic_params(&generics.params),
+                    self.where_clause(generics),
+                    self.bounds(&opaque.bounds),
+                )
+            }
+            clean::StaticItem(ref static_) | clean::ForeignStaticItem(ref static_) => format!(
+                "{}static {}{}: {};",
+                vis,
+                static_.mutability.print_with_space(),
+                name,
+                self.ty(&static_.type_),
+            ),
+            clean::ConstantItem(ref constant) => format!(
+                "{}const {}: {} = {};",
+                vis,
+                name,
+                self.ty(&constant.type_),
+                constant.expr(tcx),
+            ),
+            clean::ForeignTypeItem => format!("{}type {};", vis, name),
+            clean::MacroItem(ref macro_) => macro_.source.clone(),
+            clean::ProcMacroItem(ref macro_) => match macro_.kind {
+                MacroKind::Bang => format!("{}!() {{ /* proc-macro */ }}", name),
+                MacroKind::Attr => format!("#[{}]", name),
+                MacroKind::Derive => {
+                    let mut signature = format!("#[derive({})]", name);
+                    if !macro_.helpers.is_empty() {
+                        signature.push_str("\n\n// Attributes available to this derive:");
+                        for helper in &macro_.helpers {
+                            let _ = write!(signature, "\n#[{}]", helper);
+                        }
+                    }
+                    signature
+                }
+            },
+            _ => return None,
+        };
+        Some(signature)
+    }
+
+    /// The signature of an associated item, as written in a trait or an impl.
+    pub(crate) fn assoc_item(&self, item: &clean::Item) -> Option<String> {
+        let name = item.name?;
+        let vis = self.visibility(item);
+        let signature = match *item.kind {
+            clean::TyMethodItem(ref function) | clean::MethodItem(ref function, _) => {
+                format!("{}{}", vis, self.function(item, function))
+            }
+            clean::TyAssocConstItem(ref ty) => format!("const {}: {}", name, self.ty(ty)),
+            clean::AssocConstItem(ref ty, ref default) => {
+                format!("{}const {}: {} = {}", vis, name, self.ty(ty), default.expr(self.tcx))
+            }
+            clean::TyAssocTypeItem(ref generics, ref bounds) => {
+                let mut signature =
+                    format!("type {}{}", name, self.generic_params(&generics.params));
+                if !bounds.is_empty() {
+                    let _ = write!(signature, ": {}", self.bounds(bounds));
+                }
+                signature.push_str(&self.where_clause(generics));
+                signature
+            }
+            clean::AssocTypeItem(ref typedef, _) => {
+                let generics = &typedef.generics;
+                format!(
+                    "{}type {}{}{} = {}",
+                    vis,
+                    name,
+                    self.generic_params(&generics.params),
+                    self.where_clause(generics),
+                    self.ty(&typedef.type_),
+                )
+            }
+            _ => return None,
+        };
+        Some(signature)
+    }
+
+    fn function(&self, item: &clean::Item, function: &clean::Function) -> String {
+        let header = item.fn_header(self.tcx).unwrap();
+        let generics = &function.generics;
+        format!(
+            "{}{}fn {}{}{}{}",
+            if header.constness == hir::Constness::Const { "const " } else { "" },
+            self.fn_qualifiers(header.asyncness, header.unsafety, header.abi),
+            item.name.unwrap(),
+            self.generic_params(&generics.params),
+            self.fn_decl(&function.decl),
+            self.where_clause(generics),
+        )
+    }
+
+    fn fn_qualifiers(&self, asyncness: hir::IsAsync, unsafety: hir::Unsafety, abi: Abi) -> String {
+        format!(
+            "{}{}{:#}",
+            asyncness.print_with_space(),
+            unsafety.print_with_space(),
+            print_abi_with_space(abi),
+        )
+    }
+
+    /// The arguments and the return type of a function.
+    fn fn_decl(&self, decl: &clean::FnDecl) -> String {
+        let mut inputs = decl
+            .inputs
+            .values
+            .iter()
+            .map(|input| match input.to_self() {
+                Some(clean::SelfValue) => "self".to_owned(),
+                Some(clean::SelfBorrowed(lifetime, mutability)) => format!(
+                    "&{}{}self",
+                    lifetime.map(|lifetime| format!("{} ", lifetime.0)).unwrap_or_default(),
+                    mutability.print_with_space(),
+                ),
+                // The arguments of function pointers may have no name.
+                _ if input.name == kw::Empty => self.ty(&input.type_),
+                _ => format!("{}: {}", input.name, self.ty(&input.type_)),
+            })
+            .collect::<Vec<_>>();
+        if decl.c_variadic {
+            inputs.push("...".to_owned());
+        }
+        let mut out = format!("({})", inputs.join(", "));
+        if let clean::FnRetTy::Return(ref output) = decl.output {
+            let _ = write!(out, " -> {}", self.ty(output));
+        }
+        out
+    }
+
+    /// The fields of a struct or a union and its where clause, after its generic parameters.
+    fn struct_body(
+        &self,
+        kind: CtorKind,
+        generics: &clean::Generics,
+        fields: &[clean::Item],
+    ) -> String {
+        let where_clause = self.where_clause(generics);
+        match kind {
+            CtorKind::Fictive => {
+                let mut body = String::new();
+                for field in fields.iter().filter(|field| !field.is_stripped()) {
+                    let _ = writeln!(
+                        body,
+                        "    {}{}: {},",
+                        self.visibility(field),
+                        field.name.unwrap(),
+                        self.field_type(field),
+                    );
+                }
+                if fields.iter().any(|field| field.is_stripped()) {
+                    body.push_str("    /* private fields */\n");
+                }
+                if body.is_empty() {
+                    format!("{} {{}}", where_clause)
+                } else {
+                    format!("{} {{\n{}}}", where_clause, body)
+                }
+            }
+            CtorKind::Fn => {
+                let fields = fields
+                    .iter()
+                    .map(|field| match *field.kind {
+                        clean::StrippedItem(_) => "_".to_owned(),
+                        _ => format!("{}{}", self.visibility(field), self.field_type(field)),
+                    })
+                    .collect::<Vec<_>>();
+                format!("({}){};", fields.join(", "), where_clause)
+            }
+            CtorKind::Const => format!("{};", where_clause),
+        }
+    }
+
+    pub(crate) fn variant(&self, variant: &clean::Item) -> String {
+        let name = variant.name.unwrap();
+        match *variant.kind {
+            clean::VariantItem(clean::Variant::Tuple(ref fields)) => {
+                let fields = fields
+                    .iter()
+                    .map(|field| match *field.kind {
+                        clean::StrippedItem(_) => "_".to_owned(),
+                        _ => self.field_type(field),
+                    })
+                    .collect::<Vec<_>>();
+                format!("{}({})", name, fields.join(", "))
+            }
+            clean::VariantItem(clean::Variant::Struct(ref struct_)) => {
+                let mut fields = struct_
+                    .fields
+                    .iter()
+                    .filter(|field| !field.is_stripped())
+                    .map(|field| format!("{}: {}", field.name.unwrap(), self.field_type(field)))
+                    .collect::<Vec<_>>();
+                if struct_.has_stripped_entries() {
+                    fields.push("..".to_owned());
+                }
+                format!("{} {{ {} }}", name, fields.join(", "))
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    pub(crate) fn field_type(&self, field: &clean::Item) -> String {
+        match *field.kind {
+            clean::StructFieldItem(ref ty) => self.ty(ty),
+            _ => "_".to_owned(),
+        }
+    }
+
+    pub(crate) fn impl_header(&self, impl_: &clean::Impl) -> String {
+        let generics = &impl_.generics;
+        let mut header = format!(
+            "{}impl{} ",
+            impl_.unsafety.print_with_space(),
+            self.generic_params(&generics.params),
+        );
+        if let Some(ref trait_) = impl_.trait_ {
+            if impl_.polarity == ty::ImplPolarity::Negative {
+                header.push('!');
+            }
+            let _ = write!(header, "{} for ", self.path(trait_));
+        }
+        header.push_str(&self.ty(&impl_.for_));
+        header.push_str(&self.where_clause(generics));
+        header
+    }
+
+    /// Follows [`clean::Visibility::print_with_space`], without the links.
+    pub(crate) fn visibility(&self, item: &clean::Item) -> String {
+        let tcx = self.tcx;
+        match item.visibility {
+            clean::Visibility::Public => "pub ".to_owned(),
+            clean::Visibility::Inherited => String::new(),
+            clean::Visibility::Restricted(vis_did) => {
+                let parent_module = match item.item_id {
+                    ItemId::DefId(did) => find_nearest_parent_module(tcx, did),
+                    _ => None,
+                };
+                if vis_did.is_crate_root() {
+                    "pub(crate) ".to_owned()
+                } else if parent_module == Some(vis_did) {
+                    String::new()
+                } else if parent_module.and_then(|parent| find_nearest_parent_module(tcx, parent))
+                    == Some(vis_did)
+                {
+                    "pub(super) ".to_owned()
+                } else {
+                    format!("pub(in {}) ", tcx.def_path_str(vis_did))
+                }
+            }
+        }
+    }
+
+    fn generic_params(&self, params: &[clean::GenericParamDef]) -> String {
+        let params = params
+            .iter()
+            .filter(|param| !param.is_synthetic_type_param())
+            .map(|param| self.generic_param(param))
+            .collect::<Vec<_>>();
+        if params.is_empty() { String::new() } else { format!("<{}>", params.join(", ")) }
+    }
+
+    fn generic_param(&self, param: &clean::GenericParamDef) -> String {
+        match param.kind {
+            clean::GenericParamDefKind::Lifetime { ref outlives } if outlives.is_empty() => {
+                param.name.to_string()
+            }
+            clean::GenericParamDefKind::Lifetime { ref outlives } => {
+                format!("{}: {}", param.name, self.list(outlives, " + ", |lt| lt.0.to_string()))
+            }
+            clean::GenericParamDefKind::Type { ref bounds, ref default, .. } => {
+                let mut out = param.name.to_string();
+                if !bounds.is_empty() {
+                    let _ = write!(out, ": {}", self.bounds(bounds));
+                }
+                if let Some(default) = default {
+                    let _ = write!(out, " = {}", self.ty(default));
+                }
+                out
+            }
+            clean::GenericParamDefKind::Const { ref ty, ref default, .. } => {
+                let mut out = format!("const {}: {}", param.name, self.ty(ty));
+                if let Some(default) = default {
+                    let _ = write!(out, " = {}", default);
+                }
+                out
+            }
+        }
+    }
+
+    fn where_clause(&self, generics: &clean::Generics) -> String {
+        if generics.where_predicates.is_empty() {
+            return String::new();
+        }
+        let predicates = self.list(&generics.where_predicates, ", ", |predicate| match predicate {
+            clean::WherePredicate::BoundPredicate { ty, bounds, bound_params } => {
+                let mut out = String::new();
+                if !bound_params.is_empty() {
+                    let params = self.list(bound_params, ", ", |lt| lt.0.to_string());
+                    let _ = write!(out, "for<{}> ", params);
+                }
+                let _ = write!(out, "{}: {}", self.ty(ty), self.bounds(bounds));
+                out
+            }
+            clean::WherePredicate::RegionPredicate { lifetime, bounds } => {
+                format!("{}: {}", lifetime.0, self.bounds(bounds))
+            }
+            clean::WherePredicate::EqPredicate { lhs, rhs } => {
+                format!("{} = {}", self.ty(lhs), self.term(rhs))
+            }
+        });
+        format!(" where {}", predicates)
+    }
+
+    /// The `for<...>` binder of higher-ranked function pointers and trait bounds.
+    fn higher_ranked(&self, params: &[clean::GenericParamDef]) -> String {
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("for<{}> ", self.list(params, ", ", |param| self.generic_param(param)))
+        }
+    }
+
+    fn bounds(&self, bounds: &[clean::GenericBound]) -> String {
+        self.list(bounds, " + ", |bound| match bound {
+            clean::GenericBound::TraitBound(poly_trait, modifier) => {
+                let modifier = match modifier {
+                    hir::TraitBoundModifier::None => "",
+                    hir::TraitBoundModifier::Maybe => "?",
+                    hir::TraitBoundModifier::MaybeConst => "~const ",
+                };
+                format!("{}{}", modifier, self.poly_trait(poly_trait))
+            }
+            clean::GenericBound::Outlives(lifetime) => lifetime.0.to_string(),
+        })
+    }
+
+    fn poly_trait(&self, poly_trait: &clean::PolyTrait) -> String {
+        let higher_ranked = self.higher_ranked(&poly_trait.generic_params);
+        format!("{}{}", higher_ranked, self.path(&poly_trait.trait_))
+    }
+
+    fn ty(&self, ty: &clean::Type) -> String {
+        match ty {
+            clean::Type::Path { path } => self.path(path),
+            clean::Type::DynTrait(traits, lifetime) => {
+                let mut out = format!("dyn {}", self.list(traits, " + ", |t| self.poly_trait(t)));
+                if let Some(lifetime) = lifetime {
+                    let _ = write!(out, " + {}", lifetime.0);
+                }
+                out
+            }
+            clean::Type::Generic(name) => name.to_string(),
+            clean::Type::Primitive(primitive) => primitive.as_sym().to_string(),
+            clean::Type::BareFunction(function) => format!(
+                "{}{}fn{}",
+                self.higher_ranked(&function.generic_params),
+                self.fn_qualifiers(hir::IsAsync::NotAsync, function.unsafety, function.abi),
+                self.fn_decl(&function.decl),
+            ),
+            clean::Type::Tuple(types) if types.len() == 1 => format!("({},)", self.ty(&types[0])),
+            clean::Type::Tuple(types) => format!("({})", self.list(types, ", ", |ty| self.ty(ty))),
+            clean::Type::Slice(ty) => format!("[{}]", self.ty(ty)),
+            clean::Type::Array(ty, len) => format!("[{}; {}]", self.ty(ty), len),
+            clean::Type::RawPointer(mutability, ty) => format!(
+                "*{} {}",
+                if *mutability == hir::Mutability::Mut { "mut" } else { "const" },
+                self.ty(ty),
+            ),
+            clean::Type::BorrowedRef { lifetime, mutability, type_ } => format!(
+                "&{}{}{}",
+                lifetime.as_ref().map(|lifetime| format!("{} ", lifetime.0)).unwrap_or_default(),
+                mutability.print_with_space(),
+                self.ty(type_),
+            ),
+            clean::Type::QPath { assoc, self_type, trait_, should_show_cast } => {
+                let self_type = if *should_show_cast {
+                    format!("<{} as {}>", self.ty(self_type), self.path(trait_))
+                } else {
+                    self.ty(self_type)
+                };
+                format!("{}::{}{}", self_type, assoc.name, self.generic_args(&assoc.args))
+            }
+            clean::Type::Infer => "_".to_owned(),
+            clean::Type::ImplTrait(bounds) => format!("impl {}", self.bounds(bounds)),
+        }
+    }
+
+    fn path(&self, path: &clean::Path) -> String {
+        self.list(&path.segments, "::", |segment| {
+            if segment.name == kw::PathRoot {
+                String::new()
+            } else {
+                format!("{}{}", segment.name, self.generic_args(&segment.args))
+            }
+        })
+    }
+
+    fn generic_args(&self, args: &clean::GenericArgs) -> String {
+        match args {
+            clean::GenericArgs::AngleBracketed { args, bindings } => {
+                let args = args
+                    .iter()
+                    .map(|arg| match arg {
+                        clean::GenericArg::Lifetime(lifetime) => lifetime.0.to_string(),
+                        clean::GenericArg::Type(ty) => self.ty(ty),
+                        clean::GenericArg::Const(constant) => constant.expr(self.tcx),
+                        clean::GenericArg::Infer => "_".to_owned(),
+                    })
+                    .chain(bindings.iter().map(|binding| self.binding(binding)))
+                    .collect::<Vec<_>>();
+                if args.is_empty() { String::new() } else { format!("<{}>", args.join(", ")) }
+            }
+            clean::GenericArgs::Parenthesized { inputs, output } => {
+                let mut out = format!("({})", self.list(inputs, ", ", |ty| self.ty(ty)));
+                if let Some(output) = output {
+                    let _ = write!(out, " -> {}", self.ty(output));
+                }
+                out
+            }
+        }
+    }
+
+    fn binding(&self, binding: &clean::TypeBinding) -> String {
+        let name = format!("{}{}", binding.assoc.name, self.generic_args(&binding.assoc.args));
+        match binding.kind {
+            clean::TypeBindingKind::Equality { ref term } => {
+                format!("{} = {}", name, self.term(term))
+            }
+            clean::TypeBindingKind::Constraint { ref bounds } => {
+                format!("{}: {}", name, self.bounds(bounds))
+            }
+        }
+    }
+
+    fn term(&self, term: &clean::Term) -> String {
+        match term {
+            clean::Term::Type(ty) => self.ty(ty),
+            clean::Term::Constant(constant) => constant.expr(self.tcx),
+        }
+    }
+
+    fn list<T>(&self, items: &[T], separator: &str, print: impl Fn(&T) -> String) -> String {
+        items.iter().map(print).collect::<Vec<_>>().join(separator)
+    }
+}
@@ -0,0 +1,232 @@
+//! Packing of the generated documentation into a single zip archive (`--bundle`), so that it
+//! can be handed around and browsed offline like any other file.
+//!
+//! Everything found in the output directory goes into the archive: the static files, the
+//! search index and every crate documented there, which includes the dependencies when they
+//! were documented into the same directory. Entries are stored uncompressed and with a fixed
+//! timestamp, so the same documentation always produces the same archive.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::docfs::PathError;
+use crate::error::Error;
+
+#[cfg(test)]
+mod tests;
+
+/// Writes the content of `src` into the zip archive `dst`. The entries are placed in a top-level
+/// directory named after the archive, so `docs.zip` extracts into `docs/`.
+pub(super) fn write_bundle(src: &Path, dst: &Path) -> Result<(), Error> {
+    let prefix = dst.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+    let prefix = prefix.unwrap_or_else(|| "doc".to_owned());
+    let out = File::create(dst).map_err(|e| Error::new(e, dst))?;
+    let archive = fs::canonicalize(dst).map_err(|e| Error::new(e, dst))?;
+    let mut files = Vec::new();
+    collect_files(src, &prefix, &archive, &mut files).map_err(|e| Error::new(e, src))?;
+    files.sort();
+
+    let mut zip = ZipWriter::new(BufWriter::new(out));
+    for (name, path) in &files {
+        let data = fs::read(path).map_err(|e| Error::new(e, path))?;
+        zip.add(name, &data).map_err(|e| Error::new(e, dst))?;
+    }
+    zip.finish().and_then(|mut out| out.flush()).map_err(|e| Error::new(e, dst))
+}
+
+/// Collects the files under `dir` along with their name in the archive. Hidden files (such as
+/// the `.lock` file cargo leaves in `target/doc`) and the archive itself, given as a canonical
+/// path, are skipped.
+fn collect_files(
+    dir: &Path,
+    name: &str,
+    archive: &Path,
+    files: &mut Vec<(String, PathBuf)>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        let path = entry.path();
+        if file_name.starts_with('.') {
+            continue;
+        }
+        if archive.file_name() == Some(&entry.file_name()) && fs::canonicalize(&path)? == archive {
+            continue;
+        }
+        let entry_name = format!("{}/{}", name, file_name);
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, &entry_name, archive, files)?;
+        } else {
+            files.push((entry_name, path));
+        }
+    }
+    Ok(())
+}
+
+/// The "version needed to extract" of the entries: 2.0, or 4.5 once zip64 records are needed.
+const VERSION: u16 = 20;
+const VERSION_ZIP64: u16 = 45;
+/// General purpose flag marking the entry names as UTF-8.
+const FLAG_UTF8: u16 = 1 << 11;
+/// MS-DOS date of the entries, 1980-01-01, the earliest one the format can represent.
+const DOS_DATE: u16 = (1 << 5) | 1;
+/// Unix permissions of the entries (`-rw-r--r--`), stored in the high half of the external
+/// attributes.
+const UNIX_MODE: u32 = 0o100644;
+
+/// A minimal writer of zip archives, storing its entries without compression.
+pub(super) struct ZipWriter<W: Write> {
+    out: W,
+    offset: u64,
+    entries: Vec<CentralEntry>,
+}
+
+struct CentralEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u64,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub(super) fn new(out: W) -> Self {
+        ZipWriter { out, offset: 0, entries: Vec::new() }
+    }
+
+    pub(super) fn add(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let size = u32::try_from(data.len()).map_err(|_| {
+            io::Error::new(io::ErrorKind::Other, format!("`{}` is too large to be bundled", name))
+        })?;
+        let name_len = u16::try_from(name.len()).map_err(|_| {
+            io::Error::new(io::ErrorKind::Other, format!("`{}` is too long a path", name))
+        })?;
+        let crc = crc32(data);
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        push_u32(&mut header, 0x04034b50);
+        push_u16(&mut header, VERSION);
+        push_u16(&mut header, FLAG_UTF8);
+        push_u16(&mut header, 0); // stored
+        push_u16(&mut header, 0); // time
+        push_u16(&mut header, DOS_DATE);
+        push_u32(&mut header, crc);
+        push_u32(&mut header, size); // compressed size
+        push_u32(&mut header, size);
+        push_u16(&mut header, name_len);
+        push_u16(&mut header, 0); // extra field length
+        header.extend_from_slice(name.as_bytes());
+        self.out.write_all(&header)?;
+        self.out.write_all(data)?;
+
+        self.entries.push(CentralEntry { name: name.to_owned(), crc, size, offset: self.offset });
+        self.offset += (header.len() + data.len()) as u64;
+        Ok(())
+    }
+
+    /// Writes the central directory and returns the underlying writer.
+    pub(super) fn finish(mut self) -> io::Result<W> {
+        let directory_offset = self.offset;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            // Local headers located past 4GiB have their offset in a zip64 extra field.
+            let zip64 = entry.offset >= u32::MAX as u64;
+            push_u32(&mut directory, 0x02014b50);
+            push_u16(&mut directory, (3 << 8) | VERSION_ZIP64); // made by: Unix
+            push_u16(&mut directory, if zip64 { VERSION_ZIP64 } else { VERSION });
+            push_u16(&mut directory, FLAG_UTF8);
+            push_u16(&mut directory, 0); // stored
+            push_u16(&mut directory, 0); // time
+            push_u16(&mut directory, DOS_DATE);
+            push_u32(&mut directory, entry.crc);
+            push_u32(&mut directory, entry.size);
+            push_u32(&mut directory, entry.size);
+            push_u16(&mut directory, entry.name.len() as u16);
+            push_u16(&mut directory, if zip64 { 12 } else { 0 }); // extra field length
+            push_u16(&mut directory, 0); // comment length
+            push_u16(&mut directory, 0); // disk number
+            push_u16(&mut directory, 0); // internal attributes
+            push_u32(&mut directory, UNIX_MODE << 16);
+            push_u32(&mut directory, if zip64 { u32::MAX } else { entry.offset as u32 });
+            directory.extend_from_slice(entry.name.as_bytes());
+            if zip64 {
+                push_u16(&mut directory, 0x0001);
+                push_u16(&mut directory, 8);
+                push_u64(&mut directory, entry.offset);
+            }
+        }
+        let directory_size = directory.len() as u64;
+        let count = self.entries.len() as u64;
+
+        // Archives with too many entries, or too large, need the zip64 end records, in which
+        // case the fields of the regular one are saturated.
+        let zip64 = count >= u16::MAX as u64
+            || directory_offset >= u32::MAX as u64
+            || directory_size >= u32::MAX as u64;
+        if zip64 {
+            let end_offset = directory_offset + directory_size;
+            push_u32(&mut directory, 0x06064b50);
+            push_u64(&mut directory, 44); // size of the rest of the record
+            push_u16(&mut directory, (3 << 8) | VERSION_ZIP64);
+            push_u16(&mut directory, VERSION_ZIP64);
+            push_u32(&mut directory, 0); // disk number
+            push_u32(&mut directory, 0); // disk of the central directory
+            push_u64(&mut directory, count);
+            push_u64(&mut directory, count);
+            push_u64(&mut directory, directory_size);
+            push_u64(&mut directory, directory_offset);
+
+            push_u32(&mut directory, 0x07064b50);
+            push_u32(&mut directory, 0); // disk of the zip64 end record
+            push_u64(&mut directory, end_offset);
+            push_u32(&mut directory, 1); // number of disks
+        }
+
+        push_u32(&mut directory, 0x06054b50);
+        push_u16(&mut directory, 0); // disk number
+        push_u16(&mut directory, 0); // disk of the central directory
+        push_u16(&mut directory, count.min(u16::MAX as u64) as u16);
+        push_u16(&mut directory, count.min(u16::MAX as u64) as u16);
+        push_u32(&mut directory, directory_size.min(u32::MAX as u64) as u32);
+        push_u32(&mut directory, directory_offset.min(u32::MAX as u64) as u32);
+        push_u16(&mut directory, 0); // comment length
+        self.out.write_all(&directory)?;
+        Ok(self.out)
+    }
+}
+
+fn push_u16(buf: &mut Vec<u8>, value: u16) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u64(buf: &mut Vec<u8>, value: u64) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Lookup table of the CRC-32 (IEEE) checksum used by zip archives.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data
+        .iter()
+        .fold(!0, |crc, &byte| CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
@@ -0,0 +1,51 @@
+use super::{crc32, ZipWriter};
+
+fn u16_at(buf: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([buf[pos], buf[pos + 1]])
+}
+
+fn u32_at(buf: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([buf[pos], buf[pos + 1], buf[pos + 2], buf[pos + 3]])
+}
+
+#[test]
+fn test_crc32() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414fa339);
+}
+
+#[test]
+fn test_zip_layout() {
+    let mut zip = ZipWriter::new(Vec::new());
+    zip.add("doc/index.html", b"<html></html>").unwrap();
+    zip.add("doc/search-index.js", b"").unwrap();
+    let buf = zip.finish().unwrap();
+
+    // First local header.
+    assert_eq!(u32_at(&buf, 0), 0x04034b50);
+    assert_eq!(u32_at(&buf, 14), crc32(b"<html></html>"));
+    assert_eq!(u32_at(&buf, 22), 13);
+    assert_eq!(&buf[30..44], b"doc/index.html");
+    assert_eq!(&buf[44..57], b"<html></html>");
+    // Second local header, right after the first entry.
+    assert_eq!(u32_at(&buf, 57), 0x04034b50);
+    assert_eq!(&buf[87..106], b"doc/search-index.js");
+
+    // End of central directory record.
+    let end = buf.len() - 22;
+    assert_eq!(u32_at(&buf, end), 0x06054b50);
+    assert_eq!(u16_at(&buf, end + 10), 2);
+    let directory_size = u32_at(&buf, end + 12) as usize;
+    let directory_offset = u32_at(&buf, end + 16) as usize;
+    assert_eq!(directory_offset, 106);
+    assert_eq!(directory_offset + directory_size, end);
+
+    // Central directory entries point back at the local headers.
+    assert_eq!(u32_at(&buf, directory_offset), 0x02014b50);
+    assert_eq!(u32_at(&buf, directory_offset + 42), 0);
+    assert_eq!(&buf[directory_offset + 46..directory_offset + 60], b"doc/index.html");
+    let second = directory_offset + 46 + 14;
+    assert_eq!(u32_at(&buf, second), 0x02014b50);
+    assert_eq!(u32_at(&buf, second + 42), 57);
+}
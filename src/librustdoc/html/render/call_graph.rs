@@ -0,0 +1,222 @@
+//! The call graph of the documented crate (`--show-call-graph`): for every function and method,
+//! the functions of the crate which call it ("Used by") and the ones it calls ("Calls").
+//!
+//! Like `--scrape-examples`, the calls are found by visiting the bodies of the crate and
+//! resolving the callee of every call expression, but only the calls between functions which
+//! are documented are kept, so private functions only show up with `--document-private-items`.
+//! The graph is also written as JSON next to the docs.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, TyCtxt};
+use serde::Serialize;
+
+use super::Context;
+use crate::clean;
+use crate::html::escape::Escape;
+use crate::html::format::{href, Buffer};
+use crate::visit::DocVisitor;
+
+#[derive(Default)]
+pub(crate) struct CallGraph {
+    /// The functions calling each function.
+    callers: FxHashMap<DefId, FxHashSet<DefId>>,
+    /// The functions called by each function.
+    callees: FxHashMap<DefId, FxHashSet<DefId>>,
+}
+
+/// The JSON representation of a function in the call graph.
+#[derive(Serialize)]
+struct FunctionCalls {
+    calls: Vec<String>,
+    used_by: Vec<String>,
+}
+
+impl CallGraph {
+    pub(crate) fn new(tcx: TyCtxt<'_>, krate: &clean::Crate) -> Self {
+        let mut documented = FindDocumentedFunctions { functions: FxHashSet::default() };
+        documented.visit_crate(krate);
+        let mut finder =
+            FindCalls { tcx, documented: documented.functions, graph: CallGraph::default() };
+        tcx.hir().deep_visit_all_item_likes(&mut finder);
+        finder.graph
+    }
+
+    fn add_call(&mut self, caller: DefId, callee: DefId) {
+        self.callers.entry(callee).or_default().insert(caller);
+        self.callees.entry(caller).or_default().insert(callee);
+    }
+
+    /// Returns the JSON representation of the graph, which maps the path of every function to
+    /// the paths of the functions it calls and of the functions calling it.
+    pub(crate) fn to_json(&self, tcx: TyCtxt<'_>) -> String {
+        let mut functions = BTreeMap::new();
+        for &did in self.callers.keys().chain(self.callees.keys()) {
+            functions.entry(display_name(tcx, did)).or_insert_with(|| FunctionCalls {
+                calls: sorted_names(tcx, self.callees.get(&did)),
+                used_by: sorted_names(tcx, self.callers.get(&did)),
+            });
+        }
+        serde_json::to_string(&functions).unwrap()
+    }
+}
+
+fn sorted_names(tcx: TyCtxt<'_>, functions: Option<&FxHashSet<DefId>>) -> Vec<String> {
+    let mut names =
+        functions.into_iter().flatten().map(|&did| display_name(tcx, did)).collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Returns the path of a function of the crate, starting with the name of the crate.
+fn display_name(tcx: TyCtxt<'_>, did: DefId) -> String {
+    let path = with_no_trimmed_paths!(tcx.def_path_str(did));
+    let path = path.strip_prefix("crate::").unwrap_or(&path);
+    // Trait impl items are printed as `<Type as Trait>::item`.
+    if path.starts_with('<') {
+        path.to_owned()
+    } else {
+        format!("{}::{}", tcx.crate_name(LOCAL_CRATE), path)
+    }
+}
+
+/// Visitor for finding the functions and methods which get rendered, i.e. the ones which
+/// haven't been stripped from the cleaned crate.
+struct FindDocumentedFunctions {
+    functions: FxHashSet<DefId>,
+}
+
+impl DocVisitor for FindDocumentedFunctions {
+    fn visit_item(&mut self, item: &clean::Item) {
+        if item.is_stripped() {
+            return;
+        }
+        if let clean::FunctionItem(..) | clean::MethodItem(..) | clean::TyMethodItem(..) =
+            *item.kind
+        {
+            self.functions.extend(item.item_id.as_def_id());
+        }
+        self.visit_item_recur(item)
+    }
+}
+
+/// Visitor for traversing a crate and finding the calls between its functions.
+struct FindCalls<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The functions of the crate which are documented, the calls from and to the other ones
+    /// are left out.
+    documented: FxHashSet<DefId>,
+    graph: CallGraph,
+}
+
+impl<'tcx> Visitor<'tcx> for FindCalls<'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
+        intravisit::walk_expr(self, ex);
+
+        let tcx = self.tcx;
+        // Calling typeck on an expression outside a function body panics, see the same check in
+        // `scrape_examples`.
+        let hir = tcx.hir();
+        if hir.maybe_body_owned_by(hir.local_def_id_to_hir_id(ex.hir_id.owner)).is_none() {
+            return;
+        }
+
+        let callee = match ex.kind {
+            hir::ExprKind::Call(f, _) => {
+                match tcx.typeck(ex.hir_id.owner).node_type_opt(f.hir_id).map(|ty| ty.kind()) {
+                    Some(&ty::FnDef(def_id, _)) => def_id,
+                    _ => return,
+                }
+            }
+            hir::ExprKind::MethodCall(..) => {
+                match tcx.typeck(ex.hir_id.owner).type_dependent_def_id(ex.hir_id) {
+                    Some(def_id) => def_id,
+                    None => return,
+                }
+            }
+            _ => return,
+        };
+        // Closures are part of the body of their parent, so the owner is the calling item.
+        let caller = ex.hir_id.owner.to_def_id();
+
+        // Calls from the code generated by derives and other macros generating whole items
+        // aren't written by the authors of the crate.
+        if !callee.is_local()
+            || !self.documented.contains(&caller)
+            || !self.documented.contains(&callee)
+            || tcx.def_span(caller).from_expansion()
+        {
+            return;
+        }
+        self.graph.add_call(caller, callee);
+    }
+}
+
+/// Returns the URL of the documentation of a function, if it is documented.
+fn function_href(cx: &Context<'_>, did: DefId) -> Option<String> {
+    let tcx = cx.tcx();
+    if tcx.def_kind(did) != DefKind::AssocFn {
+        return href(did, cx).ok().map(|(url, ..)| url);
+    }
+    // Methods are documented on the page of their type or trait.
+    let item = tcx.associated_item(did);
+    let (parent, anchor) = match item.container {
+        ty::TraitContainer(trait_did) if item.defaultness.has_value() => (trait_did, "method"),
+        ty::TraitContainer(trait_did) => (trait_did, "tymethod"),
+        ty::ImplContainer(impl_did) => (tcx.type_of(impl_did).ty_adt_def()?.did(), "method"),
+    };
+    let (url, ..) = href(parent, cx).ok()?;
+    Some(format!("{}#{}.{}", url, anchor, item.name))
+}
+
+/// Generates the HTML for the "Used by" and "Calls" sections of a function.
+pub(super) fn render_call_graph(w: &mut Buffer, cx: &mut Context<'_>, item: &clean::Item) {
+    let shared = Rc::clone(&cx.shared);
+    let (Some(call_graph), Some(did)) = (&shared.call_graph, item.item_id.as_def_id()) else {
+        return;
+    };
+    let tcx = cx.tcx();
+
+    for (id, title, functions) in [
+        ("used-by", "Used by", call_graph.callers.get(&did)),
+        ("calls", "Calls", call_graph.callees.get(&did)),
+    ] {
+        let Some(functions) = functions else { continue };
+        let mut functions = functions
+            .iter()
+            .map(|&did| (display_name(tcx, did), function_href(cx, did)))
+            .collect::<Vec<_>>();
+        functions.sort();
+
+        let id = cx.id_map.derive(id);
+        write!(
+            w,
+            "<div class=\"docblock call-graph\">\
+                <h5 id=\"{id}\"><a href=\"#{id}\">{title}</a></h5>\
+                <ul>",
+        );
+        for (name, url) in functions {
+            match url {
+                Some(url) => {
+                    write!(w, "<li><a href=\"{}\"><code>{}</code></a></li>", url, Escape(&name))
+                }
+                None => write!(w, "<li><code>{}</code></li>", Escape(&name)),
+            }
+        }
+        w.write_str("</ul></div>");
+    }
+}
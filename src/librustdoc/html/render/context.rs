@@ -13,6 +13,8 @@
 use rustc_span::source_map::FileName;
 use rustc_span::{sym, Symbol};
 
+use super::bundle::write_bundle;
+use super::call_graph::CallGraph;
 use super::print_item::{full_path, item_path, print_item};
 use super::search_index::build_index;
 use super::write_shared::write_shared;
@@ -124,6 +126,10 @@ pub(crate) struct SharedContext<'tcx> {
     pub(crate) cache: Cache,
 
     pub(crate) call_locations: AllCallLocations,
+    /// The path of the zip archive to pack the output directory into, with `--bundle`.
+    bundle: Option<PathBuf>,
+    /// The calls between the functions of the crate, with `--show-call-graph`.
+    pub(crate) call_graph: Option<CallGraph>,
 }
 
 impl SharedContext<'_> {
@@ -408,6 +414,8 @@ fn init(
             generate_link_to_definition,
             call_locations,
             no_emit_shared,
+            bundle,
+            show_call_graph,
             ..
         } = options;
 
@@ -492,6 +500,12 @@ fn init(
             span_correspondance_map: matches,
             cache,
             call_locations,
+            bundle,
+            call_graph: if show_call_graph {
+                Some(tcx.sess.time("build_call_graph", || CallGraph::new(tcx, &krate)))
+            } else {
+                None
+            },
         };
 
         // Add the default themes to the `Vec` of stylepaths
@@ -648,6 +662,12 @@ fn after_krate(&mut self) -> Result<(), Error> {
             }
         }
 
+        if let Some(ref call_graph) = shared.call_graph {
+            let call_graph_path = self.dst.join(crate_name.as_str()).join("call-graph.json");
+            shared.ensure_dir(&self.dst.join(crate_name.as_str()))?;
+            shared.fs.write(call_graph_path, call_graph.to_json(self.tcx()))?;
+        }
+
         // No need for it anymore.
         drop(shared);
 
@@ -656,10 +676,15 @@ fn after_krate(&mut self) -> Result<(), Error> {
         let nb_errors =
             self.shared.errors.iter().map(|err| self.tcx().sess.struct_err(&err).emit()).count();
         if nb_errors > 0 {
-            Err(Error::new(io::Error::new(io::ErrorKind::Other, "I/O error"), ""))
-        } else {
-            Ok(())
+            return Err(Error::new(io::Error::new(io::ErrorKind::Other, "I/O error"), ""));
+        }
+
+        // This has to wait for all the files to be written, which is only guaranteed once the
+        // `DocFS` is closed.
+        if let Some(ref bundle) = self.shared.bundle {
+            self.sess().time("write_bundle", || write_bundle(&self.dst, bundle))?;
         }
+        Ok(())
     }
 
     fn mod_item_in(&mut self, item: &clean::Item) -> Result<(), Error> {
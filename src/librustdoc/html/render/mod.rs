@@ -28,6 +28,8 @@
 #[cfg(test)]
 mod tests;
 
+mod bundle;
+mod call_graph;
 mod context;
 mod print_item;
 mod span_map;
@@ -36,6 +38,8 @@
 pub(crate) use self::context::*;
 pub(crate) use self::span_map::{collect_spans_and_sources, LinkFromSrc};
 
+use self::call_graph::render_call_graph;
+
 use std::collections::VecDeque;
 use std::default::Default;
 use std::fmt;
@@ -111,9 +115,20 @@ pub(crate) struct IndexItem {
 #[derive(Debug)]
 pub(crate) struct RenderType {
     name: Option<String>,
+    /// What tells the type apart from the other types of the same name, if it has a path: not
+    /// for generics and primitives.
+    id: Option<RenderTypeId>,
     generics: Option<Vec<TypeWithKind>>,
 }
 
+/// The identity of a type with a path in the search index. The `DefId` is replaced by an index
+/// into the paths of the types of the crate when the search index is built.
+#[derive(Clone, Copy, Debug)]
+enum RenderTypeId {
+    DefId(DefId),
+    Index(usize),
+}
+
 /// Full type of functions/methods in the search index.
 #[derive(Debug)]
 pub(crate) struct IndexItemFunctionType {
@@ -161,7 +176,12 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         S: Serializer,
     {
         let mut seq = serializer.serialize_seq(None)?;
-        seq.serialize_element(&self.ty.name)?;
+        // The types with a path are written as the index of their path, which their name is
+        // the last segment of.
+        match self.ty.id {
+            Some(RenderTypeId::Index(index)) => seq.serialize_element(&index)?,
+            Some(RenderTypeId::DefId(_)) | None => seq.serialize_element(&self.ty.name)?,
+        }
         seq.serialize_element(&self.kind)?;
         if let Some(generics) = &self.ty.generics {
             seq.serialize_element(generics)?;
@@ -484,6 +504,12 @@ fn document_full_inner(
     if let clean::ItemKind::FunctionItem(..) | clean::ItemKind::MethodItem(..) = kind {
         render_call_locations(w, cx, item);
     }
+    if let clean::ItemKind::FunctionItem(..)
+    | clean::ItemKind::MethodItem(..)
+    | clean::ItemKind::TyMethodItem(..) = kind
+    {
+        render_call_graph(w, cx, item);
+    }
 }
 
 /// Add extra information about an item such as:
@@ -2385,7 +2411,7 @@ fn sidebar_enum(cx: &Context<'_>, buf: &mut Buffer, it: &clean::Item, e: &clean:
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum ItemSection {
+pub(crate) enum ItemSection {
     Reexports,
     PrimitiveTypes,
     Modules,
@@ -2414,7 +2440,7 @@ enum ItemSection {
 }
 
 impl ItemSection {
-    const ALL: &'static [Self] = {
+    pub(crate) const ALL: &'static [Self] = {
         use ItemSection::*;
         // NOTE: The order here affects the order in the UI.
         &[
@@ -2476,7 +2502,7 @@ fn id(self) -> &'static str {
         }
     }
 
-    fn name(self) -> &'static str {
+    pub(crate) fn name(self) -> &'static str {
         match self {
             Self::Reexports => "Re-exports",
             Self::Modules => "Modules",
@@ -2507,7 +2533,7 @@ fn name(self) -> &'static str {
     }
 }
 
-fn item_ty_to_section(ty: ItemType) -> ItemSection {
+pub(crate) fn item_ty_to_section(ty: ItemType) -> ItemSection {
     match ty {
         ItemType::ExternCrate | ItemType::Import => ItemSection::Reexports,
         ItemType::Module => ItemSection::Modules,
@@ -1,8 +1,11 @@
 use std::collections::hash_map::Entry;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::iter;
 
 use rustc_data_structures::fx::FxHashMap;
-use rustc_middle::ty::TyCtxt;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::{self, TyCtxt};
 use rustc_span::symbol::{kw, Symbol};
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 
@@ -12,7 +15,9 @@
 use crate::formats::item_type::ItemType;
 use crate::html::format::join_with_double_colon;
 use crate::html::markdown::short_markdown_summary;
-use crate::html::render::{IndexItem, IndexItemFunctionType, RenderType, TypeWithKind};
+use crate::html::render::{
+    IndexItem, IndexItemFunctionType, RenderType, RenderTypeId, TypeWithKind,
+};
 
 /// Builds the search index from the collected metadata
 pub(crate) fn build_index<'tcx>(
@@ -48,7 +53,7 @@ pub(crate) fn build_index<'tcx>(
         .doc_value()
         .map_or_else(String::new, |s| short_markdown_summary(&s, &krate.module.link_names(cache)));
 
-    let Cache { ref mut search_index, ref paths, .. } = *cache;
+    let Cache { ref mut search_index, ref paths, ref impls, .. } = *cache;
 
     // Aliases added through `#[doc(alias = "...")]`. Since a few items can have the same alias,
     // we need the alias element to have an array of items.
@@ -69,6 +74,56 @@ pub(crate) fn build_index<'tcx>(
         }
     }
 
+    // The types of the functions refer to the paths of the types, which tell apart the types of
+    // the same name.
+    let mut type_paths = TypePaths::default();
+    for item in search_index.iter_mut() {
+        if let Some(ref mut search_type) = item.search_type {
+            type_paths.convert(tcx, &mut search_type.inputs);
+            type_paths.convert(tcx, &mut search_type.output);
+        }
+    }
+
+    // The targets of the `Deref` implementations, and the types implementing each trait, so that
+    // the search can match a type through `Deref` or against the bounds of a generic.
+    let deref_trait = tcx.lang_items().deref_trait();
+    let mut derefs: BTreeMap<usize, IndexedType> = BTreeMap::new();
+    let mut implementors: BTreeMap<usize, BTreeSet<IndexedType>> = BTreeMap::new();
+    for impl_ in impls.values().flatten() {
+        let impl_ = impl_.inner_impl();
+        let Some(ref trait_) = impl_.trait_ else { continue };
+        if impl_.polarity != ty::ImplPolarity::Positive {
+            continue;
+        }
+        // Auto traits are implemented by almost every type, so rather than listing all of them,
+        // they are stored as implemented by any type.
+        let implementor = if impl_.kind.is_auto() {
+            IndexedType::Any
+        } else {
+            match get_implementor(impl_, tcx, &mut type_paths) {
+                Some(implementor) => implementor,
+                None => continue,
+            }
+        };
+        if Some(trait_.def_id()) == deref_trait && !impl_.kind.is_blanket() {
+            let target = impl_.items.iter().find_map(|item| match *item.kind {
+                clean::AssocTypeItem(ref typedef, _) => Some(&typedef.type_),
+                _ => None,
+            });
+            let target = match target {
+                // `impl<T> Deref for Box<T>`, the target is the first generic of the type.
+                Some(Type::Generic(_)) => Some(IndexedType::Any),
+                Some(target) => get_indexed_type(target, tcx, &mut type_paths),
+                None => None,
+            };
+            if let (IndexedType::Path(for_), Some(target)) = (&implementor, target) {
+                derefs.insert(*for_, target);
+            }
+        }
+        let trait_ = type_paths.index(tcx, trait_.def_id());
+        implementors.entry(trait_).or_default().insert(implementor);
+    }
+
     // Reduce `DefId` in paths into smaller sequential numbers,
     // and prune the paths that do not appear in the index.
     let mut lastpath = "";
@@ -112,6 +167,13 @@ struct CrateData<'a> {
         //
         // To be noted: the `usize` elements are indexes to `items`.
         aliases: &'a BTreeMap<String, Vec<usize>>,
+        // The paths of the types, which the types of the functions, the `Deref` targets and the
+        // implementors refer to by index.
+        type_paths: Vec<String>,
+        // The `Deref` targets of the types.
+        derefs: &'a BTreeMap<usize, IndexedType>,
+        // The types implementing each trait.
+        implementors: &'a BTreeMap<usize, BTreeSet<IndexedType>>,
     }
 
     impl<'a> Serialize for CrateData<'a> {
@@ -120,8 +182,15 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             S: Serializer,
         {
             let has_aliases = !self.aliases.is_empty();
-            let mut crate_data =
-                serializer.serialize_struct("CrateData", if has_aliases { 9 } else { 8 })?;
+            let has_type_paths = !self.type_paths.is_empty();
+            let has_derefs = !self.derefs.is_empty();
+            let has_implementors = !self.implementors.is_empty();
+            let len = 8
+                + has_aliases as usize
+                + has_type_paths as usize
+                + has_derefs as usize
+                + has_implementors as usize;
+            let mut crate_data = serializer.serialize_struct("CrateData", len)?;
             crate_data.serialize_field("doc", &self.doc)?;
             crate_data.serialize_field(
                 "t",
@@ -166,6 +235,15 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             if has_aliases {
                 crate_data.serialize_field("a", &self.aliases)?;
             }
+            if has_type_paths {
+                crate_data.serialize_field("y", &self.type_paths)?;
+            }
+            if has_derefs {
+                crate_data.serialize_field("r", &self.derefs)?;
+            }
+            if has_implementors {
+                crate_data.serialize_field("m", &self.implementors)?;
+            }
             crate_data.end()
         }
     }
@@ -179,6 +257,9 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             items: crate_items,
             paths: crate_paths,
             aliases: &aliases,
+            type_paths: type_paths.paths,
+            derefs: &derefs,
+            implementors: &implementors,
         })
         .expect("failed serde conversion")
         // All these `replace` calls are because we have to go through JS string for JSON content.
@@ -189,6 +270,67 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     )
 }
 
+/// The paths of the types of the search index, which are referred to by their index.
+#[derive(Default)]
+struct TypePaths {
+    indexes: FxHashMap<DefId, usize>,
+    paths: Vec<String>,
+}
+
+impl TypePaths {
+    fn index(&mut self, tcx: TyCtxt<'_>, did: DefId) -> usize {
+        let paths = &mut self.paths;
+        *self.indexes.entry(did).or_insert_with(|| {
+            paths.push(get_type_path(tcx, did));
+            paths.len() - 1
+        })
+    }
+
+    /// Replaces the `DefId`s of `types`, and of their generics, by the index of their path.
+    fn convert(&mut self, tcx: TyCtxt<'_>, types: &mut [TypeWithKind]) {
+        for ty in types {
+            if let Some(RenderTypeId::DefId(did)) = ty.ty.id {
+                ty.ty.id = Some(RenderTypeId::Index(self.index(tcx, did)));
+            }
+            if let Some(ref mut generics) = ty.ty.generics {
+                self.convert(tcx, generics);
+            }
+        }
+    }
+}
+
+/// Returns the path where the type `did` is defined, rather than the one it is documented at,
+/// so that it is the same in the search index of every crate.
+fn get_type_path(tcx: TyCtxt<'_>, did: DefId) -> String {
+    let crate_name = tcx.crate_name(did.krate);
+    let path = tcx.def_path(did).data.into_iter().filter_map(|elem| elem.data.get_opt_name());
+    join_with_double_colon(&iter::once(crate_name).chain(path).collect::<Vec<_>>())
+}
+
+/// A type in the `Deref` targets and the implementors of the search index.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum IndexedType {
+    /// The index of the path of the type.
+    Path(usize),
+    /// A primitive type, by name.
+    Primitive(String),
+    /// The first generic of the type, for a `Deref` target, or any type, for an implementor.
+    Any,
+}
+
+impl Serialize for IndexedType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            IndexedType::Path(index) => index.serialize(serializer),
+            IndexedType::Primitive(ref name) => name.serialize(serializer),
+            IndexedType::Any => "".serialize(serializer),
+        }
+    }
+}
+
 pub(crate) fn get_function_type_for_search<'tcx>(
     item: &clean::Item,
     tcx: TyCtxt<'tcx>,
@@ -211,10 +353,24 @@ pub(crate) fn get_function_type_for_search<'tcx>(
 fn get_index_type(clean_type: &clean::Type, generics: Vec<TypeWithKind>) -> RenderType {
     RenderType {
         name: get_index_type_name(clean_type).map(|s| s.as_str().to_ascii_lowercase()),
+        id: get_index_type_did(clean_type).map(RenderTypeId::DefId),
         generics: if generics.is_empty() { None } else { Some(generics) },
     }
 }
 
+/// Returns the `DefId` of the types with a path, which tells them apart from the other types of
+/// the same name. Generics and primitives are only known by their name.
+fn get_index_type_did(clean_type: &clean::Type) -> Option<DefId> {
+    match *clean_type {
+        clean::Type::Path { ref path } => Some(path.def_id()),
+        clean::DynTrait(ref bounds, _) => Some(bounds[0].trait_.def_id()),
+        clean::BorrowedRef { ref type_, .. } | clean::RawPointer(_, ref type_) => {
+            get_index_type_did(type_)
+        }
+        _ => None,
+    }
+}
+
 fn get_index_type_name(clean_type: &clean::Type) -> Option<Symbol> {
     match *clean_type {
         clean::Type::Path { ref path, .. } => {
@@ -225,25 +381,124 @@ fn get_index_type_name(clean_type: &clean::Type) -> Option<Symbol> {
             let path = &bounds[0].trait_;
             Some(path.segments.last().unwrap().name)
         }
-        // We return an empty name because we don't care about the generic name itself.
-        clean::Generic(_) | clean::ImplTrait(_) => Some(kw::Empty),
+        // Type parameters keep their name so that the search can tell them apart, whereas
+        // `impl Trait` and associated types are anonymous generics.
+        clean::Generic(name) => Some(name),
+        clean::ImplTrait(_) | clean::QPath { .. } => Some(kw::Empty),
         clean::Primitive(ref p) => Some(p.as_sym()),
         clean::BorrowedRef { ref type_, .. } | clean::RawPointer(_, ref type_) => {
             get_index_type_name(type_)
         }
-        clean::BareFunction(_)
-        | clean::Tuple(_)
-        | clean::Slice(_)
-        | clean::Array(_, _)
-        | clean::QPath { .. }
-        | clean::Infer => None,
+        // These are searched for by the name of their primitive: `slice`, `array`, `tuple`,
+        // `unit` or `fn`.
+        clean::BareFunction(_) | clean::Tuple(_) | clean::Slice(_) | clean::Array(_, _) => {
+            clean_type.primitive_type().map(|p| p.as_sym())
+        }
+        clean::Infer => None,
     }
 }
 
-/// The point of this function is to replace bounds with types.
+/// Returns the type `ty` as it is written in the `Deref` targets and the implementors of the
+/// search index, if it has a path or is a primitive.
+fn get_indexed_type(ty: &Type, tcx: TyCtxt<'_>, type_paths: &mut TypePaths) -> Option<IndexedType> {
+    match get_index_type_did(ty) {
+        Some(did) => Some(IndexedType::Path(type_paths.index(tcx, did))),
+        None => ty.primitive_type().map(|p| IndexedType::Primitive(p.as_sym().to_string())),
+    }
+}
+
+/// Returns the type implementing the trait of `impl_` in the search index.
+///
+/// Blanket implementations over the types implementing a single trait, such as
+/// `impl<I: Iterator> IntoIterator for I`, are stored as implemented by that trait: every type
+/// implementing `Iterator` implements `IntoIterator` as well. The ones over every type, such as
+/// `impl<T> From<T> for T`, are stored as implemented by any type. This is also what keeps the
+/// implementations that rustdoc lists for every type out of the index, as they are stored once,
+/// from the blanket implementation they come from.
+fn get_implementor(
+    impl_: &clean::Impl,
+    tcx: TyCtxt<'_>,
+    type_paths: &mut TypePaths,
+) -> Option<IndexedType> {
+    let mut for_ = impl_.kind.as_blanket_ty().unwrap_or(&impl_.for_);
+    // As in the functions, references and pointers are not told apart from their pointee.
+    while let Type::BorrowedRef { ref type_, .. } | Type::RawPointer(_, ref type_) = *for_ {
+        for_ = &**type_;
+    }
+    match *for_ {
+        Type::Generic(param) => {
+            let mut bounds = get_generic_bounds(&impl_.generics, param).filter_map(get_bound_type);
+            match (bounds.next(), bounds.next()) {
+                (None, _) => Some(IndexedType::Any),
+                (Some(bound), None) => get_indexed_type(&bound, tcx, type_paths),
+                _ => None,
+            }
+        }
+        _ => get_indexed_type(for_, tcx, type_paths),
+    }
+}
+
+/// Returns the bounds of the type parameter `param`, which can be "inlined" like `T: Display`,
+/// or in a `where` predicate.
+fn get_generic_bounds<'a>(
+    generics: &'a Generics,
+    param: Symbol,
+) -> impl Iterator<Item = &'a GenericBound> + 'a {
+    let inline_bounds = generics
+        .params
+        .iter()
+        .filter(move |def| def.is_type() && def.name == param)
+        .filter_map(|def| def.get_bounds());
+    let where_bounds = generics.where_predicates.iter().filter_map(move |pred| match *pred {
+        WherePredicate::BoundPredicate { ty: Type::Generic(name), ref bounds, .. }
+            if name == param =>
+        {
+            Some(&bounds[..])
+        }
+        _ => None,
+    });
+    inline_bounds.chain(where_bounds).flatten()
+}
+
+/// Returns the type standing for a trait bound in the search index, or `None` for the bounds
+/// which don't restrict anything (`?Sized` and lifetimes).
+fn get_bound_type(bound: &GenericBound) -> Option<Type> {
+    match *bound {
+        GenericBound::TraitBound(_, hir::TraitBoundModifier::Maybe) => None,
+        _ => bound.get_trait_path().map(|path| Type::Path { path }),
+    }
+}
+
+/// Returns the generic arguments of the last segment of `path`, including the types of the
+/// associated type bindings (`u8` in `Iterator<Item = u8>`) and of the parenthesized arguments
+/// of the `Fn` traits.
+fn get_path_generics(path: &clean::Path) -> Vec<&Type> {
+    match path.segments.last().map(|segment| &segment.args) {
+        Some(clean::GenericArgs::AngleBracketed { args, bindings }) => args
+            .iter()
+            .filter_map(|arg| match arg {
+                clean::GenericArg::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .chain(bindings.iter().filter_map(|binding| match binding.kind {
+                clean::TypeBindingKind::Equality { term: clean::Term::Type(ref ty) } => Some(ty),
+                _ => None,
+            }))
+            .collect(),
+        Some(clean::GenericArgs::Parenthesized { inputs, output }) => {
+            inputs.iter().chain(output.as_deref()).collect()
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Adds the type of `arg` to `res`, with its generics.
 ///
-/// i.e. `[T, U]` when you have the following bounds: `T: Display, U: Option<T>` will return
-/// `[Display, Option]`. If a type parameter has no trait bound, it is discarded.
+/// Type parameters are kept as generics named after the parameter, whose own generics are their
+/// trait bounds, inline or in a `where` clause: `T: Display` becomes `t<display>`. `impl Trait`
+/// becomes an anonymous generic in the same way. This is what allows the search to unify the
+/// type variables of a query (as in `Vec<T> -> Option<T>`) with the generics of a function, and
+/// to match a generic against the types implementing its bounds.
 ///
 /// Important note: It goes through generics recursively. So if you have
 /// `T: Option<Result<(), ()>>`, it'll go into `Option` and then into `Result`.
@@ -260,76 +515,25 @@ fn add_generics_and_bounds_as_types<'tcx, 'a>(
     fn insert_ty(
         res: &mut Vec<TypeWithKind>,
         tcx: TyCtxt<'_>,
-        ty: Type,
-        mut generics: Vec<TypeWithKind>,
+        ty: &Type,
+        generics: Vec<TypeWithKind>,
         cache: &Cache,
     ) {
-        // generics and impl trait are both identified by their generics,
-        // rather than a type name itself
-        let anonymous = ty.is_full_generic() || ty.is_impl_trait();
-        let generics_empty = generics.is_empty();
-
-        if anonymous {
-            if generics_empty {
-                // This is a type parameter with no trait bounds (for example: `T` in
-                // `fn f<T>(p: T)`, so not useful for the rustdoc search because we would end up
-                // with an empty type with an empty name. Let's just discard it.
-                return;
-            } else if generics.len() == 1 {
-                // In this case, no need to go through an intermediate state if the type parameter
-                // contains only one trait bound.
-                //
-                // For example:
-                //
-                // `fn foo<T: Display>(r: Option<T>) {}`
-                //
-                // In this case, it would contain:
-                //
-                // ```
-                // [{
-                //     name: "option",
-                //     generics: [{
-                //         name: "",
-                //         generics: [
-                //             name: "Display",
-                //             generics: []
-                //         }]
-                //     }]
-                // }]
-                // ```
-                //
-                // After removing the intermediate (unnecessary) type parameter, it'll become:
-                //
-                // ```
-                // [{
-                //     name: "option",
-                //     generics: [{
-                //         name: "Display",
-                //         generics: []
-                //     }]
-                // }]
-                // ```
-                //
-                // To be noted that it can work if there is ONLY ONE trait bound, otherwise we still
-                // need to keep it as is!
-                res.push(generics.pop().unwrap());
-                return;
-            }
-        }
-        let mut index_ty = get_index_type(&ty, generics);
-        if index_ty.name.as_ref().map(|s| s.is_empty() && generics_empty).unwrap_or(true) {
+        let index_ty = get_index_type(ty, generics);
+        if index_ty.name.is_none() {
             return;
         }
-        if anonymous {
-            // We remove the name of the full generic because we have no use for it.
-            index_ty.name = Some(String::new());
-            res.push(TypeWithKind::from((index_ty, ItemType::Generic)));
-        } else if let Some(kind) = ty.def_id(cache).map(|did| tcx.def_kind(did).into()) {
-            res.push(TypeWithKind::from((index_ty, kind)));
-        } else if ty.is_primitive() {
-            // This is a primitive, let's store it as such.
-            res.push(TypeWithKind::from((index_ty, ItemType::Primitive)));
-        }
+        let kind = match *ty {
+            Type::Generic(_) | Type::ImplTrait(_) | Type::QPath { .. } => ItemType::Generic,
+            // Checked before the `DefId`, which is the one of the module documenting the
+            // primitive.
+            _ if ty.is_primitive() => ItemType::Primitive,
+            _ => match ty.def_id(cache) {
+                Some(did) => tcx.def_kind(did).into(),
+                None => return,
+            },
+        };
+        res.push(TypeWithKind::from((index_ty, kind)));
     }
 
     if recurse >= 10 {
@@ -349,102 +553,59 @@ fn insert_ty(
         arg
     };
 
-    // If this argument is a type parameter and not a trait bound or a type, we need to look
-    // for its bounds.
-    if let Type::Generic(arg_s) = *arg {
-        // First we check if the bounds are in a `where` predicate...
-        if let Some(where_pred) = generics.where_predicates.iter().find(|g| match g {
-            WherePredicate::BoundPredicate { ty, .. } => ty.def_id(cache) == arg.def_id(cache),
-            _ => false,
-        }) {
-            let mut ty_generics = Vec::new();
-            let bounds = where_pred.get_bounds().unwrap_or_else(|| &[]);
-            for bound in bounds.iter() {
-                if let GenericBound::TraitBound(poly_trait, _) = bound {
-                    for param_def in poly_trait.generic_params.iter() {
-                        match &param_def.kind {
-                            clean::GenericParamDefKind::Type { default: Some(ty), .. } => {
-                                add_generics_and_bounds_as_types(
-                                    self_,
-                                    generics,
-                                    ty,
-                                    tcx,
-                                    recurse + 1,
-                                    &mut ty_generics,
-                                    cache,
-                                )
-                            }
-                            _ => {}
-                        }
-                    }
+    let mut ty_generics = Vec::new();
+    let mut add_generic = |ty: &Type| {
+        add_generics_and_bounds_as_types(
+            self_,
+            generics,
+            ty,
+            tcx,
+            recurse + 1,
+            &mut ty_generics,
+            cache,
+        )
+    };
+    match *arg {
+        // References and pointers don't matter to the search, only what they point to.
+        Type::BorrowedRef { ref type_, .. } | Type::RawPointer(_, ref type_) => {
+            add_generics_and_bounds_as_types(self_, generics, type_, tcx, recurse + 1, res, cache);
+            return;
+        }
+        Type::Generic(arg_s) => {
+            for bound in get_generic_bounds(generics, arg_s) {
+                if let Some(ty) = get_bound_type(bound) {
+                    add_generic(&ty);
                 }
             }
-            insert_ty(res, tcx, arg.clone(), ty_generics, cache);
         }
-        // Otherwise we check if the trait bounds are "inlined" like `T: Option<u32>`...
-        if let Some(bound) = generics.params.iter().find(|g| g.is_type() && g.name == arg_s) {
-            let mut ty_generics = Vec::new();
-            for bound in bound.get_bounds().unwrap_or(&[]) {
-                if let Some(path) = bound.get_trait_path() {
-                    let ty = Type::Path { path };
-                    add_generics_and_bounds_as_types(
-                        self_,
-                        generics,
-                        &ty,
-                        tcx,
-                        recurse + 1,
-                        &mut ty_generics,
-                        cache,
-                    );
+        Type::ImplTrait(ref bounds) => {
+            for bound in bounds {
+                if let Some(ty) = get_bound_type(bound) {
+                    add_generic(&ty);
                 }
             }
-            insert_ty(res, tcx, arg.clone(), ty_generics, cache);
         }
-    } else if let Type::ImplTrait(ref bounds) = *arg {
-        let mut ty_generics = Vec::new();
-        for bound in bounds {
-            if let Some(path) = bound.get_trait_path() {
-                let ty = Type::Path { path };
-                add_generics_and_bounds_as_types(
-                    self_,
-                    generics,
-                    &ty,
-                    tcx,
-                    recurse + 1,
-                    &mut ty_generics,
-                    cache,
-                );
-            }
+        Type::Path { ref path } => get_path_generics(path).into_iter().for_each(add_generic),
+        Type::DynTrait(ref bounds, _) => {
+            get_path_generics(&bounds[0].trait_).into_iter().for_each(add_generic)
         }
-        insert_ty(res, tcx, arg.clone(), ty_generics, cache);
-    } else {
-        // This is not a type parameter. So for example if we have `T, U: Option<T>`, and we're
-        // looking at `Option`, we enter this "else" condition, otherwise if it's `T`, we don't.
-        //
-        // So in here, we can add it directly and look for its own type parameters (so for `Option`,
-        // we will look for them but not for `T`).
-        let mut ty_generics = Vec::new();
-        if let Some(arg_generics) = arg.generics() {
-            for gen in arg_generics.iter() {
-                add_generics_and_bounds_as_types(
-                    self_,
-                    generics,
-                    gen,
-                    tcx,
-                    recurse + 1,
-                    &mut ty_generics,
-                    cache,
-                );
+        Type::Tuple(ref types) => types.iter().for_each(add_generic),
+        Type::Slice(ref ty) | Type::Array(ref ty, _) => add_generic(ty),
+        Type::BareFunction(ref f) => {
+            f.decl.inputs.values.iter().for_each(|input| add_generic(&input.type_));
+            if let FnRetTy::Return(ref ty) = f.decl.output {
+                add_generic(ty);
             }
         }
-        insert_ty(res, tcx, arg.clone(), ty_generics, cache);
+        Type::Primitive(_) | Type::QPath { .. } | Type::Infer => {}
     }
+    insert_ty(res, tcx, arg, ty_generics, cache);
 }
 
 /// Return the full list of types when bounds have been resolved.
 ///
-/// i.e. `fn foo<A: Display, B: Option<A>>(x: u32, y: B)` will return
-/// `[u32, Display, Option]`.
+/// i.e. `fn foo<A: Display>(x: u32, y: Option<A>)` will return `[u32, Option<A<Display>>]`, where
+/// `A` is a generic.
 fn get_fn_inputs_and_outputs<'tcx>(
     func: &Function,
     tcx: TyCtxt<'tcx>,
@@ -473,36 +634,28 @@ fn get_fn_inputs_and_outputs<'tcx>(
 
     let mut all_types = Vec::new();
     for arg in decl.inputs.values.iter() {
-        let mut args = Vec::new();
-        add_generics_and_bounds_as_types(self_, generics, &arg.type_, tcx, 0, &mut args, cache);
-        if !args.is_empty() {
-            all_types.extend(args);
-        } else {
-            if let Some(kind) = arg.type_.def_id(cache).map(|did| tcx.def_kind(did).into()) {
-                all_types.push(TypeWithKind::from((get_index_type(&arg.type_, vec![]), kind)));
-            }
-        }
+        add_generics_and_bounds_as_types(
+            self_,
+            generics,
+            &arg.type_,
+            tcx,
+            0,
+            &mut all_types,
+            cache,
+        );
     }
 
     let mut ret_types = Vec::new();
-    match decl.output {
-        FnRetTy::Return(ref return_type) => {
-            add_generics_and_bounds_as_types(
-                self_,
-                generics,
-                return_type,
-                tcx,
-                0,
-                &mut ret_types,
-                cache,
-            );
-            if ret_types.is_empty() {
-                if let Some(kind) = return_type.def_id(cache).map(|did| tcx.def_kind(did).into()) {
-                    ret_types.push(TypeWithKind::from((get_index_type(return_type, vec![]), kind)));
-                }
-            }
-        }
-        _ => {}
-    };
+    if let FnRetTy::Return(ref return_type) = decl.output {
+        add_generics_and_bounds_as_types(
+            self_,
+            generics,
+            return_type,
+            tcx,
+            0,
+            &mut ret_types,
+            cache,
+        );
+    }
     (all_types, ret_types)
 }
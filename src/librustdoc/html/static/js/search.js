@@ -33,11 +33,25 @@ const itemTypes = [
     "attr",
     "derive",
     "traitalias",
+    "generic",
 ];
 
 // used for special search precedence
 const TY_PRIMITIVE = itemTypes.indexOf("primitive");
 const TY_KEYWORD = itemTypes.indexOf("keyword");
+// The kind of the generics in the function types of the search index, which aren't items.
+const TY_GENERIC = itemTypes.indexOf("generic");
+// The item types whose names can be used as a type in a query.
+const TYPE_ITEM_TYPES = [
+    "struct",
+    "enum",
+    "union",
+    "trait",
+    "traitalias",
+    "primitive",
+    "type",
+    "foreigntype",
+].map(ty => itemTypes.indexOf(ty));
 const ROOT_PATH = typeof window !== "undefined" ? window.rootPath : "../";
 
 function hasOwnPropertyRustdoc(obj, property) {
@@ -116,15 +130,31 @@ function initSearch(rawSearchIndex) {
     const MAX_RESULTS = 200;
     const GENERICS_DATA = 2;
     const NAME = 0;
+    const KIND = 1;
+    // What tells a type apart from the other types of the same name: the path of the types which
+    // have one, or the name of a primitive. Generics don't have one.
+    const KEY = 3;
     const INPUTS_DATA = 0;
     const OUTPUT_DATA = 1;
     const NO_TYPE_FILTER = -1;
+    // The distance of a type matched indirectly: through `Deref`, or because it implements the
+    // bounds of a generic.
+    const INDIRECT_MATCH_LEV = 0.5;
     /**
      *  @type {Array<Row>}
      */
     let searchIndex;
     let currentResults;
     const ALIASES = Object.create(null);
+    // The `Deref` target of each type, by key. An empty target stands for the first generic of
+    // the type, like `T` in `Box<T>`.
+    const DEREFS = Object.create(null);
+    // The implementors of each trait, by key. An empty implementor stands for any type.
+    const IMPLEMENTORS = Object.create(null);
+    // The names of the types of all crates, which can't be used as type variables.
+    const TYPE_NAMES = Object.create(null);
+    // The paths of the types of the functions, by name.
+    const TYPE_PATHS = Object.create(null);
 
     function isWhitespace(c) {
         return " \t\n\r".indexOf(c) !== -1;
@@ -256,10 +286,13 @@ function initSearch(rawSearchIndex) {
      * @param {ParserState} parserState
      * @param {string} name                  - Name of the query element.
      * @param {Array<QueryElement>} generics - List of generics of this query element.
+     * @param {boolean} isInGenerics
+     * @param {boolean} typeVariable         - Whether the element is written like a type
+     *                                         variable: a single uppercase letter, like `T`.
      *
      * @return {QueryElement}                - The newly created `QueryElement`.
      */
-    function createQueryElement(query, parserState, name, generics, isInGenerics) {
+    function createQueryElement(query, parserState, name, generics, isInGenerics, typeVariable) {
         if (name === "*" || (name.length === 0 && generics.length === 0)) {
             return;
         }
@@ -295,6 +328,7 @@ function initSearch(rawSearchIndex) {
             pathWithoutLast: pathSegments.slice(0, pathSegments.length - 1),
             pathLast: pathSegments[pathSegments.length - 1],
             generics: generics,
+            typeVariable: typeVariable,
         };
     }
 
@@ -326,7 +360,8 @@ function initSearch(rawSearchIndex) {
                 } else if (
                     isStopCharacter(c) ||
                     isSpecialStartCharacter(c) ||
-                    isSeparatorCharacter(c)
+                    isSeparatorCharacter(c) ||
+                    c === "="
                 ) {
                     break;
                 } else if (c === ":") { // If we allow paths ("str::string" for example).
@@ -355,16 +390,30 @@ function initSearch(rawSearchIndex) {
     function getNextElem(query, parserState, elems, isInGenerics) {
         const generics = [];
 
+        skipReference(parserState);
         let start = parserState.pos;
         let end;
+        let isString = false;
         // We handle the strings on their own mostly to make code easier to follow.
         if (parserState.userQuery[parserState.pos] === "\"") {
             start += 1;
             getStringElem(query, parserState, isInGenerics);
             end = parserState.pos - 1;
+            isString = true;
         } else {
             end = getIdentEndPosition(parserState);
         }
+        const name = parserState.userQuery.slice(start, end);
+        if (!isString && (name === "impl" || name === "dyn") && skipTypeKeyword(parserState)) {
+            // `impl Trait` and `dyn Trait` are searched for as `Trait`.
+            getNextElem(query, parserState, elems, isInGenerics);
+            return;
+        } else if (isInGenerics && start < end && skipAssocTypeBinding(parserState)) {
+            // Only the type of an associated type binding matters: `Iterator<Item = u8>` is
+            // searched for as `Iterator<u8>`.
+            getNextElem(query, parserState, elems, isInGenerics);
+            return;
+        }
         if (parserState.pos < parserState.length &&
             parserState.userQuery[parserState.pos] === "<"
         ) {
@@ -379,17 +428,92 @@ function initSearch(rawSearchIndex) {
         if (start >= end && generics.length === 0) {
             return;
         }
+        const typeVariable = !isString && generics.length === 0 &&
+            /^[A-Z]$/.test(query.original.slice(start, end));
         elems.push(
             createQueryElement(
                 query,
                 parserState,
-                parserState.userQuery.slice(start, end),
+                name,
                 generics,
-                isInGenerics
+                isInGenerics,
+                typeVariable
             )
         );
     }
 
+    /**
+     * Skips the whitespace characters at the current parser position.
+     *
+     * @param {ParserState} parserState
+     */
+    function skipWhitespace(parserState) {
+        while (parserState.pos < parserState.length &&
+            isWhitespaceCharacter(parserState.userQuery[parserState.pos])
+        ) {
+            parserState.pos += 1;
+        }
+    }
+
+    /**
+     * Skips the `&` and `&mut` in front of a type: references are searched for as the type they
+     * point to.
+     *
+     * @param {ParserState} parserState
+     */
+    function skipReference(parserState) {
+        while (parserState.userQuery[parserState.pos] === "&") {
+            parserState.pos += 1;
+            skipWhitespace(parserState);
+            if (parserState.userQuery.slice(parserState.pos, parserState.pos + 4) === "mut ") {
+                parserState.pos += 4;
+                skipWhitespace(parserState);
+            }
+        }
+    }
+
+    /**
+     * Called after an `impl` or `dyn` ident: if a type follows, skips the whitespace up to it and
+     * returns `true`. Otherwise, the ident is the name of the query element.
+     *
+     * @param {ParserState} parserState
+     *
+     * @return {boolean}
+     */
+    function skipTypeKeyword(parserState) {
+        const pos = parserState.pos;
+        skipWhitespace(parserState);
+        if (pos !== parserState.pos &&
+            parserState.pos < parserState.length &&
+            isIdentCharacter(parserState.userQuery[parserState.pos])
+        ) {
+            return true;
+        }
+        parserState.pos = pos;
+        return false;
+    }
+
+    /**
+     * Called after an ident in generics: if it is the name of an associated type binding
+     * (`Item` in `Iterator<Item = u8>`), skips the `=` and the whitespace around it and returns
+     * `true`.
+     *
+     * @param {ParserState} parserState
+     *
+     * @return {boolean}
+     */
+    function skipAssocTypeBinding(parserState) {
+        const pos = parserState.pos;
+        skipWhitespace(parserState);
+        if (parserState.userQuery[parserState.pos] === "=") {
+            parserState.pos += 1;
+            skipWhitespace(parserState);
+            return true;
+        }
+        parserState.pos = pos;
+        return false;
+    }
+
     /**
      * This function parses the next query element until it finds `endChar`, calling `getNextElem`
      * to collect each element.
@@ -600,12 +724,16 @@ function initSearch(rawSearchIndex) {
      *
      * ident = *(ALPHA / DIGIT / "_") [!]
      * path = ident *(DOUBLE-COLON ident)
-     * arg = path [generics]
-     * arg-without-generic = path
+     * reference = AMPERSAND *WS ["mut" 1*WS]
+     * type-keyword = ("impl" / "dyn") 1*WS
+     * arg = *reference [type-keyword] path [generics]
+     * arg-without-generic = *reference [type-keyword] path
+     * assoc-type-binding = ident *WS EQUAL *WS
+     * generic-arg = [assoc-type-binding] arg-without-generic
      * type-sep = COMMA/WS *(COMMA/WS)
      * nonempty-arg-list = *(type-sep) arg *(type-sep arg) *(type-sep)
-     * nonempty-arg-list-without-generics = *(type-sep) arg-without-generic
-     *                                      *(type-sep arg-without-generic) *(type-sep)
+     * nonempty-arg-list-without-generics = *(type-sep) generic-arg
+     *                                      *(type-sep generic-arg) *(type-sep)
      * generics = OPEN-ANGLE-BRACKET [ nonempty-arg-list-without-generics ] *(type-sep)
      *            CLOSE-ANGLE-BRACKET/EOF
      * return-args = RETURN-ARROW *(type-sep) nonempty-arg-list
@@ -643,6 +771,8 @@ function initSearch(rawSearchIndex) {
      *     "derive" /
      *     "traitalias")
      *
+     * AMPERSAND = "&"
+     * EQUAL = "="
      * OPEN-ANGLE-BRACKET = "<"
      * CLOSE-ANGLE-BRACKET = ">"
      * COLON = ":"
@@ -728,6 +858,10 @@ function initSearch(rawSearchIndex) {
      */
     function execQuery(parsedQuery, searchWords, filterCrates, currentCrate) {
         const results_others = {}, results_in_args = {}, results_returned = {};
+        // The type variables of the query, like `T` in `Vec<T> -> Option<T>`, and the generics
+        // they are bound to while checking an item.
+        const typeVariables = [];
+        const typeVariableBindings = Object.create(null);
 
         function transformResults(results) {
             const duplicates = {};
@@ -897,57 +1031,32 @@ function initSearch(rawSearchIndex) {
         function checkGenerics(row, elem, defaultLev) {
             if (row.length <= GENERICS_DATA || row[GENERICS_DATA].length === 0) {
                 return elem.generics.length === 0 ? defaultLev : MAX_LEV_DISTANCE + 1;
-            } else if (row[GENERICS_DATA].length > 0 && row[GENERICS_DATA][0][NAME] === "") {
-                if (row.length > GENERICS_DATA) {
-                    return checkGenerics(row[GENERICS_DATA][0], elem, defaultLev);
-                }
-                return elem.generics.length === 0 ? defaultLev : MAX_LEV_DISTANCE + 1;
             }
             // The names match, but we need to be sure that all generics kinda
-            // match as well.
-            let elem_name;
+            // match as well: each generic of the query has to match a different generic of the
+            // row.
             if (elem.generics.length > 0 && row[GENERICS_DATA].length >= elem.generics.length) {
-                const elems = Object.create(null);
-                for (const entry of row[GENERICS_DATA]) {
-                    elem_name = entry[NAME];
-                    if (elem_name === "") {
-                        // Pure generic, needs to check into it.
-                        if (checkGenerics(entry, elem, MAX_LEV_DISTANCE + 1) !== 0) {
-                            return MAX_LEV_DISTANCE + 1;
-                        }
-                        continue;
-                    }
-                    if (elems[elem_name] === undefined) {
-                        elems[elem_name] = 0;
-                    }
-                    elems[elem_name] += 1;
-                }
-                // We need to find the type that matches the most to remove it in order
-                // to move forward.
+                const matched = [];
+                let lev = 0;
                 for (const generic of elem.generics) {
-                    let match = null;
-                    if (elems[generic.name]) {
-                        match = generic.name;
-                    } else {
-                        for (elem_name in elems) {
-                            if (!hasOwnPropertyRustdoc(elems, elem_name)) {
-                                continue;
-                            }
-                            if (elem_name === generic) {
-                                match = elem_name;
-                                break;
-                            }
+                    let best = MAX_LEV_DISTANCE + 1, bestIndex = -1;
+                    for (let i = 0, len = row[GENERICS_DATA].length; i < len; ++i) {
+                        if (matched[i]) {
+                            continue;
+                        }
+                        const tmp_lev = checkType(row[GENERICS_DATA][i], generic, true);
+                        if (tmp_lev < best) {
+                            best = tmp_lev;
+                            bestIndex = i;
                         }
                     }
-                    if (match === null) {
+                    if (best >= 1) {
                         return MAX_LEV_DISTANCE + 1;
                     }
-                    elems[match] -= 1;
-                    if (elems[match] === 0) {
-                        delete elems[match];
-                    }
+                    matched[bestIndex] = true;
+                    lev = Math.max(lev, best);
                 }
-                return 0;
+                return lev;
             }
             return MAX_LEV_DISTANCE + 1;
         }
@@ -972,6 +1081,78 @@ function initSearch(rawSearchIndex) {
             return lev;
         }
 
+        /**
+         * Returns `true` if the query element (`elem`) is a type variable: it is written as a
+         * single uppercase letter and it isn't the name of a type.
+         *
+         * @param {QueryElement} elem
+         *
+         * @return {boolean}
+         */
+        function isTypeVariable(elem) {
+            return elem.typeVariable && TYPE_NAMES[elem.name] !== true;
+        }
+
+        /**
+         * Returns `true` if the type of the query element (`elem`) implements the trait whose key
+         * is `trait`. As the query only names the type, any type of this name will do.
+         *
+         * @param {QueryElement} elem
+         * @param {string} trait
+         *
+         * @return {boolean}
+         */
+        function implementsTrait(elem, trait) {
+            const implementors = IMPLEMENTORS[trait];
+            if (implementors === undefined) {
+                return false;
+            } else if (implementors[""] === true || implementors[elem.name] === true) {
+                return true;
+            }
+            const paths = TYPE_PATHS[elem.name];
+            return paths !== undefined && paths.some(path => implementors[path] === true);
+        }
+
+        /**
+         * This function checks if the generic (`row`), which is a type parameter or an
+         * `impl Trait`, matches the given type (`elem`).
+         *
+         * A type variable matches the generic it is bound to, or any anonymous generic (such as
+         * `impl Trait`). Another type matches if it is one of the trait bounds of the generic,
+         * or, with a distance of `INDIRECT_MATCH_LEV`, if it implements all of them.
+         *
+         * @param {Row} row
+         * @param {QueryElement} elem - The element from the parsed query.
+         *
+         * @return {integer} - Returns a Levenshtein distance to the best match. If there is
+         *                     no match, returns `MAX_LEV_DISTANCE + 1`.
+         */
+        function checkGenericParam(row, elem) {
+            if (isTypeVariable(elem)) {
+                if (row[NAME] === "" || typeVariableBindings[elem.name] === row[NAME]) {
+                    return 0;
+                }
+                return MAX_LEV_DISTANCE + 1;
+            } else if (row.length <= GENERICS_DATA || row[GENERICS_DATA].length === 0) {
+                return MAX_LEV_DISTANCE + 1;
+            }
+            const lev = checkIfInGenerics(row, elem);
+            if (lev === 0) {
+                return 0;
+            }
+            const implementsBounds = row[GENERICS_DATA].every(bound => {
+                if (bound[NAME] !== elem.name && !implementsTrait(elem, bound[KEY])) {
+                    return false;
+                }
+                // The generics of the trait, like `u8` in `IntoIterator<Item = u8>`, have to
+                // match the ones of the type.
+                return elem.generics.length === 0 ||
+                    bound[GENERICS_DATA].length === 0 ||
+                    checkGenerics(bound, elem, 0) < 1;
+            });
+            return implementsBounds ? Math.min(lev, INDIRECT_MATCH_LEV) : lev;
+        }
+
         /**
           * This function checks if the object (`row`) matches the given type (`elem`) and its
           * generics (if any).
@@ -984,11 +1165,10 @@ function initSearch(rawSearchIndex) {
           *                     no match, returns `MAX_LEV_DISTANCE + 1`.
           */
         function checkType(row, elem, literalSearch) {
-            if (row[NAME].length === 0) {
-                // This is a pure "generic" search, no need to run other checks.
-                if (row.length > GENERICS_DATA) {
-                    return checkIfInGenerics(row, elem);
-                }
+            if (row[KIND] === TY_GENERIC) {
+                return checkGenericParam(row, elem);
+            } else if (isTypeVariable(elem)) {
+                // A type variable only stands for generics.
                 return MAX_LEV_DISTANCE + 1;
             }
 
@@ -999,8 +1179,12 @@ function initSearch(rawSearchIndex) {
                     if (elem.generics.length === 0) {
                         const checkGeneric = (row.length > GENERICS_DATA &&
                             row[GENERICS_DATA].length > 0);
-                        if (checkGeneric && row[GENERICS_DATA]
-                            .findIndex(tmp_elem => tmp_elem[NAME] === elem.name) !== -1) {
+                        if (checkGeneric && row[GENERICS_DATA].findIndex(tmp_elem => {
+                            if (tmp_elem[KIND] === TY_GENERIC) {
+                                return checkGenericParam(tmp_elem, elem) === 0;
+                            }
+                            return tmp_elem[NAME] === elem.name;
+                        }) !== -1) {
                             return 0;
                         }
                     }
@@ -1009,7 +1193,7 @@ function initSearch(rawSearchIndex) {
                     return checkGenerics(row, elem, MAX_LEV_DISTANCE + 1);
                 }
                 return 0;
-            } else if (row.length > GENERICS_DATA) {
+            } else if (row[GENERICS_DATA].length > 0) {
                 if (elem.generics.length === 0) {
                     if (lev === 0) {
                         return 0;
@@ -1046,8 +1230,97 @@ function initSearch(rawSearchIndex) {
             return lev;
         }
 
+        /**
+         * Returns the type the query element (`elem`) dereferences to, or `null` if it doesn't
+         * implement `Deref`. As the query only names the type, its target is only known if all
+         * the types of this name which implement `Deref` have the same.
+         *
+         * @param {QueryElement} elem
+         *
+         * @return {QueryElement|null}
+         */
+        function derefQueryElement(elem) {
+            if (isTypeVariable(elem) || TYPE_PATHS[elem.name] === undefined) {
+                return null;
+            }
+            let target;
+            for (const path of TYPE_PATHS[elem.name]) {
+                if (DEREFS[path] === undefined) {
+                    continue;
+                } else if (target !== undefined && target !== DEREFS[path]) {
+                    return null;
+                }
+                target = DEREFS[path];
+            }
+            if (target === undefined) {
+                return null;
+            } else if (target === "") {
+                return elem.generics.length > 0 ? elem.generics[0] : null;
+            }
+            const name = keyName(target);
+            return {
+                name: name,
+                fullPath: [name],
+                pathWithoutLast: [],
+                pathLast: name,
+                generics: elem.generics,
+                typeVariable: false,
+            };
+        }
+
+        /**
+         * Returns the type the object (`row`) dereferences to, or `null` if it doesn't
+         * implement `Deref`.
+         *
+         * @param {Row} row
+         *
+         * @return {Row|null}
+         */
+        function derefRow(row) {
+            const target = row[KEY] === undefined ? undefined : DEREFS[row[KEY]];
+            const generics = row[GENERICS_DATA];
+            if (target === undefined) {
+                return null;
+            } else if (target === "") {
+                return generics.length > 0 ? generics[0] : null;
+            }
+            return [keyName(target), row[KIND], generics, target];
+        }
+
+        /**
+         * This function checks if the object (`row`) matches the given type (`elem`), possibly
+         * after dereferencing one of them, in which case the distance is at least
+         * `INDIRECT_MATCH_LEV`.
+         *
+         * @param {Row} row
+         * @param {QueryElement} elem
+         * @param {boolean} derefElem - Whether `elem` is dereferenced, rather than `row`.
+         *
+         * @return {integer} - Returns a Levenshtein distance to the best match. If there is
+         *                     no match, returns `MAX_LEV_DISTANCE + 1`.
+         */
+        function checkTypeThroughDeref(row, elem, derefElem) {
+            let lev = checkType(row, elem, parsedQuery.literalSearch);
+            // Limits the number of dereferences, in case of a cycle.
+            for (let i = 0; lev !== 0 && i < 3; ++i) {
+                if (derefElem) {
+                    elem = derefQueryElement(elem);
+                } else {
+                    row = derefRow(row);
+                }
+                if (row === null || elem === null) {
+                    break;
+                }
+                const tmp_lev = checkType(row, elem, parsedQuery.literalSearch);
+                lev = Math.min(lev, Math.max(tmp_lev, INDIRECT_MATCH_LEV));
+            }
+            return lev;
+        }
+
         /**
          * This function checks if the object (`row`) has an argument with the given type (`elem`).
+         * The argument can also be of a type the given one dereferences to, like `&str` for
+         * `String`.
          *
          * @param {Row} row
          * @param {QueryElement} elem    - The element from the parsed query.
@@ -1061,20 +1334,21 @@ function initSearch(rawSearchIndex) {
 
             if (row && row.type && row.type[INPUTS_DATA] && row.type[INPUTS_DATA].length > 0) {
                 for (const input of row.type[INPUTS_DATA]) {
-                    if (!typePassesFilter(typeFilter, input[1])) {
+                    if (!typePassesFilter(typeFilter, input[KIND])) {
                         continue;
                     }
-                    lev = Math.min(lev, checkType(input, elem, parsedQuery.literalSearch));
+                    lev = Math.min(lev, checkTypeThroughDeref(input, elem, true));
                     if (lev === 0) {
                         return 0;
                     }
                 }
             }
-            return parsedQuery.literalSearch ? MAX_LEV_DISTANCE + 1 : lev;
+            return parsedQuery.literalSearch && lev >= 1 ? MAX_LEV_DISTANCE + 1 : lev;
         }
 
         /**
-         * This function checks if the object (`row`) returns the given type (`elem`).
+         * This function checks if the object (`row`) returns the given type (`elem`). The
+         * returned type can also dereference to the given one, like `String` for `str`.
          *
          * @param {Row} row
          * @param {QueryElement} elem   - The element from the parsed query.
@@ -1092,16 +1366,86 @@ function initSearch(rawSearchIndex) {
                     ret = [ret];
                 }
                 for (const ret_ty of ret) {
-                    if (!typePassesFilter(typeFilter, ret_ty[1])) {
+                    if (!typePassesFilter(typeFilter, ret_ty[KIND])) {
                         continue;
                     }
-                    lev = Math.min(lev, checkType(ret_ty, elem, parsedQuery.literalSearch));
+                    lev = Math.min(lev, checkTypeThroughDeref(ret_ty, elem, false));
                     if (lev === 0) {
                         return 0;
                     }
                 }
             }
-            return parsedQuery.literalSearch ? MAX_LEV_DISTANCE + 1 : lev;
+            return parsedQuery.literalSearch && lev >= 1 ? MAX_LEV_DISTANCE + 1 : lev;
+        }
+
+        /**
+         * Adds the names of the generics found in `types` to `names`.
+         *
+         * @param {Array<Row>} types
+         * @param {Array<string>} names
+         */
+        function addGenericNames(types, names) {
+            for (const type of types) {
+                if (type[KIND] === TY_GENERIC && type[NAME] !== "" &&
+                    names.indexOf(type[NAME]) === -1
+                ) {
+                    names.push(type[NAME]);
+                }
+                if (type.length > GENERICS_DATA) {
+                    addGenericNames(type[GENERICS_DATA], names);
+                }
+            }
+        }
+
+        /**
+         * Calls `callback` with the type variables of the query bound to the generics of the
+         * object (`row`), for each way of binding them, and returns the best distance it
+         * returned. Two type variables can't be bound to the same generic, and a type variable
+         * can be left unbound, in which case it only matches anonymous generics.
+         *
+         * @param {Row} row
+         * @param {function(): integer} callback
+         *
+         * @return {integer}
+         */
+        function withTypeVariableBindings(row, callback) {
+            if (typeVariables.length === 0) {
+                return callback();
+            }
+            const generics = [];
+            if (row && row.type) {
+                addGenericNames(row.type[INPUTS_DATA], generics);
+                if (row.type.length > OUTPUT_DATA) {
+                    const ret = row.type[OUTPUT_DATA];
+                    addGenericNames(typeof ret[0] === "string" ? [ret] : ret, generics);
+                }
+            }
+            let lev = MAX_LEV_DISTANCE + 1;
+            const bind = i => {
+                if (i === typeVariables.length) {
+                    lev = Math.min(lev, callback());
+                    return;
+                }
+                for (const generic of generics) {
+                    if (lev === 0) {
+                        return;
+                    }
+                    let bound = false;
+                    for (let j = 0; j < i; ++j) {
+                        bound = bound || typeVariableBindings[typeVariables[j]] === generic;
+                    }
+                    if (!bound) {
+                        typeVariableBindings[typeVariables[i]] = generic;
+                        bind(i + 1);
+                    }
+                }
+                if (lev !== 0) {
+                    typeVariableBindings[typeVariables[i]] = undefined;
+                    bind(i + 1);
+                }
+            };
+            bind(0);
+            return lev;
         }
 
         function checkPath(contains, ty) {
@@ -1249,7 +1593,7 @@ function initSearch(rawSearchIndex) {
          * @param {integer} lev
          */
         function addIntoResults(results, fullId, id, index, lev) {
-            if (lev === 0 || (!parsedQuery.literalSearch && lev <= MAX_LEV_DISTANCE)) {
+            if (lev < 1 || (!parsedQuery.literalSearch && lev <= MAX_LEV_DISTANCE)) {
                 if (results[fullId] !== undefined) {
                     const result = results[fullId];
                     if (result.dontValidate || result.lev <= lev) {
@@ -1294,8 +1638,12 @@ function initSearch(rawSearchIndex) {
             let lev, lev_add = 0, index = -1;
             const fullId = row.id;
 
-            const in_args = findArg(row, elem, parsedQuery.typeFilter);
-            const returned = checkReturned(row, elem, parsedQuery.typeFilter);
+            const in_args = withTypeVariableBindings(row, () => {
+                return findArg(row, elem, parsedQuery.typeFilter);
+            });
+            const returned = withTypeVariableBindings(row, () => {
+                return checkReturned(row, elem, parsedQuery.typeFilter);
+            });
 
             addIntoResults(results_in_args, fullId, pos, index, in_args);
             addIntoResults(results_returned, fullId, pos, index, returned);
@@ -1372,8 +1720,7 @@ function initSearch(rawSearchIndex) {
                 return;
             }
 
-            let totalLev = 0;
-            let nbLev = 0;
+            let totalLev, nbLev;
 
             // If the result is too "bad", we return false and it ends this search.
             function checkArgs(elems, callback) {
@@ -1389,17 +1736,18 @@ function initSearch(rawSearchIndex) {
                 }
                 return true;
             }
-            if (!checkArgs(parsedQuery.elems, findArg)) {
-                return;
-            }
-            if (!checkArgs(parsedQuery.returned, checkReturned)) {
-                return;
-            }
-
-            if (nbLev === 0) {
-                return;
-            }
-            const lev = Math.round(totalLev / nbLev);
+            // The type variables have to be bound to the same generics in all the elements.
+            const lev = withTypeVariableBindings(row, () => {
+                totalLev = 0;
+                nbLev = 0;
+                if (!checkArgs(parsedQuery.elems, findArg) ||
+                    !checkArgs(parsedQuery.returned, checkReturned) ||
+                    nbLev === 0
+                ) {
+                    return MAX_LEV_DISTANCE + 1;
+                }
+                return totalLev / nbLev;
+            });
             addIntoResults(results, row.id, pos, 0, lev);
         }
 
@@ -1426,7 +1774,9 @@ function initSearch(rawSearchIndex) {
                     elem = parsedQuery.returned[0];
                     for (i = 0, nSearchWords = searchWords.length; i < nSearchWords; ++i) {
                         row = searchIndex[i];
-                        in_returned = checkReturned(row, elem, parsedQuery.typeFilter);
+                        in_returned = withTypeVariableBindings(row, () => {
+                            return checkReturned(row, elem, parsedQuery.typeFilter);
+                        });
                         addIntoResults(results_others, row.id, i, -1, in_returned);
                     }
                 }
@@ -1437,7 +1787,25 @@ function initSearch(rawSearchIndex) {
             }
         }
 
+        /**
+         * Collects the type variables of the query elements (`elems`) into `typeVariables`.
+         *
+         * @param {Array<QueryElement>} elems
+         */
+        function collectTypeVariables(elems) {
+            for (const elem of elems) {
+                if (!elem) {
+                    continue;
+                } else if (isTypeVariable(elem) && typeVariables.indexOf(elem.name) === -1) {
+                    typeVariables.push(elem.name);
+                }
+                collectTypeVariables(elem.generics);
+            }
+        }
+
         if (parsedQuery.error === null) {
+            collectTypeVariables(parsedQuery.elems);
+            collectTypeVariables(parsedQuery.returned);
             innerRunQuery();
         }
 
@@ -1838,6 +2206,76 @@ function initSearch(rawSearchIndex) {
             filterCrates);
     }
 
+    /**
+     * Returns the name of the type whose key is `key`, which is the last segment of its path.
+     *
+     * @param {string} key
+     *
+     * @return {string}
+     */
+    function keyName(key) {
+        return key.slice(key.lastIndexOf(":") + 1).toLowerCase();
+    }
+
+    /**
+     * Converts a type of the search index of a crate to the form the search works with:
+     * `[name, kind, generics, key]`. The types with a path are written as the index of their
+     * path in `typePaths`.
+     *
+     * @param {Array<?>} type
+     * @param {Array<string>} typePaths
+     *
+     * @return {Row}
+     */
+    function convertType(type, typePaths) {
+        const generics = type.length > GENERICS_DATA ?
+            type[GENERICS_DATA].map(generic => convertType(generic, typePaths)) : [];
+        if (typeof type[NAME] === "number") {
+            const path = typePaths[type[NAME]];
+            return [keyName(path), type[KIND], generics, path];
+        }
+        const key = type[KIND] === TY_PRIMITIVE ? type[NAME] : undefined;
+        return [type[NAME], type[KIND], generics, key];
+    }
+
+    /**
+     * Converts the types of a function of the search index of a crate, as `convertType` does.
+     *
+     * @param {Array<?>|null} functionType
+     * @param {Array<string>} typePaths
+     *
+     * @return {Array<?>|null}
+     */
+    function convertFunctionType(functionType, typePaths) {
+        if (!functionType) {
+            return functionType;
+        }
+        const converted = [
+            functionType[INPUTS_DATA].map(input => convertType(input, typePaths)),
+        ];
+        if (functionType.length > OUTPUT_DATA) {
+            const output = functionType[OUTPUT_DATA];
+            // A single returned type isn't wrapped into an array.
+            converted.push(Array.isArray(output[0]) ?
+                output.map(type => convertType(type, typePaths)) :
+                convertType(output, typePaths));
+        }
+        return converted;
+    }
+
+    /**
+     * Converts a type of the `Deref` targets or the implementors of the search index of a crate
+     * to its key: the index of its path in `typePaths`, or the name of a primitive.
+     *
+     * @param {number|string} type
+     * @param {Array<string>} typePaths
+     *
+     * @return {string}
+     */
+    function convertKey(type, typePaths) {
+        return typeof type === "number" ? typePaths[type] : type;
+    }
+
     function buildIndex(rawSearchIndex) {
         searchIndex = [];
         /**
@@ -1873,9 +2311,23 @@ function initSearch(rawSearchIndex) {
              *
              * `p` is a mystery and isn't the same length as n/t/d/q/i/f.
              *
+             * `y` contains the paths of the types with a path, where they are defined. The types
+             * in `f`, `r` and `m` refer to them by index. Primitives are written as their name.
+             *
+             * `r` maps the types implementing `Deref` to their target, or to an empty string if
+             * the target is their first generic (like `T` for `Box<T>`).
+             *
+             * `m` maps the traits to their implementors. A blanket implementation over the
+             * implementors of a trait, like `impl<I: Iterator> IntoIterator for I`, has this
+             * trait as implementor. An empty string stands for any type, for the auto traits and
+             * the blanket implementations over every type, like `impl<T> From<T> for T`.
+             *
              * @type {{
              *   doc: string,
              *   a: Object,
+             *   y: Array<string>,
+             *   r: Object,
+             *   m: Object,
              *   n: Array<string>,
              *   t: Array<Number>,
              *   d: Array<string>,
@@ -1924,6 +2376,22 @@ function initSearch(rawSearchIndex) {
             // an array of [(String) alias name
             //             [Number] index to items]
             const aliases = crateCorpus.a;
+            // an array of (String) paths of the types
+            const typePaths = crateCorpus.y || [];
+            // an object of (Number) type to (Number | String) deref target
+            const derefs = crateCorpus.r;
+            // an object of (Number) trait to [(Number | String) implementor]
+            const implementors = crateCorpus.m;
+
+            for (const path of typePaths) {
+                const name = keyName(path);
+                if (TYPE_PATHS[name] === undefined) {
+                    TYPE_PATHS[name] = [];
+                }
+                if (TYPE_PATHS[name].indexOf(path) === -1) {
+                    TYPE_PATHS[name].push(path);
+                }
+            }
 
             // convert `rawPaths` entries into object form
             let len = paths.length;
@@ -1957,7 +2425,7 @@ function initSearch(rawSearchIndex) {
                     path: itemPaths[i] ? itemPaths[i] : lastPath,
                     desc: itemDescs[i],
                     parent: itemParentIdxs[i] > 0 ? paths[itemParentIdxs[i] - 1] : undefined,
-                    type: itemFunctionSearchTypes[i],
+                    type: convertFunctionType(itemFunctionSearchTypes[i], typePaths),
                     id: id,
                     normalizedName: word.indexOf("_") === -1 ? word : word.replace(/_/g, ""),
                 };
@@ -1965,6 +2433,9 @@ function initSearch(rawSearchIndex) {
                 searchIndex.push(row);
                 lastPath = row.path;
                 crateSize += 1;
+                if (TYPE_ITEM_TYPES.indexOf(row.ty) !== -1) {
+                    TYPE_NAMES[word] = true;
+                }
             }
 
             if (aliases) {
@@ -1982,11 +2453,52 @@ function initSearch(rawSearchIndex) {
                     }
                 }
             }
+            if (derefs) {
+                for (const type in derefs) {
+                    if (hasOwnPropertyRustdoc(derefs, type)) {
+                        DEREFS[typePaths[type]] = convertKey(derefs[type], typePaths);
+                    }
+                }
+            }
+            if (implementors) {
+                for (const traitIndex in implementors) {
+                    if (!hasOwnPropertyRustdoc(implementors, traitIndex)) {
+                        continue;
+                    }
+                    const trait = typePaths[traitIndex];
+                    if (IMPLEMENTORS[trait] === undefined) {
+                        IMPLEMENTORS[trait] = Object.create(null);
+                    }
+                    for (const implementor of implementors[traitIndex]) {
+                        IMPLEMENTORS[trait][convertKey(implementor, typePaths)] = true;
+                    }
+                }
+            }
             currentIndex += crateSize;
         }
+        addBlanketImplementors();
         return searchWords;
     }
 
+    /**
+     * Expands the blanket implementations in `IMPLEMENTORS`, once the search index of all the
+     * crates is loaded: the implementors of `Iterator` become implementors of `IntoIterator` as
+     * well, through `impl<I: Iterator> IntoIterator for I`.
+     */
+    function addBlanketImplementors() {
+        for (const trait of Object.keys(IMPLEMENTORS)) {
+            // `Object.keys` returns a copy, since more implementors are added along the way.
+            for (const implementor of Object.keys(IMPLEMENTORS[trait])) {
+                if (implementor === trait || IMPLEMENTORS[implementor] === undefined) {
+                    continue;
+                }
+                for (const blanketImplementor of Object.keys(IMPLEMENTORS[implementor])) {
+                    IMPLEMENTORS[trait][blanketImplementor] = true;
+                }
+            }
+        }
+    }
+
     /**
      * Callback for when the search form is submitted.
      * @param {Event} [e] - The event that triggered this call, if any
@@ -41,6 +41,7 @@ pub(super) fn convert_item(&self, item: clean::Item) -> Option<Item> {
             .map(rustc_ast_pretty::pprust::attribute_to_string)
             .collect();
         let span = item.span(self.tcx);
+        let cfg = item.cfg.as_deref().map(from_cfg);
         let clean::Item { name, attrs: _, kind: _, visibility, item_id, cfg: _ } = item;
         let inner = match *item.kind {
             clean::StrippedItem(_) => return None,
@@ -55,6 +56,7 @@ pub(super) fn convert_item(&self, item: clean::Item) -> Option<Item> {
             docs,
             attrs,
             deprecation: deprecation.map(from_deprecation),
+            cfg,
             inner,
             links,
         })
@@ -116,6 +118,19 @@ pub(crate) fn from_deprecation(deprecation: rustc_attr::Deprecation) -> Deprecat
     Deprecation { since: since.map(|s| s.to_string()), note: note.map(|s| s.to_string()) }
 }
 
+pub(crate) fn from_cfg(cfg: &clean::cfg::Cfg) -> Cfg {
+    match cfg {
+        clean::cfg::Cfg::True => Cfg::True,
+        clean::cfg::Cfg::False => Cfg::False,
+        clean::cfg::Cfg::Cfg(name, value) => {
+            Cfg::Cfg { name: name.to_string(), value: value.map(|value| value.to_string()) }
+        }
+        clean::cfg::Cfg::Not(cfg) => Cfg::Not(Box::new(from_cfg(cfg))),
+        clean::cfg::Cfg::Any(cfgs) => Cfg::Any(cfgs.iter().map(from_cfg).collect()),
+        clean::cfg::Cfg::All(cfgs) => Cfg::All(cfgs.iter().map(from_cfg).collect()),
+    }
+}
+
 impl FromWithTcx<clean::GenericArgs> for GenericArgs {
     fn from_tcx(args: clean::GenericArgs, tcx: TyCtxt<'_>) -> Self {
         use clean::GenericArgs::*;
@@ -216,6 +231,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 fn from_clean_item(item: clean::Item, tcx: TyCtxt<'_>) -> ItemEnum {
     use clean::ItemKind::*;
     let name = item.name;
+    let item_id = item.item_id;
     let is_crate = item.is_crate();
     let header = item.fn_header(tcx);
 
@@ -229,7 +245,7 @@ fn from_clean_item(item: clean::Item, tcx: TyCtxt<'_>) -> ItemEnum {
         VariantItem(v) => ItemEnum::Variant(v.into_tcx(tcx)),
         FunctionItem(f) => ItemEnum::Function(from_function(f, header.unwrap(), tcx)),
         ForeignFunctionItem(f) => ItemEnum::Function(from_function(f, header.unwrap(), tcx)),
-        TraitItem(t) => ItemEnum::Trait(t.into_tcx(tcx)),
+        TraitItem(t) => ItemEnum::Trait(from_trait(t, item_id.expect_def_id(), tcx)),
         TraitAliasItem(t) => ItemEnum::TraitAlias(t.into_tcx(tcx)),
         MethodItem(m, _) => ItemEnum::Method(from_function_method(m, true, header.unwrap(), tcx)),
         TyMethodItem(m) => ItemEnum::Method(from_function_method(m, false, header.unwrap(), tcx)),
@@ -530,17 +546,16 @@ fn from_tcx(decl: clean::FnDecl, tcx: TyCtxt<'_>) -> Self {
     }
 }
 
-impl FromWithTcx<clean::Trait> for Trait {
-    fn from_tcx(trait_: clean::Trait, tcx: TyCtxt<'_>) -> Self {
-        let clean::Trait { unsafety, items, generics, bounds, is_auto } = trait_;
-        Trait {
-            is_auto,
-            is_unsafe: unsafety == rustc_hir::Unsafety::Unsafe,
-            items: ids(items, tcx),
-            generics: generics.into_tcx(tcx),
-            bounds: bounds.into_iter().map(|x| x.into_tcx(tcx)).collect(),
-            implementations: Vec::new(), // Added in JsonRenderer::item
-        }
+pub(crate) fn from_trait(trait_: clean::Trait, did: DefId, tcx: TyCtxt<'_>) -> Trait {
+    let clean::Trait { unsafety, items, generics, bounds, is_auto } = trait_;
+    Trait {
+        is_auto,
+        is_unsafe: unsafety == rustc_hir::Unsafety::Unsafe,
+        is_object_safe: tcx.is_object_safe(did),
+        items: ids(items, tcx),
+        generics: generics.into_tcx(tcx),
+        bounds: bounds.into_iter().map(|x| x.into_tcx(tcx)).collect(),
+        implementations: Vec::new(), // Added in JsonRenderer::item
     }
 }
 
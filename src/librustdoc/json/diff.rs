@@ -0,0 +1,892 @@
+//! Comparing the public API of two versions of a crate, see `--api-diff`.
+//!
+//! Both versions are read from rustdoc's JSON output. Their public items are collected under
+//! the paths they can be named by from other crates, and the items found under the same path in
+//! both versions are compared by their rendered signature, by the traits they implement and by
+//! the auto traits they implement. Each change is classified as major if it can break code using
+//! the crate, and as minor otherwise, following the SemVer guidelines of the Cargo book as far as
+//! they can be checked from the JSON output.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::Path;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustdoc_json_types::{
+    Abi, Crate, FnDecl, GenericArg, GenericArgs, GenericBound, GenericParamDef,
+    GenericParamDefKind, Generics, Header, Id, Impl, Import, Item, ItemEnum, MacroKind, Module,
+    StructType, Term, TraitBoundModifier, Type, TypeBinding, TypeBindingKind, Variant, Visibility,
+    WherePredicate, FORMAT_VERSION,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Traits the compiler implements for types automatically, which are tracked separately from
+/// the other trait impls as they are usually not written down.
+const AUTO_TRAITS: &[&str] = &["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
+
+/// Compares the JSON output of the crate at `old` with the one at `new` and prints the changes to
+/// the public API to stdout.
+pub(crate) fn run(old: &Path, new: &Path) -> Result<(), String> {
+    let old = load(old)?;
+    let new = load(new)?;
+    print!("{}", render(&diff(&old, &new)));
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<Crate, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read `{}`: {}", path.display(), err))?;
+    let krate: Crate = serde_json::from_str(&json)
+        .map_err(|err| format!("failed to parse `{}`: {}", path.display(), err))?;
+    if krate.format_version != FORMAT_VERSION {
+        return Err(format!(
+            "`{}` uses version {} of the JSON format, but this rustdoc reads version {}",
+            path.display(),
+            krate.format_version,
+            FORMAT_VERSION,
+        ));
+    }
+    Ok(krate)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Severity {
+    /// A change that can break code using the crate.
+    Major,
+    /// A change that cannot break code using the crate.
+    Minor,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Change {
+    pub(crate) severity: Severity,
+    pub(crate) path: String,
+    pub(crate) message: String,
+    /// The old and the new signature of a changed item.
+    pub(crate) signatures: Option<(String, String)>,
+}
+
+impl Change {
+    fn new(severity: Severity, path: &str, message: String) -> Change {
+        Change { severity, path: path.to_owned(), message, signatures: None }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Major => "major",
+            Severity::Minor => "minor",
+        };
+        write!(f, "{}: {}", severity, self.message)?;
+        if let Some((old, new)) = &self.signatures {
+            write!(f, "\n    old: {}\n    new: {}", old, new)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the `changes` as a report, one change per line, followed by a summary of what kind
+/// of version bump they require.
+pub(crate) fn render(changes: &[Change]) -> String {
+    let mut out = String::new();
+    for change in changes {
+        let _ = writeln!(out, "{}", change);
+    }
+    let major = changes.iter().filter(|change| change.severity == Severity::Major).count();
+    let minor = changes.len() - major;
+    if changes.is_empty() {
+        out.push_str("no changes to the public API\n");
+    } else {
+        let _ = writeln!(
+            out,
+            "\n{} major and {} minor change{}, the new version needs a {} version bump",
+            major,
+            minor,
+            if changes.len() == 1 { "" } else { "s" },
+            if major > 0 { "major" } else { "minor" },
+        );
+    }
+    out
+}
+
+/// Returns the changes to the public API from `old` to `new`, major changes first and sorted
+/// by path otherwise.
+pub(crate) fn diff(old: &Crate, new: &Crate) -> Vec<Change> {
+    let old_api = public_api(old);
+    let new_api = public_api(new);
+    // Fields, variants and associated items of added or removed items are not listed on their
+    // own, they are part of the change to their parent.
+    let has_parent_in = |item: &ApiItem, api: &BTreeMap<String, ApiItem>| {
+        item.parent.as_ref().map_or(true, |parent| api.contains_key(parent))
+    };
+
+    let mut changes = Vec::new();
+    for (path, old_item) in &old_api {
+        match new_api.get(path) {
+            Some(new_item) => compare(path, old_item, new_item, &mut changes),
+            None if has_parent_in(old_item, &new_api) => changes.push(Change::new(
+                Severity::Major,
+                path,
+                format!("removed {} `{}`", old_item.kind, path),
+            )),
+            None => {}
+        }
+    }
+    for (path, new_item) in &new_api {
+        if old_api.contains_key(path) || !has_parent_in(new_item, &old_api) {
+            continue;
+        }
+        changes.push(match new_item.breaking_addition {
+            Some(parent) => Change::new(
+                Severity::Major,
+                path,
+                format!("added {} `{}` to {}", new_item.kind, path, parent),
+            ),
+            None => {
+                Change::new(Severity::Minor, path, format!("added {} `{}`", new_item.kind, path))
+            }
+        });
+    }
+    changes.sort();
+    changes
+}
+
+fn compare(path: &str, old: &ApiItem, new: &ApiItem, changes: &mut Vec<Change>) {
+    if old.kind != new.kind || old.signature != new.signature {
+        changes.push(Change {
+            signatures: Some((old.signature.clone(), new.signature.clone())),
+            ..Change::new(Severity::Major, path, format!("changed {} `{}`", new.kind, path))
+        });
+    }
+    match (old.provided, new.provided) {
+        (Some(true), Some(false)) => changes.push(Change::new(
+            Severity::Major,
+            path,
+            format!("`{}` no longer has a default", path),
+        )),
+        (Some(false), Some(true)) => changes.push(Change::new(
+            Severity::Minor,
+            path,
+            format!("`{}` now has a default", path),
+        )),
+        _ => {}
+    }
+    for removed in old.impls.difference(&new.impls) {
+        changes.push(Change::new(Severity::Major, path, format!("removed `{}`", removed)));
+    }
+    for added in new.impls.difference(&old.impls) {
+        changes.push(Change::new(Severity::Minor, path, format!("added `{}`", added)));
+    }
+    for lost in old.auto_traits.difference(&new.auto_traits) {
+        changes.push(Change::new(
+            Severity::Major,
+            path,
+            format!("`{}` no longer implements `{}`", path, lost),
+        ));
+    }
+    for gained in new.auto_traits.difference(&old.auto_traits) {
+        changes.push(Change::new(
+            Severity::Minor,
+            path,
+            format!("`{}` now implements `{}`", path, gained),
+        ));
+    }
+}
+
+/// An item of the public API, under one of the paths it can be named by.
+#[derive(Debug, Default)]
+struct ApiItem {
+    kind: &'static str,
+    signature: String,
+    /// The path of the item this is a field, variant or associated item of.
+    parent: Option<String>,
+    /// For items of traits, whether the trait provides a default for them.
+    provided: Option<bool>,
+    /// The kind of parent that adding this item to breaks code using it, if it does.
+    breaking_addition: Option<&'static str>,
+    /// The headers of the trait impls of a type.
+    impls: BTreeSet<String>,
+    /// The auto traits a type implements.
+    auto_traits: BTreeSet<String>,
+}
+
+impl ApiItem {
+    fn new(kind: &'static str, signature: String) -> ApiItem {
+        ApiItem { kind, signature, ..Default::default() }
+    }
+
+    fn member_of(mut self, parent: &str) -> ApiItem {
+        self.parent = Some(parent.to_owned());
+        self
+    }
+}
+
+/// What a public path of the crate refers to.
+enum Public<'a> {
+    Item(&'a Item),
+    /// A re-export of an item that is not part of the JSON output.
+    Reexport(&'a Import),
+}
+
+fn public_api(krate: &Crate) -> BTreeMap<String, ApiItem> {
+    let mut walker = Walker {
+        krate,
+        modules: FxHashSet::default(),
+        found: BTreeMap::new(),
+        public_paths: FxHashMap::default(),
+    };
+    if let Some(root) = krate.index.get(&krate.root) {
+        walker.module(root, root.name.clone().unwrap_or_default());
+    }
+
+    let mut builder =
+        ApiBuilder { krate, public_paths: walker.public_paths, items: BTreeMap::new() };
+    for (path, public) in walker.found {
+        match public {
+            Public::Item(item) => builder.add_item(path, item),
+            Public::Reexport(import) => {
+                builder
+                    .items
+                    .insert(path, ApiItem::new("re-export", format!("pub use {}", import.source)));
+            }
+        }
+    }
+    builder.items
+}
+
+/// Finds the public paths of a crate by walking its modules, starting from the crate root.
+struct Walker<'a> {
+    krate: &'a Crate,
+    /// The modules currently being walked, to not loop on glob re-exports of a parent module.
+    modules: FxHashSet<&'a Id>,
+    found: BTreeMap<String, Public<'a>>,
+    /// The preferred path of each item, which is used for it in signatures, so that moving an
+    /// item between private modules does not change the signatures mentioning it.
+    public_paths: FxHashMap<&'a Id, String>,
+}
+
+impl<'a> Walker<'a> {
+    fn module(&mut self, item: &'a Item, path: String) {
+        let ItemEnum::Module(module) = &item.inner else { return };
+        if !self.modules.insert(&item.id) {
+            return;
+        }
+        self.module_items(module, &path);
+        self.modules.remove(&item.id);
+        self.found.insert(path, Public::Item(item));
+    }
+
+    fn module_items(&mut self, module: &'a Module, path: &str) {
+        for id in &module.items {
+            let Some(child) = self.krate.index.get(id) else { continue };
+            if child.visibility != Visibility::Public {
+                continue;
+            }
+            match &child.inner {
+                ItemEnum::Import(import) => self.import(import, path),
+                _ => {
+                    if let Some(name) = &child.name {
+                        self.item(child, format!("{}::{}", path, name));
+                    }
+                }
+            }
+        }
+    }
+
+    fn import(&mut self, import: &'a Import, path: &str) {
+        let target = import.id.as_ref().and_then(|id| self.krate.index.get(id));
+        match target {
+            Some(target) if import.glob => match &target.inner {
+                ItemEnum::Module(module) => {
+                    if self.modules.insert(&target.id) {
+                        self.module_items(module, path);
+                        self.modules.remove(&target.id);
+                    }
+                }
+                ItemEnum::Enum(enum_) => {
+                    for variant in enum_.variants.iter().filter_map(|id| self.krate.index.get(id)) {
+                        if let Some(name) = &variant.name {
+                            self.item(variant, format!("{}::{}", path, name));
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Some(target) => self.item(target, format!("{}::{}", path, import.name)),
+            // The contents of glob re-exports of other crates are not known.
+            None if import.glob => {}
+            None => {
+                self.found.insert(format!("{}::{}", path, import.name), Public::Reexport(import));
+            }
+        }
+    }
+
+    fn item(&mut self, item: &'a Item, path: String) {
+        match &item.inner {
+            ItemEnum::Module(_) => self.module(item, path),
+            ItemEnum::Import(_) | ItemEnum::Impl(_) | ItemEnum::PrimitiveType(_) => {}
+            _ => {
+                let preferred = self.public_paths.entry(&item.id).or_insert_with(|| path.clone());
+                let depth = |path: &str| path.matches("::").count();
+                if (depth(&path), &path) < (depth(preferred), &*preferred) {
+                    *preferred = path.clone();
+                }
+                self.found.insert(path, Public::Item(item));
+            }
+        }
+    }
+}
+
+/// Describes the public items of a crate, rendering their signatures as they would be written
+/// in Rust, except that paths are always written in full and argument names are left out.
+struct ApiBuilder<'a> {
+    krate: &'a Crate,
+    public_paths: FxHashMap<&'a Id, String>,
+    items: BTreeMap<String, ApiItem>,
+}
+
+impl<'a> ApiBuilder<'a> {
+    fn add_item(&mut self, path: String, item: &Item) {
+        let name = path.rsplit("::").next().unwrap_or_default().to_owned();
+        let non_exhaustive = is_non_exhaustive(item);
+        let api_item = match &item.inner {
+            ItemEnum::Module(_) => ApiItem::new("module", format!("mod {}", name)),
+            ItemEnum::ExternCrate { name: krate, .. } => {
+                ApiItem::new("extern crate", format!("extern crate {}", krate))
+            }
+            ItemEnum::Struct(struct_) => {
+                let fields = match (&struct_.struct_type, struct_.fields_stripped) {
+                    (StructType::Unit, _) => "",
+                    (StructType::Tuple, false) => "(..)",
+                    (StructType::Tuple, true) => "(/* private fields */)",
+                    (StructType::Plain, false) => " { .. }",
+                    (StructType::Plain, true) => " { /* private fields */ }",
+                };
+                let signature = format!(
+                    "{}struct {}{}{}{}",
+                    if non_exhaustive { "#[non_exhaustive] " } else { "" },
+                    name,
+                    self.generic_params(&struct_.generics.params),
+                    fields,
+                    self.where_clause(&struct_.generics),
+                );
+                let breaking_addition = (!struct_.fields_stripped && !non_exhaustive)
+                    .then_some("a struct without private fields");
+                self.fields(&struct_.fields, &path, false, breaking_addition);
+                let mut api_item = ApiItem::new("struct", signature);
+                self.impls(&mut api_item, &struct_.impls, &path);
+                api_item
+            }
+            ItemEnum::Union(union_) => {
+                let signature = format!(
+                    "union {}{}{}",
+                    name,
+                    self.generic_params(&union_.generics.params),
+                    self.where_clause(&union_.generics),
+                );
+                self.fields(&union_.fields, &path, false, None);
+                let mut api_item = ApiItem::new("union", signature);
+                self.impls(&mut api_item, &union_.impls, &path);
+                api_item
+            }
+            ItemEnum::Enum(enum_) => {
+                let signature = format!(
+                    "{}enum {}{}{}",
+                    if non_exhaustive { "#[non_exhaustive] " } else { "" },
+                    name,
+                    self.generic_params(&enum_.generics.params),
+                    self.where_clause(&enum_.generics),
+                );
+                let breaking_addition = (!non_exhaustive).then_some("an exhaustive enum");
+                for variant in enum_.variants.iter().filter_map(|id| self.krate.index.get(id)) {
+                    if let Some(variant_name) = &variant.name {
+                        let variant_path = format!("{}::{}", path, variant_name);
+                        let mut api_item = self.variant(&variant_path, variant).member_of(&path);
+                        api_item.breaking_addition = breaking_addition;
+                        self.items.insert(variant_path, api_item);
+                    }
+                }
+                let mut api_item = ApiItem::new("enum", signature);
+                self.impls(&mut api_item, &enum_.impls, &path);
+                api_item
+            }
+            ItemEnum::Variant(_) => self.variant(&path, item),
+            ItemEnum::Function(function) => ApiItem::new(
+                "function",
+                self.function(&name, &function.header, &function.generics, &function.decl),
+            ),
+            ItemEnum::Trait(trait_) => {
+                let mut signature = format!(
+                    "{}{}trait {}{}",
+                    if trait_.is_unsafe { "unsafe " } else { "" },
+                    if trait_.is_auto { "auto " } else { "" },
+                    name,
+                    self.generic_params(&trait_.generics.params),
+                );
+                if !trait_.bounds.is_empty() {
+                    let _ = write!(signature, ": {}", self.bounds(&trait_.bounds));
+                }
+                signature.push_str(&self.where_clause(&trait_.generics));
+                for trait_item in trait_.items.iter().filter_map(|id| self.krate.index.get(id)) {
+                    if let Some(item_name) = &trait_item.name {
+                        let Some(mut api_item) = self.assoc_item(item_name, trait_item) else {
+                            continue;
+                        };
+                        if api_item.provided == Some(false) {
+                            api_item.breaking_addition = Some("a trait");
+                        }
+                        let item_path = format!("{}::{}", path, item_name);
+                        self.items.insert(item_path, api_item.member_of(&path));
+                    }
+                }
+                ApiItem::new("trait", signature)
+            }
+            ItemEnum::TraitAlias(alias) => ApiItem::new(
+                "trait alias",
+                format!(
+                    "trait {}{} = {}{}",
+                    name,
+                    self.generic_params(&alias.generics.params),
+                    self.bounds(&alias.params),
+                    self.where_clause(&alias.generics),
+                ),
+            ),
+            ItemEnum::Typedef(typedef) => ApiItem::new(
+                "type alias",
+                format!(
+                    "type {}{}{} = {}",
+                    name,
+                    self.generic_params(&typedef.generics.params),
+                    self.where_clause(&typedef.generics),
+                    self.ty(&typedef.type_),
+                ),
+            ),
+            ItemEnum::OpaqueTy(opaque) => ApiItem::new(
+                "type alias",
+                format!(
+                    "type {}{}{} = impl {}",
+                    name,
+                    self.generic_params(&opaque.generics.params),
+                    self.where_clause(&opaque.generics),
+                    self.bounds(&opaque.bounds),
+                ),
+            ),
+            ItemEnum::Constant(constant) => {
+                ApiItem::new("constant", format!("const {}: {}", name, self.ty(&constant.type_)))
+            }
+            ItemEnum::Static(static_) => ApiItem::new(
+                "static",
+                format!(
+                    "static {}{}: {}",
+                    if static_.mutable { "mut " } else { "" },
+                    name,
+                    self.ty(&static_.type_),
+                ),
+            ),
+            ItemEnum::ForeignType => ApiItem::new("foreign type", format!("type {}", name)),
+            ItemEnum::Macro(_) => ApiItem::new("macro", format!("macro_rules! {}", name)),
+            ItemEnum::ProcMacro(proc_macro) => {
+                let (kind, signature) = match proc_macro.kind {
+                    MacroKind::Bang => ("macro", format!("{}!", name)),
+                    MacroKind::Attr => ("attribute macro", format!("#[{}]", name)),
+                    MacroKind::Derive if proc_macro.helpers.is_empty() => {
+                        ("derive macro", format!("#[derive({})]", name))
+                    }
+                    MacroKind::Derive => (
+                        "derive macro",
+                        format!(
+                            "#[derive({})] with helper attributes {}",
+                            name,
+                            proc_macro.helpers.join(", ")
+                        ),
+                    ),
+                };
+                ApiItem::new(kind, signature)
+            }
+            ItemEnum::Import(_)
+            | ItemEnum::StructField(_)
+            | ItemEnum::Method(_)
+            | ItemEnum::Impl(_)
+            | ItemEnum::PrimitiveType(_)
+            | ItemEnum::AssocConst { .. }
+            | ItemEnum::AssocType { .. } => return,
+        };
+        self.items.insert(path, api_item);
+    }
+
+    /// Adds the public `fields` of the struct, union or enum variant at `path`. The fields of enum
+    /// variants are as public as the enum itself.
+    fn fields(
+        &mut self,
+        fields: &[Id],
+        path: &str,
+        of_variant: bool,
+        breaking_addition: Option<&'static str>,
+    ) {
+        for field in fields.iter().filter_map(|id| self.krate.index.get(id)) {
+            let (Some(name), ItemEnum::StructField(ty)) = (&field.name, &field.inner) else {
+                continue;
+            };
+            if field.visibility != Visibility::Public && !of_variant {
+                continue;
+            }
+            let mut api_item =
+                ApiItem::new("field", format!("{}: {}", name, self.ty(ty))).member_of(path);
+            api_item.breaking_addition = breaking_addition;
+            self.items.insert(format!("{}::{}", path, name), api_item);
+        }
+    }
+
+    fn variant(&mut self, path: &str, variant: &Item) -> ApiItem {
+        let name = path.rsplit("::").next().unwrap_or_default();
+        let non_exhaustive = is_non_exhaustive(variant);
+        let signature = match &variant.inner {
+            ItemEnum::Variant(Variant::Plain) => name.to_owned(),
+            ItemEnum::Variant(Variant::Tuple(types)) => {
+                format!("{}({})", name, self.list(types, |ty| self.ty(ty)))
+            }
+            ItemEnum::Variant(Variant::Struct(fields)) => {
+                let breaking_addition = (!non_exhaustive).then_some("an enum variant");
+                self.fields(fields, path, true, breaking_addition);
+                format!("{} {{ .. }}", name)
+            }
+            _ => String::new(),
+        };
+        let prefix = if non_exhaustive { "#[non_exhaustive] " } else { "" };
+        ApiItem::new("variant", format!("{}{}", prefix, signature))
+    }
+
+    /// Describes an associated item of a trait or of an inherent impl.
+    fn assoc_item(&self, name: &str, item: &Item) -> Option<ApiItem> {
+        let api_item = match &item.inner {
+            ItemEnum::Method(method) => ApiItem {
+                provided: Some(method.has_body),
+                ..ApiItem::new(
+                    "method",
+                    self.function(name, &method.header, &method.generics, &method.decl),
+                )
+            },
+            ItemEnum::AssocConst { type_, default } => ApiItem {
+                provided: Some(default.is_some()),
+                ..ApiItem::new("associated constant", format!("const {}: {}", name, self.ty(type_)))
+            },
+            ItemEnum::AssocType { generics, bounds, default } => {
+                let mut signature =
+                    format!("type {}{}", name, self.generic_params(&generics.params));
+                if !bounds.is_empty() {
+                    let _ = write!(signature, ": {}", self.bounds(bounds));
+                }
+                signature.push_str(&self.where_clause(generics));
+                ApiItem {
+                    provided: Some(default.is_some()),
+                    ..ApiItem::new("associated type", signature)
+                }
+            }
+            _ => return None,
+        };
+        Some(api_item)
+    }
+
+    /// Collects the trait impls of the type at `path` into `api_item`, and adds the public items
+    /// of its inherent impls. Blanket impls are left out, they change along with the bounds the
+    /// type satisfies.
+    fn impls(&mut self, api_item: &mut ApiItem, impls: &[Id], path: &str) {
+        for impl_ in impls.iter().filter_map(|id| self.krate.index.get(id)) {
+            let ItemEnum::Impl(impl_) = &impl_.inner else { continue };
+            if impl_.blanket_impl.is_some() {
+                continue;
+            }
+            let Some(trait_) = &impl_.trait_ else {
+                for item in impl_.items.iter().filter_map(|id| self.krate.index.get(id)) {
+                    if item.visibility != Visibility::Public {
+                        continue;
+                    }
+                    let Some(name) = &item.name else { continue };
+                    if let Some(mut assoc_item) = self.assoc_item(name, item) {
+                        assoc_item.provided = None;
+                        self.items
+                            .insert(format!("{}::{}", path, name), assoc_item.member_of(path));
+                    }
+                }
+                continue;
+            };
+            let trait_name = match trait_ {
+                Type::ResolvedPath { name, .. } => name.rsplit("::").next().unwrap_or_default(),
+                _ => "",
+            };
+            if impl_.synthetic || AUTO_TRAITS.contains(&trait_name) {
+                if !impl_.negative {
+                    api_item.auto_traits.insert(trait_name.to_owned());
+                }
+            } else {
+                api_item.impls.insert(self.impl_header(impl_, trait_));
+            }
+        }
+    }
+
+    fn impl_header(&self, impl_: &Impl, trait_: &Type) -> String {
+        format!(
+            "{}impl{} {}{} for {}{}",
+            if impl_.is_unsafe { "unsafe " } else { "" },
+            self.generic_params(&impl_.generics.params),
+            if impl_.negative { "!" } else { "" },
+            self.ty(trait_),
+            self.ty(&impl_.for_),
+            self.where_clause(&impl_.generics),
+        )
+    }
+
+    fn function(&self, name: &str, header: &Header, generics: &Generics, decl: &FnDecl) -> String {
+        format!(
+            "{}fn {}{}{}{}",
+            self.header(header),
+            name,
+            self.generic_params(&generics.params),
+            self.fn_decl(decl),
+            self.where_clause(generics),
+        )
+    }
+
+    fn header(&self, header: &Header) -> String {
+        let mut out = String::new();
+        if header.const_ {
+            out.push_str("const ");
+        }
+        if header.async_ {
+            out.push_str("async ");
+        }
+        if header.unsafe_ {
+            out.push_str("unsafe ");
+        }
+        let (abi, unwind) = match &header.abi {
+            Abi::Rust => return out,
+            Abi::C { unwind } => ("C", *unwind),
+            Abi::Cdecl { unwind } => ("cdecl", *unwind),
+            Abi::Stdcall { unwind } => ("stdcall", *unwind),
+            Abi::Fastcall { unwind } => ("fastcall", *unwind),
+            Abi::Aapcs { unwind } => ("aapcs", *unwind),
+            Abi::Win64 { unwind } => ("win64", *unwind),
+            Abi::SysV64 { unwind } => ("sysv64", *unwind),
+            Abi::System { unwind } => ("system", *unwind),
+            Abi::Other(abi) => (abi.as_str(), false),
+        };
+        let _ = write!(out, "extern \"{}{}\" ", abi, if unwind { "-unwind" } else { "" });
+        out
+    }
+
+    /// Renders the argument and return types of a function, its argument names do not matter to
+    /// callers.
+    fn fn_decl(&self, decl: &FnDecl) -> String {
+        let mut inputs = decl.inputs.iter().map(|(_, ty)| self.ty(ty)).collect::<Vec<_>>();
+        if decl.c_variadic {
+            inputs.push("...".to_owned());
+        }
+        let mut out = format!("({})", inputs.join(", "));
+        if let Some(output) = &decl.output {
+            let _ = write!(out, " -> {}", self.ty(output));
+        }
+        out
+    }
+
+    fn generic_params(&self, params: &[GenericParamDef]) -> String {
+        let params = params
+            .iter()
+            .filter(|param| {
+                !matches!(param.kind, GenericParamDefKind::Type { synthetic: true, .. })
+            })
+            .map(|param| self.generic_param(param))
+            .collect::<Vec<_>>();
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params.join(", "))
+        }
+    }
+
+    fn generic_param(&self, param: &GenericParamDef) -> String {
+        match &param.kind {
+            GenericParamDefKind::Lifetime { outlives } if outlives.is_empty() => param.name.clone(),
+            GenericParamDefKind::Lifetime { outlives } => {
+                format!("{}: {}", param.name, outlives.join(" + "))
+            }
+            GenericParamDefKind::Type { bounds, default, .. } => {
+                let mut out = param.name.clone();
+                if !bounds.is_empty() {
+                    let _ = write!(out, ": {}", self.bounds(bounds));
+                }
+                if let Some(default) = default {
+                    let _ = write!(out, " = {}", self.ty(default));
+                }
+                out
+            }
+            GenericParamDefKind::Const { type_, default } => {
+                let mut out = format!("const {}: {}", param.name, self.ty(type_));
+                if let Some(default) = default {
+                    let _ = write!(out, " = {}", default);
+                }
+                out
+            }
+        }
+    }
+
+    fn where_clause(&self, generics: &Generics) -> String {
+        if generics.where_predicates.is_empty() {
+            return String::new();
+        }
+        let predicates = self.list(&generics.where_predicates, |predicate| match predicate {
+            WherePredicate::BoundPredicate { type_, bounds, generic_params } => format!(
+                "{}{}: {}",
+                self.higher_ranked(generic_params),
+                self.ty(type_),
+                self.bounds(bounds)
+            ),
+            WherePredicate::RegionPredicate { lifetime, bounds } => {
+                format!("{}: {}", lifetime, self.bounds(bounds))
+            }
+            WherePredicate::EqPredicate { lhs, rhs } => {
+                format!("{} = {}", self.ty(lhs), self.term(rhs))
+            }
+        });
+        format!(" where {}", predicates)
+    }
+
+    fn higher_ranked(&self, params: &[GenericParamDef]) -> String {
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("for<{}> ", self.list(params, |param| self.generic_param(param)))
+        }
+    }
+
+    fn bounds(&self, bounds: &[GenericBound]) -> String {
+        bounds
+            .iter()
+            .map(|bound| match bound {
+                GenericBound::TraitBound { trait_, generic_params, modifier } => {
+                    let modifier = match modifier {
+                        TraitBoundModifier::None => "",
+                        TraitBoundModifier::Maybe => "?",
+                        TraitBoundModifier::MaybeConst => "~const ",
+                    };
+                    format!("{}{}{}", self.higher_ranked(generic_params), modifier, self.ty(trait_))
+                }
+                GenericBound::Outlives(lifetime) => lifetime.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    fn ty(&self, ty: &Type) -> String {
+        match ty {
+            Type::ResolvedPath { name, id, args, param_names } => {
+                let mut out = self.path(id, name);
+                if let Some(args) = args {
+                    out.push_str(&self.generic_args(args));
+                }
+                if param_names.is_empty() {
+                    out
+                } else {
+                    // A trait object with additional bounds.
+                    format!("dyn {} + {}", out, self.bounds(param_names))
+                }
+            }
+            Type::Generic(name) | Type::Primitive(name) => name.clone(),
+            Type::FunctionPointer(pointer) => format!(
+                "{}{}fn{}",
+                self.higher_ranked(&pointer.generic_params),
+                self.header(&pointer.header),
+                self.fn_decl(&pointer.decl),
+            ),
+            Type::Tuple(types) if types.len() == 1 => format!("({},)", self.ty(&types[0])),
+            Type::Tuple(types) => format!("({})", self.list(types, |ty| self.ty(ty))),
+            Type::Slice(ty) => format!("[{}]", self.ty(ty)),
+            Type::Array { type_, len } => format!("[{}; {}]", self.ty(type_), len),
+            Type::ImplTrait(bounds) => format!("impl {}", self.bounds(bounds)),
+            Type::Infer => "_".to_owned(),
+            Type::RawPointer { mutable, type_ } => {
+                format!("*{} {}", if *mutable { "mut" } else { "const" }, self.ty(type_))
+            }
+            Type::BorrowedRef { lifetime, mutable, type_ } => format!(
+                "&{}{}{}",
+                lifetime.as_ref().map(|lifetime| format!("{} ", lifetime)).unwrap_or_default(),
+                if *mutable { "mut " } else { "" },
+                self.ty(type_),
+            ),
+            Type::QualifiedPath { name, args, self_type, trait_ } => format!(
+                "<{} as {}>::{}{}",
+                self.ty(self_type),
+                self.ty(trait_),
+                name,
+                self.generic_args(args),
+            ),
+        }
+    }
+
+    /// Returns the path `id` is referred to by: its preferred public path for items of this
+    /// crate, and the path it is defined at for items of other crates.
+    fn path(&self, id: &Id, name: &str) -> String {
+        if let Some(path) = self.public_paths.get(id) {
+            return path.clone();
+        }
+        match self.krate.paths.get(id) {
+            Some(summary) => summary.path.join("::"),
+            None => name.to_owned(),
+        }
+    }
+
+    fn generic_args(&self, args: &GenericArgs) -> String {
+        match args {
+            GenericArgs::AngleBracketed { args, bindings } => {
+                let args = args
+                    .iter()
+                    .map(|arg| match arg {
+                        GenericArg::Lifetime(lifetime) => lifetime.clone(),
+                        GenericArg::Type(ty) => self.ty(ty),
+                        GenericArg::Const(constant) => constant.expr.clone(),
+                        GenericArg::Infer => "_".to_owned(),
+                    })
+                    .chain(bindings.iter().map(|binding| self.binding(binding)))
+                    .collect::<Vec<_>>();
+                if args.is_empty() {
+                    String::new()
+                } else {
+                    format!("<{}>", args.join(", "))
+                }
+            }
+            GenericArgs::Parenthesized { inputs, output } => {
+                let mut out = format!("({})", self.list(inputs, |ty| self.ty(ty)));
+                if let Some(output) = output {
+                    let _ = write!(out, " -> {}", self.ty(output));
+                }
+                out
+            }
+        }
+    }
+
+    fn binding(&self, binding: &TypeBinding) -> String {
+        let name = format!("{}{}", binding.name, self.generic_args(&binding.args));
+        match &binding.binding {
+            TypeBindingKind::Equality(term) => format!("{} = {}", name, self.term(term)),
+            TypeBindingKind::Constraint(bounds) => format!("{}: {}", name, self.bounds(bounds)),
+        }
+    }
+
+    fn term(&self, term: &Term) -> String {
+        match term {
+            Term::Type(ty) => self.ty(ty),
+            Term::Constant(constant) => constant.expr.clone(),
+        }
+    }
+
+    fn list<T>(&self, items: &[T], render: impl Fn(&T) -> String) -> String {
+        items.iter().map(render).collect::<Vec<_>>().join(", ")
+    }
+}
+
+fn is_non_exhaustive(item: &Item) -> bool {
+    item.attrs.iter().any(|attr| attr == "#[non_exhaustive]")
+}
@@ -0,0 +1,255 @@
+use super::*;
+
+use rustdoc_json_types::{Enum, Function, Method, Struct, Trait};
+
+fn item(id: &str, name: &str, inner: ItemEnum) -> Item {
+    Item {
+        id: Id(id.to_owned()),
+        crate_id: 0,
+        name: Some(name.to_owned()),
+        span: None,
+        visibility: Visibility::Public,
+        docs: None,
+        links: Default::default(),
+        attrs: Vec::new(),
+        deprecation: None,
+        cfg: None,
+        inner,
+    }
+}
+
+fn ids(ids: &[&str]) -> Vec<Id> {
+    ids.iter().map(|id| Id(id.to_string())).collect()
+}
+
+/// A crate named `krate` whose root module contains the items with the `root` ids.
+fn krate(root: &[&str], items: Vec<Item>) -> Crate {
+    let root_module =
+        item("0", "krate", ItemEnum::Module(Module { is_crate: true, items: ids(root) }));
+    Crate {
+        root: root_module.id.clone(),
+        crate_version: None,
+        includes_private: false,
+        index: items.into_iter().chain([root_module]).map(|item| (item.id.clone(), item)).collect(),
+        paths: Default::default(),
+        external_crates: Default::default(),
+        format_version: FORMAT_VERSION,
+    }
+}
+
+fn generics() -> Generics {
+    Generics { params: Vec::new(), where_predicates: Vec::new() }
+}
+
+fn header() -> Header {
+    Header { const_: false, unsafe_: false, async_: false, abi: Abi::Rust }
+}
+
+fn function(id: &str, name: &str, input: &str) -> Item {
+    let decl = FnDecl {
+        inputs: vec![("x".to_owned(), Type::Primitive(input.to_owned()))],
+        output: None,
+        c_variadic: false,
+    };
+    item(id, name, ItemEnum::Function(Function { decl, generics: generics(), header: header() }))
+}
+
+fn method(id: &str, name: &str, has_body: bool) -> Item {
+    let decl = FnDecl { inputs: Vec::new(), output: None, c_variadic: false };
+    let method = Method { decl, generics: generics(), header: header(), has_body };
+    Item { visibility: Visibility::Default, ..item(id, name, ItemEnum::Method(method)) }
+}
+
+fn struct_(id: &str, fields: &[&str], fields_stripped: bool, impls: &[&str]) -> Item {
+    let struct_ = Struct {
+        struct_type: StructType::Plain,
+        generics: generics(),
+        fields_stripped,
+        fields: ids(fields),
+        impls: ids(impls),
+    };
+    item(id, "S", ItemEnum::Struct(struct_))
+}
+
+fn field(id: &str, name: &str) -> Item {
+    item(id, name, ItemEnum::StructField(Type::Primitive("u8".to_owned())))
+}
+
+fn trait_impl(id: &str, trait_name: &str, synthetic: bool) -> Item {
+    let impl_ = Impl {
+        is_unsafe: false,
+        generics: generics(),
+        provided_trait_methods: Vec::new(),
+        trait_: Some(Type::ResolvedPath {
+            name: trait_name.to_owned(),
+            id: Id(format!("1:{}", trait_name)),
+            args: None,
+            param_names: Vec::new(),
+        }),
+        for_: Type::ResolvedPath {
+            name: "S".to_owned(),
+            id: Id("s".to_owned()),
+            args: None,
+            param_names: Vec::new(),
+        },
+        items: Vec::new(),
+        negative: false,
+        synthetic,
+        blanket_impl: None,
+    };
+    Item { name: None, ..item(id, "", ItemEnum::Impl(impl_)) }
+}
+
+fn messages(old: &Crate, new: &Crate) -> Vec<String> {
+    diff(old, new).iter().map(|change| change.to_string()).collect()
+}
+
+#[test]
+fn added_removed_and_changed_functions() {
+    let old = krate(&["f", "g"], vec![function("f", "f", "u32"), function("g", "g", "u32")]);
+    let new = krate(&["f", "h"], vec![function("f", "f", "u64"), function("h", "h", "u32")]);
+    assert_eq!(
+        messages(&old, &new),
+        [
+            "major: changed function `krate::f`\n    old: fn f(u32)\n    new: fn f(u64)",
+            "major: removed function `krate::g`",
+            "minor: added function `krate::h`",
+        ]
+    );
+}
+
+#[test]
+fn moving_an_item_behind_a_reexport() {
+    let old = krate(&["f"], vec![function("f", "f", "u32")]);
+    let import = Import {
+        source: "self::inner::f".to_owned(),
+        name: "f".to_owned(),
+        id: Some(Id("f".to_owned())),
+        glob: false,
+    };
+    let new = krate(
+        &["use"],
+        vec![item("use", "f", ItemEnum::Import(import)), function("f", "f", "u32")],
+    );
+    assert_eq!(messages(&old, &new), Vec::<String>::new());
+    assert_eq!(render(&diff(&old, &new)), "no changes to the public API\n");
+}
+
+#[test]
+fn struct_fields() {
+    let old = krate(&["s"], vec![struct_("s", &["a"], false, &[]), field("a", "a")]);
+    let new = krate(
+        &["s"],
+        vec![struct_("s", &["a", "b"], false, &[]), field("a", "a"), field("b", "b")],
+    );
+    assert_eq!(
+        messages(&old, &new),
+        ["major: added field `krate::S::b` to a struct without private fields"]
+    );
+
+    let old = krate(&["s"], vec![struct_("s", &["a"], true, &[]), field("a", "a")]);
+    let new =
+        krate(&["s"], vec![struct_("s", &["a", "b"], true, &[]), field("a", "a"), field("b", "b")]);
+    assert_eq!(messages(&old, &new), ["minor: added field `krate::S::b`"]);
+}
+
+#[test]
+fn fields_of_new_structs_are_not_listed() {
+    let old = krate(&[], Vec::new());
+    let new = krate(&["s"], vec![struct_("s", &["a"], false, &[]), field("a", "a")]);
+    assert_eq!(messages(&old, &new), ["minor: added struct `krate::S`"]);
+}
+
+#[test]
+fn trait_items() {
+    let trait_ = |items: &[&str]| {
+        let trait_ = Trait {
+            is_auto: false,
+            is_unsafe: false,
+            is_object_safe: true,
+            items: ids(items),
+            generics: generics(),
+            bounds: Vec::new(),
+            implementations: Vec::new(),
+        };
+        item("t", "T", ItemEnum::Trait(trait_))
+    };
+    let old = krate(&["t"], vec![trait_(&["a"]), method("a", "a", false)]);
+    let new = krate(
+        &["t"],
+        vec![
+            trait_(&["a", "b", "c"]),
+            method("a", "a", true),
+            method("b", "b", false),
+            method("c", "c", true),
+        ],
+    );
+    assert_eq!(
+        messages(&old, &new),
+        [
+            "major: added method `krate::T::b` to a trait",
+            "minor: `krate::T::a` now has a default",
+            "minor: added method `krate::T::c`",
+        ]
+    );
+}
+
+#[test]
+fn trait_impls_and_auto_traits() {
+    let old = krate(
+        &["s"],
+        vec![
+            struct_("s", &[], true, &["send", "sync", "clone"]),
+            trait_impl("send", "Send", true),
+            trait_impl("sync", "Sync", true),
+            trait_impl("clone", "Clone", false),
+        ],
+    );
+    let new = krate(
+        &["s"],
+        vec![
+            struct_("s", &[], true, &["send", "debug"]),
+            trait_impl("send", "Send", true),
+            trait_impl("debug", "Debug", false),
+        ],
+    );
+    assert_eq!(
+        messages(&old, &new),
+        [
+            "major: `krate::S` no longer implements `Sync`",
+            "major: removed `impl Clone for krate::S`",
+            "minor: added `impl Debug for krate::S`",
+        ]
+    );
+    assert!(render(&diff(&old, &new))
+        .ends_with("\n2 major and 1 minor changes, the new version needs a major version bump\n"));
+}
+
+#[test]
+fn enum_variants() {
+    let enum_ = |variants: &[&str], attrs: &[&str]| {
+        let enum_ = Enum {
+            generics: generics(),
+            variants_stripped: false,
+            variants: ids(variants),
+            impls: Vec::new(),
+        };
+        Item {
+            attrs: attrs.iter().map(|attr| attr.to_string()).collect(),
+            ..item("e", "E", ItemEnum::Enum(enum_))
+        }
+    };
+    let variant = |name: &str| Item {
+        visibility: Visibility::Default,
+        ..item(name, name, ItemEnum::Variant(Variant::Plain))
+    };
+
+    let old = krate(&["e"], vec![enum_(&["A"], &[]), variant("A")]);
+    let new = krate(&["e"], vec![enum_(&["A", "B"], &[]), variant("A"), variant("B")]);
+    assert_eq!(messages(&old, &new), ["major: added variant `krate::E::B` to an exhaustive enum"]);
+
+    let non_exhaustive = &["#[non_exhaustive]"];
+    let old = krate(&["e"], vec![enum_(&["A"], non_exhaustive), variant("A")]);
+    let new = krate(&["e"], vec![enum_(&["A", "B"], non_exhaustive), variant("A"), variant("B")]);
+    assert_eq!(messages(&old, &new), ["minor: added variant `krate::E::B`"]);
+}
@@ -5,6 +5,7 @@
 //! docs for usage and details.
 
 mod conversions;
+pub(crate) mod diff;
 
 use std::cell::RefCell;
 use std::fs::{create_dir_all, File};
@@ -26,7 +27,7 @@
 use crate::error::Error;
 use crate::formats::cache::Cache;
 use crate::formats::FormatRenderer;
-use crate::json::conversions::{from_item_id, from_item_id_with_name, IntoWithTcx};
+use crate::json::conversions::{from_item_id, from_item_id_with_name, from_trait, IntoWithTcx};
 use crate::{clean, try_err};
 
 #[derive(Clone)]
@@ -126,12 +127,17 @@ fn get_trait_items(&mut self) -> Vec<(types::Id, types::Item)> {
                                 .last()
                                 .map(|s| s.to_string()),
                             visibility: types::Visibility::Public,
-                            inner: types::ItemEnum::Trait(trait_item.clone().into_tcx(self.tcx)),
+                            inner: types::ItemEnum::Trait(from_trait(
+                                trait_item.clone(),
+                                id,
+                                self.tcx,
+                            )),
                             span: None,
                             docs: Default::default(),
                             links: Default::default(),
                             attrs: Default::default(),
                             deprecation: Default::default(),
+                            cfg: None,
                         },
                     ))
                 } else {
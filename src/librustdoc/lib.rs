@@ -119,7 +119,9 @@ macro_rules! map {
 pub mod html;
 mod json;
 pub(crate) mod lint;
+mod man;
 mod markdown;
+mod markdown_render;
 mod passes;
 mod scrape_examples;
 mod theme;
@@ -522,6 +524,30 @@ fn opts() -> Vec<RustcOptGroup> {
                 "calculate percentage of public items with documentation",
             )
         }),
+        unstable("coverage-min-docs", |o| {
+            o.optopt(
+                "",
+                "coverage-min-docs",
+                "with --show-coverage, fail if less than this percentage of items is documented",
+                "PERCENTAGE",
+            )
+        }),
+        unstable("coverage-min-examples", |o| {
+            o.optopt(
+                "",
+                "coverage-min-examples",
+                "with --show-coverage, fail if less than this percentage of items has examples",
+                "PERCENTAGE",
+            )
+        }),
+        unstable("api-diff", |o| {
+            o.optopt(
+                "",
+                "api-diff",
+                "compare the public API of the JSON output given as input with an older one",
+                "OLD.json",
+            )
+        }),
         unstable("enable-per-target-ignores", |o| {
             o.optflagmulti(
                 "",
@@ -556,6 +582,14 @@ fn opts() -> Vec<RustcOptGroup> {
                 "Generate JSON file at the top level instead of generating HTML redirection files",
             )
         }),
+        unstable("bundle", |o| {
+            o.optopt(
+                "",
+                "bundle",
+                "Also pack the whole output directory into a zip archive, to browse it offline",
+                "PATH.zip",
+            )
+        }),
         unstable("emit", |o| {
             o.optmulti(
                 "",
@@ -567,6 +601,13 @@ fn opts() -> Vec<RustcOptGroup> {
         unstable("no-run", |o| {
             o.optflagmulti("", "no-run", "Compile doctests without running them")
         }),
+        unstable("merge-doctests", |o| {
+            o.optflagmulti(
+                "",
+                "merge-doctests",
+                "Compile the doctests which allow it into a single binary per edition",
+            )
+        }),
         unstable("show-type-layout", |o| {
             o.optflagmulti("", "show-type-layout", "Include the memory layout of types in the docs")
         }),
@@ -580,6 +621,14 @@ fn opts() -> Vec<RustcOptGroup> {
                 "Make the identifiers in the HTML source code pages navigable",
             )
         }),
+        unstable("show-call-graph", |o| {
+            o.optflag(
+                "",
+                "show-call-graph",
+                "Show the callers and callees of functions in the docs, and write the call graph \
+                of the crate to `call-graph.json`",
+            )
+        }),
         unstable("scrape-examples-output-path", |o| {
             o.optopt(
                 "",
@@ -737,6 +786,10 @@ fn run_renderer<'tcx, T: formats::FormatRenderer<'tcx>>(
 fn main_options(options: config::Options) -> MainResult {
     let diag = core::new_handler(options.error_format, None, &options.debugging_opts);
 
+    if let Some(old) = &options.api_diff {
+        return wrap_return(&diag, json::diff::run(old, &options.input));
+    }
+
     match (options.should_test, options.markdown_input()) {
         (true, true) => return wrap_return(&diag, markdown::test(options)),
         (true, false) => return doctest::run(options),
@@ -752,6 +805,7 @@ fn main_options(options: config::Options) -> MainResult {
     // need to move these items separately because we lose them by the time the closure is called,
     // but we can't create the Handler ahead of time because it's not Send
     let show_coverage = options.show_coverage;
+    let coverage_thresholds = options.coverage_thresholds;
     let run_check = options.run_check;
 
     // First, parse the crate and extract all relevant information.
@@ -813,6 +867,7 @@ fn main_options(options: config::Options) -> MainResult {
                         resolver,
                         resolver_caches,
                         show_coverage,
+                        coverage_thresholds,
                         render_options,
                         output_format,
                     )
@@ -842,6 +897,17 @@ fn main_options(options: config::Options) -> MainResult {
                     config::OutputFormat::Json => sess.time("render_json", || {
                         run_renderer::<json::JsonRenderer<'_>>(krate, render_opts, cache, tcx)
                     }),
+                    config::OutputFormat::Markdown => sess.time("render_markdown", || {
+                        run_renderer::<markdown_render::MarkdownRenderer<'_>>(
+                            krate,
+                            render_opts,
+                            cache,
+                            tcx,
+                        )
+                    }),
+                    config::OutputFormat::Man => sess.time("render_man", || {
+                        run_renderer::<man::ManRenderer<'_>>(krate, render_opts, cache, tcx)
+                    }),
                 }
             })
         })
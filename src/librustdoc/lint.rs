@@ -169,6 +169,18 @@ macro_rules! declare_rustdoc_lint {
    "codeblock could not be parsed as valid Rust or is empty"
 }
 
+declare_rustdoc_lint! {
+    /// The `unlinked_code_paths` lint detects paths in inline code which are
+    /// not intra-doc links, and which either don't resolve or could be turned
+    /// into intra-doc links. This is a `rustdoc` only lint, see the
+    /// documentation in the [rustdoc book].
+    ///
+    /// [rustdoc book]: ../../../rustdoc/lints.html#unlinked_code_paths
+    UNLINKED_CODE_PATHS,
+    Allow,
+    "detects paths in inline code that are not intra-doc links"
+}
+
 pub(crate) static RUSTDOC_LINTS: Lazy<Vec<&'static Lint>> = Lazy::new(|| {
     vec![
         BROKEN_INTRA_DOC_LINKS,
@@ -180,6 +192,7 @@ macro_rules! declare_rustdoc_lint {
         INVALID_HTML_TAGS,
         BARE_URLS,
         MISSING_CRATE_LEVEL_DOCS,
+        UNLINKED_CODE_PATHS,
     ]
 });
 
@@ -0,0 +1,407 @@
+//! Rustdoc's man page backend
+//!
+//! This writes a page in section 3 of the manual for each module and item, named after the path of
+//! the item, in the `man3` directory of the output. Once the output directory is in the `MANPATH`,
+//! `man krate::Foo` shows the documentation of `krate::Foo`. Primitive types and keywords are
+//! documented as `krate::primitive::u8` and `krate::keyword::fn`.
+
+mod troff;
+
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::symbol::Symbol;
+
+use crate::clean;
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::error::Error;
+use crate::formats::cache::Cache;
+use crate::formats::item_type::ItemType;
+use crate::formats::signature::Signatures;
+use crate::formats::FormatRenderer;
+use crate::html::markdown::plain_text_summary;
+use crate::html::render::{item_ty_to_section, ItemSection};
+use crate::try_err;
+
+use self::troff::escape;
+
+#[derive(Clone)]
+pub(crate) struct ManRenderer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The `man3` directory of the output, which holds all the pages.
+    dst: PathBuf,
+    /// The path of the current module.
+    current: Vec<Symbol>,
+    /// Whether the current module is stripped, in which case neither it nor its items get a
+    /// page.
+    stripped: bool,
+    cache: Rc<Cache>,
+}
+
+impl<'tcx> ManRenderer<'tcx> {
+    /// The path of `item`, from which its page is named.
+    fn path(&self, item: &clean::Item) -> String {
+        let mut path = self.current.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        match *item.kind {
+            clean::ModuleItem(_) => {}
+            clean::PrimitiveItem(_) | clean::KeywordItem(_) => {
+                path.truncate(1);
+                path.push(item.type_().to_string());
+                path.push(item.name.unwrap().to_string());
+            }
+            _ => path.push(item.name.unwrap().to_string()),
+        }
+        path.join("::")
+    }
+
+    fn write(&self, item: &clean::Item) -> Result<(), Error> {
+        let path = self.path(item);
+        let mut page = Page { cx: self, signatures: Signatures::new(self.tcx), out: String::new() };
+        page.item(&path, item);
+        let file = self.dst.join(format!("{}.3", page_name(&path)));
+        try_err!(fs::write(&file, page.out), &file);
+        Ok(())
+    }
+}
+
+impl<'tcx> FormatRenderer<'tcx> for ManRenderer<'tcx> {
+    fn descr() -> &'static str {
+        "man"
+    }
+
+    const RUN_ON_MODULE: bool = true;
+
+    fn init(
+        krate: clean::Crate,
+        options: RenderOptions,
+        cache: Cache,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<(Self, clean::Crate), Error> {
+        debug!("Initializing man renderer");
+        let dst = options.output.join("man3");
+        try_err!(fs::create_dir_all(&dst), &dst);
+        Ok((
+            ManRenderer { tcx, dst, current: Vec::new(), stripped: false, cache: Rc::new(cache) },
+            krate,
+        ))
+    }
+
+    fn make_child_renderer(&self) -> Self {
+        self.clone()
+    }
+
+    fn item(&mut self, item: clean::Item) -> Result<(), Error> {
+        if self.stripped || item.is_stripped() {
+            return Ok(());
+        }
+        self.write(&item)
+    }
+
+    fn mod_item_in(&mut self, item: &clean::Item) -> Result<(), Error> {
+        self.current.push(item.name.unwrap());
+        self.stripped |= item.is_stripped();
+        if self.stripped {
+            return Ok(());
+        }
+        self.write(item)
+    }
+
+    fn mod_item_out(&mut self) -> Result<(), Error> {
+        self.current.pop();
+        Ok(())
+    }
+
+    fn after_krate(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
+
+/// A man page, being written.
+struct Page<'a, 'tcx> {
+    cx: &'a ManRenderer<'tcx>,
+    signatures: Signatures<'tcx>,
+    out: String,
+}
+
+impl Page<'_, '_> {
+    fn item(&mut self, path: &str, item: &clean::Item) {
+        let cx = self.cx;
+        let krate = cx.current[0];
+        let source = match cx.cache.crate_version {
+            Some(ref version) => format!("{} {}", krate, version),
+            None => krate.to_string(),
+        };
+        let _ = writeln!(
+            self.out,
+            ".TH {} 3 \"\" {} {}",
+            quote(&page_name(path)),
+            quote(&source),
+            quote(&format!("{} documentation", krate)),
+        );
+
+        // The NAME section is what `whatis` and `apropos` search, so it always has a description.
+        let summary = item.doc_value().map(|docs| plain_text_summary(&docs)).unwrap_or_default();
+        let summary = if summary.is_empty() { item.type_().to_string() } else { summary };
+        let _ =
+            writeln!(self.out, ".SH NAME\n{} \\- {}", escape(path, false), escape(&summary, false));
+
+        if let Some(signature) = self.signatures.item(item) {
+            let _ = writeln!(self.out, ".SH SYNOPSIS\n.nf\n{}\n.fi", escape(&signature, true));
+        }
+
+        let deprecation = item.deprecation(cx.tcx);
+        let docs = item.collapsed_doc_value().filter(|docs| !docs.trim().is_empty());
+        if deprecation.is_some() || docs.is_some() {
+            self.out.push_str(".SH DESCRIPTION\n");
+        }
+        if let Some(depr) = deprecation {
+            let mut note = "Deprecated".to_owned();
+            if let Some(since) = depr.since {
+                let _ = write!(note, " since {}", since);
+            }
+            if let Some(reason) = depr.note {
+                let _ = write!(note, ": {}", reason);
+            }
+            let _ = writeln!(self.out, ".PP\n\\fB{}\\fP", escape(&note, false));
+        }
+        if docs.is_some() {
+            self.docs(item, true);
+        }
+
+        match *item.kind {
+            clean::ModuleItem(ref module) => self.module_items(path, &module.items),
+            clean::StructItem(clean::Struct { ref fields, .. })
+            | clean::UnionItem(clean::Union { ref fields, .. }) => {
+                self.fields(fields);
+                self.impls(item.item_id.expect_def_id());
+            }
+            clean::EnumItem(ref enum_) => {
+                self.variants(enum_);
+                self.impls(item.item_id.expect_def_id());
+            }
+            clean::TraitItem(ref trait_) => {
+                self.trait_items(trait_);
+                self.implementors(item.item_id.expect_def_id());
+            }
+            clean::TypedefItem(_) | clean::ForeignTypeItem | clean::PrimitiveItem(_) => {
+                self.impls(item.item_id.expect_def_id());
+            }
+            _ => {}
+        }
+
+        // Modules are documented under the path of their parent, and the other items under the
+        // path of the module they are in.
+        let parent =
+            if item.is_mod() { &cx.current[..cx.current.len() - 1] } else { &cx.current[..] };
+        if !parent.is_empty() {
+            let parent = parent.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+            let _ =
+                writeln!(self.out, ".SH SEE ALSO\n\\fB{}\\fP(3)", escape(&parent.join("."), false));
+        }
+    }
+
+    fn docs(&mut self, item: &clean::Item, subsections: bool) {
+        let Some(docs) = item.collapsed_doc_value() else { return };
+        let links = item.link_names(&self.cx.cache);
+        self.out.push_str(&troff::from_markdown(&docs, &links, subsections));
+    }
+
+    /// Writes a subsection for a member of the item, with its documentation.
+    fn member(&mut self, signature: &str, item: &clean::Item) {
+        let _ = writeln!(self.out, ".SS {}", quote(signature));
+        self.docs(item, false);
+    }
+
+    /// Writes a list entry for an item of a module, or for an implementation.
+    fn entry(&mut self, tag: &str, summary: Option<String>) {
+        let _ = writeln!(self.out, ".TP\n{}", tag);
+        if let Some(summary) = summary.filter(|summary| !summary.is_empty()) {
+            let _ = writeln!(self.out, "{}", escape(&summary, true));
+        }
+    }
+
+    fn module_items(&mut self, path: &str, items: &[clean::Item]) {
+        for &section in ItemSection::ALL {
+            let mut items = items
+                .iter()
+                .filter(|item| {
+                    !item.is_stripped()
+                        && item.type_() != ItemType::Impl
+                        && item_ty_to_section(item.type_()) == section
+                        && !matches!(
+                            *item.kind,
+                            clean::ImportItem(ref import) if !import.should_be_displayed
+                        )
+                })
+                .collect::<Vec<_>>();
+            if items.is_empty() {
+                continue;
+            }
+            // Re-exports are listed in the order they are written in.
+            if section != ItemSection::Reexports {
+                items.sort_by(|a, b| {
+                    a.name
+                        .as_ref()
+                        .map(|name| name.as_str())
+                        .cmp(&b.name.as_ref().map(|name| name.as_str()))
+                });
+            }
+            let _ = writeln!(self.out, ".SH {}", section.name().to_uppercase());
+            for item in items {
+                match *item.kind {
+                    clean::ExternCrateItem { .. } | clean::ImportItem(_) => {
+                        let code = self.signatures.item(item).unwrap_or_default();
+                        self.entry(&format!("\\fB{}\\fP", escape(&code, false)), None);
+                    }
+                    _ => {
+                        let name = item.name.unwrap();
+                        let page = match *item.kind {
+                            clean::PrimitiveItem(_) | clean::KeywordItem(_) => {
+                                format!("{}.{}.{}", self.cx.current[0], item.type_(), name)
+                            }
+                            _ => format!("{}.{}", page_name(path), name),
+                        };
+                        let summary = item.doc_value().map(|docs| plain_text_summary(&docs));
+                        self.entry(&format!("\\fB{}\\fP(3)", escape(&page, false)), summary);
+                    }
+                }
+            }
+        }
+    }
+
+    fn fields(&mut self, fields: &[clean::Item]) {
+        let fields = fields.iter().filter(|field| !field.is_stripped()).collect::<Vec<_>>();
+        if fields.is_empty() {
+            return;
+        }
+        self.out.push_str(".SH FIELDS\n");
+        for field in fields {
+            let signature =
+                format!("{}: {}", field.name.unwrap(), self.signatures.field_type(field));
+            self.member(&signature, field);
+        }
+    }
+
+    fn variants(&mut self, enum_: &clean::Enum) {
+        let variants = enum_.variants().collect::<Vec<_>>();
+        if variants.is_empty() {
+            return;
+        }
+        self.out.push_str(".SH VARIANTS\n");
+        for variant in variants {
+            let signature = self.signatures.variant(variant);
+            self.member(&signature, variant);
+            if let clean::VariantItem(clean::Variant::Struct(ref struct_)) = *variant.kind {
+                for field in struct_.fields.iter().filter(|field| !field.is_stripped()) {
+                    let signature =
+                        format!("{}: {}", field.name.unwrap(), self.signatures.field_type(field));
+                    let summary = field.doc_value().map(|docs| plain_text_summary(&docs));
+                    self.entry(&format!("\\fB{}\\fP", escape(&signature, false)), summary);
+                }
+            }
+        }
+    }
+
+    fn trait_items(&mut self, trait_: &clean::Trait) {
+        let sections: [(&str, ItemType); 4] = [
+            ("ASSOCIATED TYPES", ItemType::AssocType),
+            ("ASSOCIATED CONSTANTS", ItemType::AssocConst),
+            ("REQUIRED METHODS", ItemType::TyMethod),
+            ("PROVIDED METHODS", ItemType::Method),
+        ];
+        for (title, ty) in sections {
+            let items = trait_
+                .items
+                .iter()
+                .filter(|item| !item.is_stripped() && item.type_() == ty)
+                .collect::<Vec<_>>();
+            if items.is_empty() {
+                continue;
+            }
+            let _ = writeln!(self.out, ".SH {}", title);
+            for item in items {
+                if let Some(signature) = self.signatures.assoc_item(item) {
+                    self.member(&signature, item);
+                }
+            }
+        }
+    }
+
+    fn implementors(&mut self, did: DefId) {
+        let Some(implementors) = self.cx.cache.implementors.get(&did) else { return };
+        let mut headers = implementors
+            .iter()
+            .filter(|implementor| !implementor.inner_impl().kind.is_auto())
+            .map(|implementor| self.signatures.impl_header(implementor.inner_impl()))
+            .collect::<Vec<_>>();
+        headers.sort();
+        self.impl_list("IMPLEMENTORS", headers);
+    }
+
+    fn impls(&mut self, did: DefId) {
+        let cx = self.cx;
+        let Some(impls) = cx.cache.impls.get(&did) else { return };
+        let impls = impls.iter().filter(|i| !i.impl_item.is_stripped()).collect::<Vec<_>>();
+
+        let inherent = impls.iter().filter(|i| i.inner_impl().trait_.is_none());
+        let methods = inherent
+            .flat_map(|i| i.inner_impl().items.iter())
+            .filter(|item| !item.is_stripped())
+            .collect::<Vec<_>>();
+        if !methods.is_empty() {
+            self.out.push_str(".SH METHODS\n");
+            for item in methods {
+                if let Some(signature) = self.signatures.assoc_item(item) {
+                    self.member(&signature, item);
+                }
+            }
+        }
+
+        let sections: [(&str, fn(&clean::Impl) -> bool); 3] = [
+            ("TRAIT IMPLEMENTATIONS", |i| {
+                i.trait_.is_some() && !i.kind.is_auto() && !i.kind.is_blanket()
+            }),
+            ("AUTO TRAIT IMPLEMENTATIONS", |i| i.kind.is_auto()),
+            ("BLANKET IMPLEMENTATIONS", |i| i.kind.is_blanket()),
+        ];
+        for (title, in_section) in sections {
+            let mut headers = impls
+                .iter()
+                .filter(|i| in_section(i.inner_impl()))
+                .map(|i| self.signatures.impl_header(i.inner_impl()))
+                .collect::<Vec<_>>();
+            headers.sort();
+            self.impl_list(title, headers);
+        }
+    }
+
+    fn impl_list(&mut self, title: &str, headers: Vec<String>) {
+        if headers.is_empty() {
+            return;
+        }
+        let _ = writeln!(self.out, ".SH {}", title);
+        for header in headers {
+            let _ = writeln!(self.out, ".IP \\(bu 2\n{}", escape(&header, true));
+        }
+    }
+}
+
+/// The name of the page of the item at `path`, with the `::` of Rust paths replaced by `.`, as
+/// `::` has no place in a file name.
+fn page_name(path: &str) -> String {
+    path.replace("::", ".")
+}
+
+/// Quotes `arg` as an argument of a request.
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", escape(arg, false).replace('"', "\\(dq"))
+}
@@ -0,0 +1,184 @@
+//! Rendering Markdown documentation as the `man` macros of troff.
+
+use pulldown_cmark::{BrokenLink, CodeBlockKind, Event, Parser, Tag};
+
+use crate::clean::types::RenderedLink;
+use crate::html::markdown::{main_body_opts, map_line, ErrorCodes, LangString};
+
+#[cfg(test)]
+mod tests;
+
+/// Escapes `text` so that troff prints it as is, as part of a line which starts with `line_start`.
+pub(crate) fn escape(text: &str, mut line_start: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        // Lines starting with `.` or `'` are requests, not text.
+        if line_start && (c == '.' || c == '\'') {
+            out.push_str("\\&");
+        }
+        match c {
+            '\\' => out.push_str("\\e"),
+            '-' => out.push_str("\\-"),
+            _ => out.push(c),
+        }
+        line_start = c == '\n';
+    }
+    out
+}
+
+/// Renders the documentation `md` as the body of a section of a man page. Headings are rendered
+/// as subsections if `subsections` is true, and as bold paragraphs otherwise.
+///
+/// `links` are the intra-doc links of the documentation, which are rendered as their text.
+pub(crate) fn from_markdown(md: &str, links: &[RenderedLink], subsections: bool) -> String {
+    let mut replacer = |broken_link: BrokenLink<'_>| {
+        links
+            .iter()
+            .find(|link| link.original_text.as_str() == &*broken_link.reference)
+            .map(|link| (link.original_text.as_str().into(), "".into()))
+    };
+    let parser = Parser::new_with_broken_link_callback(md, main_body_opts(), Some(&mut replacer));
+
+    let mut w = Writer { out: String::new(), lists: Vec::new(), item_start: false };
+    // The intra-doc link being visited, and the URL of the other links.
+    let mut link: Option<&RenderedLink> = None;
+    let mut url = None;
+    let mut code_block: Option<(bool, String)> = None;
+    for event in parser {
+        match event {
+            Event::Start(Tag::Paragraph) => w.paragraph(),
+            Event::End(Tag::Paragraph) => {}
+            Event::Start(Tag::Heading(..)) => w.request(if subsections { ".SS " } else { ".B " }),
+            Event::End(Tag::Heading(..)) => w.end_line(),
+            Event::Start(Tag::BlockQuote) => w.request(".RS 4\n"),
+            Event::End(Tag::BlockQuote) => w.request(".RE\n"),
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let rust = match kind {
+                    CodeBlockKind::Fenced(lang) => {
+                        LangString::parse_without_check(&lang, ErrorCodes::No, false).rust
+                    }
+                    CodeBlockKind::Indented => true,
+                };
+                code_block = Some((rust, String::new()));
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                let Some((rust, code)) = code_block.take() else { continue };
+                w.paragraph();
+                w.request(".RS 4\n.nf\n");
+                for line in code.lines() {
+                    let line = if rust { map_line(line).for_html() } else { Some(line.into()) };
+                    if let Some(line) = line {
+                        w.out.push_str(&escape(&line, true));
+                        w.out.push('\n');
+                    }
+                }
+                w.request(".fi\n.RE\n");
+            }
+            Event::Start(Tag::List(start)) => {
+                if !w.lists.is_empty() {
+                    w.request(".RS 4\n");
+                }
+                w.lists.push(start);
+            }
+            Event::End(Tag::List(_)) => {
+                w.lists.pop();
+                if !w.lists.is_empty() {
+                    w.request(".RE\n");
+                }
+            }
+            Event::Start(Tag::Item) => {
+                let request = match w.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!(".IP {}. 4\n", *number - 1)
+                    }
+                    _ => ".IP \\(bu 2\n".to_owned(),
+                };
+                w.request(&request);
+                w.item_start = true;
+            }
+            Event::End(Tag::Item) => w.end_line(),
+            Event::Start(Tag::Emphasis) => w.out.push_str("\\fI"),
+            Event::Start(Tag::Strong) => w.out.push_str("\\fB"),
+            Event::End(Tag::Emphasis | Tag::Strong) => w.out.push_str("\\fP"),
+            Event::Start(Tag::Link(_, dest, _)) => {
+                link = links.iter().find(|link| link.original_text == *dest);
+                if link.is_none() && dest.contains("://") {
+                    url = Some(dest);
+                }
+            }
+            Event::End(Tag::Link(..)) => {
+                link = None;
+                if let Some(url) = url.take() {
+                    w.text(&format!(" <{}>", url));
+                }
+            }
+            Event::Text(text) => match code_block {
+                Some((_, ref mut code)) => code.push_str(&text),
+                None => match link {
+                    Some(link) if link.original_text == *text => w.text(&link.new_text),
+                    _ => w.text(&text),
+                },
+            },
+            Event::Code(code) => {
+                let code = match link {
+                    Some(link) if link.original_text == format!("`{}`", code) => {
+                        link.new_text.as_str()
+                    }
+                    _ => &*code,
+                };
+                w.out.push_str("\\fB");
+                w.text(code);
+                w.out.push_str("\\fP");
+            }
+            Event::SoftBreak => w.out.push('\n'),
+            Event::HardBreak => w.request(".br\n"),
+            Event::Rule => w.paragraph(),
+            Event::TaskListMarker(checked) => w.text(if checked { "[x] " } else { "[ ] " }),
+            Event::Start(Tag::TableRow | Tag::TableHead) => w.request(".br\n"),
+            Event::End(Tag::TableCell) => w.text(" "),
+            _ => {}
+        }
+    }
+    w.end_line();
+    w.out
+}
+
+struct Writer {
+    out: String,
+    /// The lists being visited, with the number of their next item if they are ordered.
+    lists: Vec<Option<u64>>,
+    /// Whether nothing was written since the start of the current list item.
+    item_start: bool,
+}
+
+impl Writer {
+    fn end_line(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn request(&mut self, request: &str) {
+        self.end_line();
+        self.out.push_str(request);
+        self.item_start = false;
+    }
+
+    /// Starts a paragraph, unless it is the first one of a list item, which starts with it.
+    fn paragraph(&mut self) {
+        if self.item_start {
+            self.item_start = false;
+        } else if self.lists.is_empty() {
+            self.request(".PP\n");
+        } else {
+            self.request(".IP \"\" 4\n");
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let line_start = self.out.is_empty() || self.out.ends_with('\n');
+        self.out.push_str(&escape(text, line_start));
+        self.item_start = false;
+    }
+}
@@ -0,0 +1,56 @@
+use super::{escape, from_markdown};
+use crate::clean::types::RenderedLink;
+
+#[test]
+fn escapes() {
+    assert_eq!(escape("a-b \\ c", true), "a\\-b \\e c");
+    assert_eq!(escape(".x\n'y", true), "\\&.x\n\\&'y");
+    assert_eq!(escape(".x", false), ".x");
+}
+
+#[test]
+fn paragraphs_and_inline_formatting() {
+    assert_eq!(
+        from_markdown("Some *emphasis*\nand **strong** `code`.\n\nSecond.", &[], true),
+        ".PP\nSome \\fIemphasis\\fP\nand \\fBstrong\\fP \\fBcode\\fP.\n.PP\nSecond.\n",
+    );
+}
+
+#[test]
+fn headings() {
+    assert_eq!(from_markdown("# Examples\n\nText", &[], true), ".SS Examples\n.PP\nText\n");
+    assert_eq!(from_markdown("# Examples", &[], false), ".B Examples\n");
+}
+
+#[test]
+fn code_blocks() {
+    assert_eq!(
+        from_markdown("```\n# fn main() {\n.let x = 1;\n# }\n```", &[], true),
+        ".PP\n.RS 4\n.nf\n\\&.let x = 1;\n.fi\n.RE\n",
+    );
+    assert_eq!(
+        from_markdown("```text\n# shown\n```", &[], true),
+        ".PP\n.RS 4\n.nf\n# shown\n.fi\n.RE\n",
+    );
+}
+
+#[test]
+fn lists() {
+    assert_eq!(
+        from_markdown("- a\n- b\n  1. c\n  2. d", &[], true),
+        ".IP \\(bu 2\na\n.IP \\(bu 2\nb\n.RS 4\n.IP 1. 4\nc\n.IP 2. 4\nd\n.RE\n",
+    );
+}
+
+#[test]
+fn links() {
+    let links = [RenderedLink {
+        original_text: "`Vec`".to_owned(),
+        new_text: "Vec".to_owned(),
+        href: String::new(),
+    }];
+    assert_eq!(
+        from_markdown("A [`Vec`] and [a site](https://example.com).", &links, true),
+        ".PP\nA \\fBVec\\fP and a site <https://example.com>.\n",
+    );
+}
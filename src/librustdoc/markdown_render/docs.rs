@@ -0,0 +1,166 @@
+//! Rewriting the documentation of an item for the page of the item in the Markdown output.
+//!
+//! Unlike the HTML backend, this doesn't render the documentation: the source is kept as written,
+//! and only the parts that don't make sense outside of rustdoc are replaced in place.
+
+use std::ops::Range;
+
+use pulldown_cmark::{BrokenLink, CodeBlockKind, Event, LinkType, Parser, Tag};
+
+use crate::clean::types::RenderedLink;
+use crate::html::markdown::{main_body_opts, map_line, ErrorCodes, LangString};
+
+#[cfg(test)]
+mod tests;
+
+/// Rewrites the documentation `md` to be included in a Markdown page:
+///
+/// * intra-doc links are replaced by the `links` to the pages of the items they resolved to,
+/// * headings are moved `heading_offset` levels down, to be nested in the sections of the page,
+/// * Rust code blocks are tagged as `rust` and their hidden lines are removed.
+pub(crate) fn rewrite(md: &str, links: &[RenderedLink], heading_offset: usize) -> String {
+    let mut replacer = |broken_link: BrokenLink<'_>| {
+        links
+            .iter()
+            .find(|link| link.original_text.as_str() == &*broken_link.reference)
+            .map(|link| (link.original_text.as_str().into(), "".into()))
+    };
+    let mut events =
+        Parser::new_with_broken_link_callback(md, main_body_opts(), Some(&mut replacer))
+            .into_offset_iter();
+
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    // The start of the heading being visited, and the end of its text.
+    let mut heading: Option<(Range<usize>, usize)> = None;
+    while let Some((event, range)) = events.next() {
+        match event {
+            Event::Start(Tag::Link(link_type, dest, _)) => {
+                // Links can't be nested, so their text ends with the last event before their end.
+                let mut text_end = range.start + 1;
+                for (event, inner) in &mut events {
+                    if let Event::End(Tag::Link(..)) = event {
+                        break;
+                    }
+                    text_end = text_end.max(inner.end);
+                }
+                if let Some(heading) = &mut heading {
+                    heading.1 = range.end;
+                }
+                let Some(link) = links.iter().find(|link| link.original_text == *dest) else {
+                    continue;
+                };
+                let text = &md[range.start + 1..text_end];
+                let text = match link_type {
+                    LinkType::Shortcut
+                    | LinkType::ShortcutUnknown
+                    | LinkType::Collapsed
+                    | LinkType::CollapsedUnknown
+                        if text == link.original_text =>
+                    {
+                        if text.starts_with('`') {
+                            format!("`{}`", link.new_text)
+                        } else {
+                            link.new_text.clone()
+                        }
+                    }
+                    _ => text.to_owned(),
+                };
+                edits.push((range, format!("[{}]({})", text, link.href)));
+            }
+            Event::Start(Tag::Heading(..)) => heading = Some((range, 0)),
+            Event::End(Tag::Heading(level, ..)) => {
+                let Some((range, text_end)) = heading.take() else { continue };
+                let level = level as usize;
+                let new_level = (level + heading_offset).min(6);
+                let source = &md[range.clone()];
+                let start = range.start + source.len() - source.trim_start().len();
+                if source.trim_start().starts_with('#') {
+                    edits.push((start..start, "#".repeat(new_level - level)));
+                } else {
+                    // A setext heading, underlined with `=` or `-`: turn it into an ATX heading,
+                    // which can have any level.
+                    edits.push((start..start, format!("{} ", "#".repeat(new_level))));
+                    let text_end = text_end.max(start);
+                    let underline_end =
+                        if source.ends_with('\n') { range.end - 1 } else { range.end };
+                    if let Some(newline) = md[text_end..underline_end].find('\n') {
+                        edits.push((text_end + newline..underline_end, String::new()));
+                    }
+                }
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let mut code = String::new();
+                for (event, _) in &mut events {
+                    match event {
+                        Event::Text(text) => code.push_str(&text),
+                        Event::End(Tag::CodeBlock(_)) => break,
+                        _ => {}
+                    }
+                }
+                if let CodeBlockKind::Fenced(lang) = &kind {
+                    if !LangString::parse_without_check(lang, ErrorCodes::No, false).rust {
+                        continue;
+                    }
+                }
+                // The lines of code blocks in block quotes and list items need the same prefix
+                // as the first one, with list markers replaced by their indentation.
+                let line_start = md[..range.start].rfind('\n').map_or(0, |i| i + 1);
+                let mut prefix = &md[line_start..range.start];
+                let mut range = range;
+                if let CodeBlockKind::Indented = kind {
+                    // The fenced block replacing it must not be indented like the code.
+                    prefix = prefix
+                        .strip_suffix("    ")
+                        .or_else(|| prefix.strip_suffix('\t'))
+                        .unwrap_or(prefix);
+                    range.start = line_start + prefix.len();
+                }
+                let prefix: String = prefix
+                    .chars()
+                    .map(|c| if c == '>' || c.is_whitespace() { c } else { ' ' })
+                    .collect();
+                let prefix = prefix.as_str();
+                let fence = "`".repeat(3.max(longest_backtick_run(&code) + 1));
+                let mut block = format!("{}rust\n", fence);
+                for line in code.lines().filter_map(|line| map_line(line).for_html()) {
+                    if !line.is_empty() {
+                        block.push_str(prefix);
+                    }
+                    block.push_str(&line);
+                    block.push('\n');
+                }
+                block.push_str(prefix);
+                block.push_str(&fence);
+                if md[range.clone()].ends_with('\n') {
+                    block.push('\n');
+                }
+                edits.push((range, block));
+            }
+            Event::Text(_) | Event::Code(_) | Event::Html(_) => {
+                if let Some(heading) = &mut heading {
+                    heading.1 = range.end;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    edits.sort_by_key(|(range, _)| (range.start, range.end));
+    let mut out = String::with_capacity(md.len());
+    let mut last = 0;
+    for (range, replacement) in edits {
+        // Replacements in parts of the source which were already replaced are dropped.
+        if range.start < last {
+            continue;
+        }
+        out.push_str(&md[last..range.start]);
+        out.push_str(&replacement);
+        last = range.end;
+    }
+    out.push_str(&md[last..]);
+    out
+}
+
+fn longest_backtick_run(code: &str) -> usize {
+    code.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
@@ -0,0 +1,57 @@
+use super::rewrite;
+use crate::clean::types::RenderedLink;
+
+fn link(original_text: &str, new_text: &str, href: &str) -> RenderedLink {
+    RenderedLink {
+        original_text: original_text.to_owned(),
+        new_text: new_text.to_owned(),
+        href: href.to_owned(),
+    }
+}
+
+#[test]
+fn intra_doc_links() {
+    let links = [
+        link("`Vec`", "Vec", "../alloc/vec/struct.Vec.md"),
+        link("Option::map", "Option::map", "enum.Option.md#method.map"),
+        link("fn@f", "f", "fn.f.md"),
+    ];
+    assert_eq!(
+        rewrite("See [`Vec`], [the method](Option::map) and [fn@f].\n", &links, 0),
+        "See [`Vec`](../alloc/vec/struct.Vec.md), [the method](enum.Option.md#method.map) and \
+         [f](fn.f.md).\n",
+    );
+    assert_eq!(
+        rewrite("A [reference][r] and [unknown].\n\n[r]: Option::map\n", &links, 0),
+        "A [reference](enum.Option.md#method.map) and [unknown].\n\n[r]: Option::map\n",
+    );
+}
+
+#[test]
+fn headings() {
+    assert_eq!(rewrite("# Examples\n\ntext\n", &[], 1), "## Examples\n\ntext\n");
+    assert_eq!(rewrite("##### Deep\n", &[], 3), "###### Deep\n");
+    assert_eq!(rewrite("Title\n=====\n\ntext\n", &[], 2), "### Title\n\ntext\n");
+}
+
+#[test]
+fn code_blocks() {
+    assert_eq!(
+        rewrite("```\n# fn main() {\nlet x = 1;\n# }\n```\n", &[], 0),
+        "```rust\nlet x = 1;\n```\n",
+    );
+    assert_eq!(rewrite("```should_panic\npanic!();\n```\n", &[], 0), "```rust\npanic!();\n```\n",);
+    assert_eq!(rewrite("```text\n# not hidden\n```\n", &[], 0), "```text\n# not hidden\n```\n");
+    assert_eq!(
+        rewrite("- item\n\n  ```\n  # use x;\n  y();\n  ```\n", &[], 0),
+        "- item\n\n  ```rust\n  y();\n  ```\n",
+    );
+    assert_eq!(
+        rewrite("> ```\n> # hidden\n> shown\n> ```\n", &[], 0),
+        "> ```rust\n> shown\n> ```\n",
+    );
+    assert_eq!(
+        rewrite("text\n\n    let x = 1;\n    ## x\n", &[], 0),
+        "text\n\n```rust\nlet x = 1;\n# x\n```\n",
+    );
+}
@@ -0,0 +1,528 @@
+//! Rustdoc's Markdown backend
+//!
+//! This writes one Markdown page per module and per item, laid out like the HTML output, for
+//! wikis and static site generators which build their pages from Markdown. Signatures are
+//! printed as Rust code blocks, and the links between items, including intra-doc links, point to
+//! the relative paths of the `.md` pages of their targets.
+
+mod docs;
+
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::hygiene::MacroKind;
+use rustc_span::symbol::Symbol;
+
+use crate::clean;
+use crate::clean::types::{ExternalLocation, ItemLink, RenderedLink};
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::error::Error;
+use crate::formats::cache::Cache;
+use crate::formats::item_type::ItemType;
+use crate::formats::signature::Signatures;
+use crate::formats::{self, FormatRenderer};
+use crate::html::format::href_relative_parts;
+use crate::html::markdown::plain_text_summary;
+use crate::html::render::{item_ty_to_section, ItemSection};
+use crate::try_err;
+
+#[derive(Clone)]
+pub(crate) struct MarkdownRenderer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The directory of the current module, which holds the pages of its items.
+    dst: PathBuf,
+    /// The path of the current module.
+    current: Vec<Symbol>,
+    /// Whether the current module is stripped, in which case neither it nor its items get a
+    /// page. The HTML backend writes redirections for them instead.
+    stripped: bool,
+    cache: Rc<Cache>,
+}
+
+impl<'tcx> MarkdownRenderer<'tcx> {
+    fn write(&self, file_name: &str, page: String) -> Result<(), Error> {
+        try_err!(fs::create_dir_all(&self.dst), &self.dst);
+        let path = self.dst.join(file_name);
+        try_err!(fs::write(&path, page), &path);
+        Ok(())
+    }
+
+    /// The relative path from the current module to the page of `did`, or the URL of its HTML
+    /// documentation if it comes from another crate documented online.
+    ///
+    /// This follows [`crate::html::format::href`].
+    fn href(&self, did: DefId) -> Option<String> {
+        let tcx = self.tcx;
+        let cache = &self.cache;
+        let did = match tcx.def_kind(did) {
+            DefKind::AssocTy | DefKind::AssocFn | DefKind::AssocConst | DefKind::Variant => {
+                // documented on their parent's page
+                tcx.parent(did)
+            }
+            _ => did,
+        };
+        if !did.is_local()
+            && !cache.access_levels.is_public(did)
+            && !cache.document_private
+            && !cache.primitive_locations.values().any(|&id| id == did)
+        {
+            return None;
+        }
+
+        let (fqp, shortty, remote) = match cache.paths.get(&did) {
+            Some(&(ref fqp, shortty)) => (fqp, shortty, None),
+            None => {
+                let &(ref fqp, shortty) = cache.external_paths.get(&did)?;
+                match cache.extern_locations[&did.krate] {
+                    ExternalLocation::Remote(ref s) => {
+                        (fqp, shortty, Some(s.trim_end_matches('/')))
+                    }
+                    ExternalLocation::Local => (fqp, shortty, None),
+                    ExternalLocation::Unknown => return None,
+                }
+            }
+        };
+        let module_fqp = if shortty == ItemType::Module { &fqp[..] } else { &fqp[..fqp.len() - 1] };
+        let mut parts: Vec<String> = match remote {
+            Some(root) => {
+                let mut parts = vec![root.to_owned()];
+                parts.extend(module_fqp.iter().map(|name| name.to_string()));
+                parts
+            }
+            None => href_relative_parts(module_fqp, &self.current)
+                .map(|name| name.to_string())
+                .collect(),
+        };
+        let extension = if remote.is_some() { "html" } else { "md" };
+        parts.push(match shortty {
+            ItemType::Module => format!("index.{}", extension),
+            _ => format!("{}.{}.{}", shortty, fqp.last().unwrap(), extension),
+        });
+        Some(parts.join("/"))
+    }
+
+    /// The intra-doc links of `item`, with their targets resolved to pages of the output.
+    fn links(&self, item: &clean::Item) -> Vec<RenderedLink> {
+        self.cache
+            .intra_doc_links
+            .get(&item.item_id)
+            .map_or(&[][..], |v| v.as_slice())
+            .iter()
+            .filter_map(|ItemLink { link, link_text, did, ref fragment }| {
+                let mut href = self.href(*did)?;
+                if let Some(ref fragment) = *fragment {
+                    fragment.render(&mut href, self.tcx);
+                }
+                Some(RenderedLink {
+                    original_text: link.clone(),
+                    new_text: link_text.clone(),
+                    href,
+                })
+            })
+            .collect()
+    }
+
+    fn page(&self, item: &clean::Item) -> String {
+        let mut page = Page { cx: self, signatures: Signatures::new(self.tcx), out: String::new() };
+        page.item(item);
+        page.out
+    }
+}
+
+impl<'tcx> FormatRenderer<'tcx> for MarkdownRenderer<'tcx> {
+    fn descr() -> &'static str {
+        "markdown"
+    }
+
+    const RUN_ON_MODULE: bool = true;
+
+    fn init(
+        krate: clean::Crate,
+        options: RenderOptions,
+        cache: Cache,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<(Self, clean::Crate), Error> {
+        debug!("Initializing markdown renderer");
+        Ok((
+            MarkdownRenderer {
+                tcx,
+                dst: options.output,
+                current: Vec::new(),
+                stripped: false,
+                cache: Rc::new(cache),
+            },
+            krate,
+        ))
+    }
+
+    fn make_child_renderer(&self) -> Self {
+        self.clone()
+    }
+
+    fn item(&mut self, item: clean::Item) -> Result<(), Error> {
+        if self.stripped || item.is_stripped() {
+            return Ok(());
+        }
+        let file_name = format!("{}.{}.md", item.type_(), item.name.unwrap());
+        self.write(&file_name, self.page(&item))
+    }
+
+    fn mod_item_in(&mut self, item: &clean::Item) -> Result<(), Error> {
+        let name = item.name.unwrap();
+        self.dst.push(name.as_str());
+        self.current.push(name);
+        self.stripped |= item.is_stripped();
+        if self.stripped {
+            return Ok(());
+        }
+        self.write("index.md", self.page(item))
+    }
+
+    fn mod_item_out(&mut self) -> Result<(), Error> {
+        self.dst.pop();
+        self.current.pop();
+        Ok(())
+    }
+
+    fn after_krate(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
+
+/// The Markdown page of an item, being written.
+struct Page<'a, 'tcx> {
+    cx: &'a MarkdownRenderer<'tcx>,
+    signatures: Signatures<'tcx>,
+    out: String,
+}
+
+impl Page<'_, '_> {
+    fn item(&mut self, item: &clean::Item) {
+        let tcx = self.cx.tcx;
+        let name = item.name.unwrap();
+        match *item.kind {
+            clean::PrimitiveItem(_) | clean::KeywordItem(_) => {
+                let _ = writeln!(self.out, "# {} `{}`\n", title(item), name);
+            }
+            _ => {
+                let mut path =
+                    self.cx.current.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+                if !item.is_mod() {
+                    path.push(name.to_string());
+                }
+                let _ = writeln!(self.out, "# {} `{}`\n", title(item), path.join("::"));
+            }
+        }
+        if let Some(depr) = item.deprecation(tcx) {
+            self.out.push_str("> **Deprecated**");
+            if let Some(since) = depr.since {
+                let _ = write!(self.out, " since {}", since);
+            }
+            if let Some(note) = depr.note {
+                let _ = write!(self.out, ": {}", note);
+            }
+            self.out.push_str("\n\n");
+        }
+        if let Some(signature) = self.signatures.item(item) {
+            let _ = writeln!(self.out, "```rust\n{}\n```\n", signature);
+        }
+        self.docs(item, 1);
+
+        match *item.kind {
+            clean::ModuleItem(ref module) => self.module_items(&module.items),
+            clean::StructItem(clean::Struct { ref fields, .. })
+            | clean::UnionItem(clean::Union { ref fields, .. }) => {
+                self.fields(fields);
+                self.impls(item.item_id.expect_def_id());
+            }
+            clean::EnumItem(ref enum_) => {
+                self.variants(enum_);
+                self.impls(item.item_id.expect_def_id());
+            }
+            clean::TraitItem(ref trait_) => {
+                self.trait_items(trait_);
+                self.implementors(item.item_id.expect_def_id());
+            }
+            clean::TypedefItem(_) | clean::ForeignTypeItem | clean::PrimitiveItem(_) => {
+                self.impls(item.item_id.expect_def_id());
+            }
+            _ => {}
+        }
+    }
+
+    /// Writes the documentation of `item`, with its headings moved `heading_offset` levels down.
+    fn docs(&mut self, item: &clean::Item, heading_offset: usize) {
+        let Some(docs) = item.collapsed_doc_value() else { return };
+        let docs = docs::rewrite(&docs, &self.cx.links(item), heading_offset);
+        if !docs.trim().is_empty() {
+            self.out.push_str(docs.trim_end());
+            self.out.push_str("\n\n");
+        }
+    }
+
+    /// Writes a heading for a member of the item, with an anchor like the one of the HTML
+    /// output, and its documentation.
+    fn member(&mut self, level: usize, id: &str, signature: &str, item: &clean::Item) {
+        let _ =
+            writeln!(self.out, "<a id=\"{}\"></a>\n{} `{}`\n", id, "#".repeat(level), signature);
+        self.docs(item, level);
+    }
+
+    fn module_items(&mut self, items: &[clean::Item]) {
+        for &section in ItemSection::ALL {
+            let mut items = items
+                .iter()
+                .filter(|item| {
+                    !item.is_stripped()
+                        && item.type_() != ItemType::Impl
+                        && item_ty_to_section(item.type_()) == section
+                        && !matches!(
+                            *item.kind,
+                            clean::ImportItem(ref import) if !import.should_be_displayed
+                        )
+                })
+                .collect::<Vec<_>>();
+            if items.is_empty() {
+                continue;
+            }
+            // Re-exports are listed in the order they are written in.
+            if section != ItemSection::Reexports {
+                items.sort_by(|a, b| {
+                    a.name
+                        .as_ref()
+                        .map(|name| name.as_str())
+                        .cmp(&b.name.as_ref().map(|name| name.as_str()))
+                });
+            }
+            let _ = writeln!(self.out, "## {}\n", section.name());
+            for item in items {
+                self.module_item(item);
+            }
+            self.out.push('\n');
+        }
+    }
+
+    fn module_item(&mut self, item: &clean::Item) {
+        match *item.kind {
+            clean::ExternCrateItem { .. } => {
+                let _ = writeln!(self.out, "- `{}`", self.signatures.item(item).unwrap());
+            }
+            clean::ImportItem(ref import) => {
+                let code = self.signatures.item(item).unwrap();
+                let _ = match import.source.did.and_then(|did| self.cx.href(did)) {
+                    Some(href) => writeln!(self.out, "- [`{}`]({})", code, href),
+                    None => writeln!(self.out, "- `{}`", code),
+                };
+            }
+            _ => {
+                let name = item.name.unwrap();
+                let _ = match item.type_() {
+                    ItemType::Module => write!(self.out, "- [`{}`]({}/index.md)", name, name),
+                    ty => write!(self.out, "- [`{}`]({}.{}.md)", name, ty, name),
+                };
+                let summary = item.doc_value().map(|docs| plain_text_summary(&docs));
+                if let Some(summary) = summary.filter(|summary| !summary.is_empty()) {
+                    let _ = write!(self.out, ": {}", summary);
+                }
+                self.out.push('\n');
+            }
+        }
+    }
+
+    fn fields(&mut self, fields: &[clean::Item]) {
+        let fields = fields.iter().filter(|field| !field.is_stripped()).collect::<Vec<_>>();
+        if fields.is_empty() {
+            return;
+        }
+        self.out.push_str("## Fields\n\n");
+        for field in fields {
+            let name = field.name.unwrap();
+            let signature = format!("{}: {}", name, self.signatures.field_type(field));
+            self.member(3, &format!("structfield.{}", name), &signature, field);
+        }
+    }
+
+    fn variants(&mut self, enum_: &clean::Enum) {
+        let variants = enum_.variants().collect::<Vec<_>>();
+        if variants.is_empty() {
+            return;
+        }
+        self.out.push_str("## Variants\n\n");
+        for variant in variants {
+            let name = variant.name.unwrap();
+            let signature = self.signatures.variant(variant);
+            self.member(3, &format!("variant.{}", name), &signature, variant);
+            if let clean::VariantItem(clean::Variant::Struct(ref struct_)) = *variant.kind {
+                for field in struct_.fields.iter().filter(|field| !field.is_stripped()) {
+                    let field_name = field.name.unwrap();
+                    let signature =
+                        format!("{}: {}", field_name, self.signatures.field_type(field));
+                    let id = format!("variant.{}.field.{}", name, field_name);
+                    self.member(4, &id, &signature, field);
+                }
+            }
+        }
+    }
+
+    fn trait_items(&mut self, trait_: &clean::Trait) {
+        let sections: [(&str, fn(&clean::Item) -> bool); 4] = [
+            ("Associated Types", |item| item.type_() == ItemType::AssocType),
+            ("Associated Constants", |item| item.type_() == ItemType::AssocConst),
+            ("Required Methods", |item| item.type_() == ItemType::TyMethod),
+            ("Provided Methods", |item| item.type_() == ItemType::Method),
+        ];
+        for (title, in_section) in sections {
+            let items = trait_.items.iter().filter(|item| !item.is_stripped() && in_section(item));
+            let mut items = items.peekable();
+            if items.peek().is_none() {
+                continue;
+            }
+            let _ = writeln!(self.out, "## {}\n", title);
+            for item in items {
+                if let Some(signature) = self.signatures.assoc_item(item) {
+                    let id = format!("{}.{}", item.type_(), item.name.unwrap());
+                    self.member(3, &id, &signature, item);
+                }
+            }
+        }
+    }
+
+    fn implementors(&mut self, did: DefId) {
+        let cx = self.cx;
+        let Some(implementors) = cx.cache.implementors.get(&did) else { return };
+        let mut implementors = implementors
+            .iter()
+            .filter(|implementor| !implementor.inner_impl().kind.is_auto())
+            .map(|implementor| {
+                let impl_ = implementor.inner_impl();
+                let href = impl_.for_.def_id(&cx.cache).and_then(|did| cx.href(did));
+                (self.signatures.impl_header(impl_), href)
+            })
+            .collect::<Vec<_>>();
+        if implementors.is_empty() {
+            return;
+        }
+        implementors.sort();
+        self.out.push_str("## Implementors\n\n");
+        for (header, href) in implementors {
+            let _ = match href {
+                Some(href) => writeln!(self.out, "- [`{}`]({})", header, href),
+                None => writeln!(self.out, "- `{}`", header),
+            };
+        }
+        self.out.push('\n');
+    }
+
+    fn impls(&mut self, did: DefId) {
+        let cx = self.cx;
+        let Some(impls) = cx.cache.impls.get(&did) else { return };
+        let impls = impls.iter().filter(|i| !i.impl_item.is_stripped()).collect::<Vec<_>>();
+        let (inherent, trait_impls): (Vec<_>, Vec<_>) =
+            impls.into_iter().partition(|i| i.inner_impl().trait_.is_none());
+
+        if !inherent.is_empty() {
+            self.out.push_str("## Implementations\n\n");
+            for i in inherent {
+                let _ =
+                    writeln!(self.out, "### `{}`\n", self.signatures.impl_header(i.inner_impl()));
+                self.docs(&i.impl_item, 3);
+                self.impl_items(i.inner_impl());
+            }
+        }
+
+        let (synthetic, trait_impls): (Vec<_>, Vec<_>) =
+            trait_impls.into_iter().partition(|i| i.inner_impl().kind.is_auto());
+        let (blanket, trait_impls): (Vec<_>, Vec<_>) =
+            trait_impls.into_iter().partition(|i| i.inner_impl().kind.is_blanket());
+
+        let mut trait_impls = trait_impls
+            .into_iter()
+            .map(|i| (self.signatures.impl_header(i.inner_impl()), i))
+            .collect::<Vec<_>>();
+        trait_impls.sort_by(|(a, _), (b, _)| a.cmp(b));
+        if !trait_impls.is_empty() {
+            self.out.push_str("## Trait Implementations\n\n");
+            for (header, i) in trait_impls {
+                let _ = match i.trait_did().and_then(|did| cx.href(did)) {
+                    Some(href) => writeln!(self.out, "### [`{}`]({})\n", header, href),
+                    None => writeln!(self.out, "### `{}`\n", header),
+                };
+                self.docs(&i.impl_item, 3);
+                self.impl_items(i.inner_impl());
+            }
+        }
+
+        for (title, impls) in
+            [("Auto Trait Implementations", synthetic), ("Blanket Implementations", blanket)]
+        {
+            self.impl_list(title, &impls);
+        }
+    }
+
+    fn impl_items(&mut self, impl_: &clean::Impl) {
+        for item in impl_.items.iter().filter(|item| !item.is_stripped()) {
+            if let Some(signature) = self.signatures.assoc_item(item) {
+                let id = format!("{}.{}", item.type_(), item.name.unwrap());
+                self.member(4, &id, &signature, item);
+            }
+        }
+    }
+
+    /// Lists the headers of `impls`, linked to the pages of their traits.
+    fn impl_list(&mut self, title: &str, impls: &[&formats::Impl]) {
+        if impls.is_empty() {
+            return;
+        }
+        let mut impls = impls
+            .iter()
+            .map(|i| (self.signatures.impl_header(i.inner_impl()), i.trait_did()))
+            .collect::<Vec<_>>();
+        impls.sort();
+        let _ = writeln!(self.out, "## {}\n", title);
+        for (header, trait_did) in impls {
+            let _ = match trait_did.and_then(|did| self.cx.href(did)) {
+                Some(href) => writeln!(self.out, "- [`{}`]({})", header, href),
+                None => writeln!(self.out, "- `{}`", header),
+            };
+        }
+        self.out.push('\n');
+    }
+}
+
+/// The kind of item in the title of its page, as in the HTML output.
+fn title(item: &clean::Item) -> &'static str {
+    match *item.kind {
+        clean::ModuleItem(_) if item.is_crate() => "Crate",
+        clean::ModuleItem(_) => "Module",
+        clean::FunctionItem(_) | clean::ForeignFunctionItem(_) => "Function",
+        clean::TraitItem(_) => "Trait",
+        clean::StructItem(_) => "Struct",
+        clean::UnionItem(_) => "Union",
+        clean::EnumItem(_) => "Enum",
+        clean::TypedefItem(_) => "Type Definition",
+        clean::MacroItem(_) => "Macro",
+        clean::ProcMacroItem(ref macro_) => match macro_.kind {
+            MacroKind::Bang => "Macro",
+            MacroKind::Attr => "Attribute Macro",
+            MacroKind::Derive => "Derive Macro",
+        },
+        clean::PrimitiveItem(_) => "Primitive Type",
+        clean::StaticItem(_) | clean::ForeignStaticItem(_) => "Static",
+        clean::ConstantItem(_) => "Constant",
+        clean::ForeignTypeItem => "Foreign Type",
+        clean::KeywordItem(_) => "Keyword",
+        clean::OpaqueTyItem(_) => "Opaque Type",
+        clean::TraitAliasItem(_) => "Trait Alias",
+        _ => "Item",
+    }
+}
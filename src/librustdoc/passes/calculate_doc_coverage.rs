@@ -1,16 +1,19 @@
 //! Calculates information used for the --show-coverage flag.
 use crate::clean;
+use crate::config::CoverageThresholds;
 use crate::core::DocContext;
 use crate::html::markdown::{find_testable_code, ErrorCodes};
 use crate::passes::check_doc_test_visibility::{should_have_doc_example, Tests};
 use crate::passes::Pass;
 use crate::visit::DocVisitor;
+use rustc_ast as ast;
 use rustc_hir as hir;
 use rustc_lint::builtin::MISSING_DOCS;
 use rustc_middle::lint::LintLevelSource;
 use rustc_middle::ty::DefIdTree;
 use rustc_session::lint;
-use rustc_span::FileName;
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::{FileName, Span};
 use serde::Serialize;
 
 use std::collections::BTreeMap;
@@ -23,10 +26,23 @@
 };
 
 fn calculate_doc_coverage(krate: clean::Crate, ctx: &mut DocContext<'_>) -> clean::Crate {
-    let mut calc = CoverageCalculator { items: Default::default(), ctx };
+    let mut calc = CoverageCalculator {
+        items: Default::default(),
+        scopes: Vec::new(),
+        scope_stack: Vec::new(),
+        path: Vec::new(),
+        ctx,
+    };
     calc.visit_crate(&krate);
+    for coverage in calc.items.values_mut() {
+        coverage.sort_missing_items();
+    }
+    for scope in &mut calc.scopes {
+        scope.coverage.sort_missing_items();
+    }
 
     calc.print_results();
+    calc.check_thresholds();
 
     krate
 }
@@ -79,6 +95,70 @@ fn examples_percentage(&self) -> Option<f64> {
     }
 }
 
+/// An item which should have documentation or a code example, but doesn't.
+#[derive(Clone, Serialize, Debug)]
+struct MissingItem {
+    path: String,
+    kind: &'static str,
+    line: usize,
+    column: usize,
+    #[serde(skip)]
+    span: Span,
+}
+
+/// The items counted in a file, or in a [`Scope`].
+#[derive(Default, Serialize, Debug)]
+struct Coverage {
+    #[serde(flatten)]
+    count: ItemCount,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing_docs: Vec<MissingItem>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing_examples: Vec<MissingItem>,
+}
+
+impl Coverage {
+    fn count_item(
+        &mut self,
+        item: &MissingItem,
+        has_docs: bool,
+        has_doc_example: bool,
+        should_have_doc_examples: bool,
+        should_have_docs: bool,
+    ) {
+        self.count.count_item(
+            has_docs,
+            has_doc_example,
+            should_have_doc_examples,
+            should_have_docs,
+        );
+        if should_have_docs && !has_docs {
+            self.missing_docs.push(item.clone());
+        }
+        if should_have_doc_examples && !has_doc_example {
+            self.missing_examples.push(item.clone());
+        }
+    }
+
+    /// Sorts the missing items in source order: modules are visited before the other items of
+    /// their parent.
+    fn sort_missing_items(&mut self) {
+        self.missing_docs.sort_by_key(|item| (item.line, item.column));
+        self.missing_examples.sort_by_key(|item| (item.line, item.column));
+    }
+}
+
+/// A module whose items are checked against the minimum coverage: the crate root, and the
+/// modules with a `#[doc(coverage(...))]` attribute. The items of a submodule with its own
+/// attribute only count towards the coverage of the submodule.
+struct Scope {
+    path: String,
+    thresholds: CoverageThresholds,
+    /// The span of the `#[doc(coverage(...))]` attribute of the module, if it has one.
+    attr_span: Option<Span>,
+    coverage: Coverage,
+}
+
 impl ops::Sub for ItemCount {
     type Output = Self;
 
@@ -102,10 +182,35 @@ fn add_assign(&mut self, rhs: Self) {
 }
 
 struct CoverageCalculator<'a, 'b> {
-    items: BTreeMap<FileName, ItemCount>,
+    items: BTreeMap<FileName, Coverage>,
+    scopes: Vec<Scope>,
+    /// The indices in `scopes` of the scopes of the modules being visited.
+    scope_stack: Vec<usize>,
+    /// The path of the item being visited.
+    path: Vec<Symbol>,
     ctx: &'a mut DocContext<'b>,
 }
 
+/// The minimum coverage set by the `#[doc(coverage(...))]` attribute of `item`, and the span of
+/// the attribute.
+fn coverage_attribute(item: &clean::Item) -> Option<(CoverageThresholds, Span)> {
+    let attr = item.attrs.lists(sym::doc).find(|attr| attr.has_name(sym::coverage))?;
+    let mut thresholds = CoverageThresholds::default();
+    for meta in attr.meta_item_list().unwrap_or_default() {
+        // The attribute was validated by rustc already.
+        let percentage = match meta.meta_item().and_then(|meta| meta.name_value_literal()) {
+            Some(ast::Lit { kind: ast::LitKind::Int(percentage, _), .. }) => *percentage as f64,
+            _ => continue,
+        };
+        match meta.name_or_empty() {
+            sym::docs => thresholds.docs = Some(percentage),
+            sym::examples => thresholds.examples = Some(percentage),
+            _ => {}
+        }
+    }
+    Some((thresholds, attr.span()))
+}
+
 fn limit_filename_len(filename: String) -> String {
     let nb_chars = filename.chars().count();
     if nb_chars > 35 {
@@ -123,7 +228,7 @@ fn to_json(&self) -> String {
                 .items
                 .iter()
                 .map(|(k, v)| (k.prefer_local().to_string(), v))
-                .collect::<BTreeMap<String, &ItemCount>>(),
+                .collect::<BTreeMap<String, &Coverage>>(),
         )
         .expect("failed to convert JSON data to string")
     }
@@ -159,7 +264,8 @@ fn print_table_record(
         );
         print_table_line();
 
-        for (file, &count) in &self.items {
+        for (file, coverage) in &self.items {
+            let count = coverage.count;
             if let Some(percentage) = count.percentage() {
                 print_table_record(
                     &limit_filename_len(file.prefer_local().to_string_lossy().into()),
@@ -181,6 +287,79 @@ fn print_table_record(
         );
         print_table_line();
     }
+
+    /// Reports an error for each scope whose coverage is below its minimum, which makes rustdoc
+    /// exit with a failure.
+    fn check_thresholds(&self) {
+        for scope in &self.scopes {
+            let coverage = &scope.coverage;
+            let checks = [
+                (
+                    "documentation",
+                    coverage.count.percentage(),
+                    scope.thresholds.docs,
+                    &coverage.missing_docs,
+                    "is not documented",
+                ),
+                (
+                    "code example",
+                    coverage.count.examples_percentage(),
+                    scope.thresholds.examples,
+                    &coverage.missing_examples,
+                    "has no code example",
+                ),
+            ];
+            for (what, percentage, minimum, missing, note) in checks {
+                let (Some(percentage), Some(minimum)) = (percentage, minimum) else { continue };
+                if percentage >= minimum {
+                    continue;
+                }
+                let msg = format!(
+                    "{} coverage of `{}` is {:.1}%, below the minimum of {}%",
+                    what, scope.path, percentage, minimum
+                );
+                let mut err = match scope.attr_span {
+                    Some(span) => self.ctx.sess().struct_span_err(span, &msg),
+                    None => self.ctx.sess().struct_err(&msg),
+                };
+                for item in missing {
+                    err.span_note(item.span, &format!("`{}` {}", item.path, note));
+                }
+                err.emit();
+            }
+        }
+    }
+
+    /// Starts a new scope for `module` if it is the crate root or has a `#[doc(coverage(...))]`
+    /// attribute, and returns whether it did.
+    fn enter_module(&mut self, module: &clean::Item) -> bool {
+        let (thresholds, attr_span) = match (coverage_attribute(module), self.scope_stack.last()) {
+            (Some((thresholds, span)), Some(&parent)) => {
+                (thresholds.or(self.scopes[parent].thresholds), Some(span))
+            }
+            (Some((thresholds, span)), None) => {
+                (thresholds.or(self.ctx.coverage_thresholds), Some(span))
+            }
+            (None, Some(_)) => return false,
+            (None, None) => (self.ctx.coverage_thresholds, None),
+        };
+        self.scope_stack.push(self.scopes.len());
+        self.scopes.push(Scope {
+            path: self.item_path(module),
+            thresholds,
+            attr_span,
+            coverage: Coverage::default(),
+        });
+        true
+    }
+
+    fn item_path(&self, item: &clean::Item) -> String {
+        let mut path = self.path.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        if let Some(name) = item.name {
+            path.push(name.to_string());
+        }
+        path.join("::")
+    }
 }
 
 impl<'a, 'b> DocVisitor for CoverageCalculator<'a, 'b> {
@@ -191,6 +370,8 @@ fn visit_item(&mut self, i: &clean::Item) {
             return;
         }
 
+        let new_scope = i.is_mod() && !i.is_stripped() && self.enter_module(i);
+
         match *i.kind {
             clean::StrippedItem(..) => {
                 // don't count items in stripped modules
@@ -215,7 +396,8 @@ fn visit_item(&mut self, i: &clean::Item) {
                     None,
                 );
 
-                let filename = i.span(self.ctx.tcx).filename(self.ctx.sess());
+                let span = i.span(self.ctx.tcx);
+                let filename = span.filename(self.ctx.sess());
                 let has_doc_example = tests.found_tests != 0;
                 // The `expect_def_id()` should be okay because `local_def_id_to_hir_id`
                 // would presumably panic if a fake `DefIndex` were passed.
@@ -261,16 +443,51 @@ fn visit_item(&mut self, i: &clean::Item) {
                 let should_have_docs = !should_be_ignored
                     && (level != lint::Level::Allow || matches!(source, LintLevelSource::Default));
 
+                let should_have_doc_example = should_have_doc_example(self.ctx, i);
+                let loc = span.lo(self.ctx.sess());
+                let item = MissingItem {
+                    path: self.item_path(i),
+                    kind: i.type_().as_str(),
+                    line: loc.line,
+                    column: loc.col.to_usize() + 1,
+                    span: span.inner(),
+                };
+
                 debug!("counting {:?} {:?} in {:?}", i.type_(), i.name, filename);
                 self.items.entry(filename).or_default().count_item(
+                    &item,
+                    has_docs,
+                    has_doc_example,
+                    should_have_doc_example,
+                    should_have_docs,
+                );
+                let scope = *self.scope_stack.last().unwrap();
+                self.scopes[scope].coverage.count_item(
+                    &item,
                     has_docs,
                     has_doc_example,
-                    should_have_doc_example(self.ctx, i),
+                    should_have_doc_example,
                     should_have_docs,
                 );
             }
         }
 
-        self.visit_item_recur(i)
+        // Items of inherent impls are named after their type.
+        let name = match *i.kind {
+            clean::ImplItem(ref impl_) => {
+                impl_.for_.def_id(&self.ctx.cache).and_then(|did| self.ctx.tcx.opt_item_name(did))
+            }
+            _ => i.name,
+        };
+        if let Some(name) = name {
+            self.path.push(name);
+        }
+        self.visit_item_recur(i);
+        if name.is_some() {
+            self.path.pop();
+        }
+        if new_scope {
+            self.scope_stack.pop();
+        }
     }
 }
@@ -31,6 +31,7 @@
 use crate::passes::Pass;
 use crate::visit::DocVisitor;
 
+mod code_paths;
 mod early;
 pub(crate) use early::early_resolve_intra_doc_links;
 
@@ -842,13 +843,19 @@ fn visit_item(&mut self, item: &Item) {
         // Rather than merging all documentation into one, resolve it one attribute at a time
         // so we know which module it came from.
         for (parent_module, doc) in item.attrs.prepare_to_doc_link_resolution() {
+            // NOTE: if there are links that start in one crate and end in another, this will not resolve them.
+            // This is a degenerate case and it's not supported by rustdoc.
+            let parent_node = parent_module.or(parent_node);
+            let base_node =
+                if item.is_mod() && inner_docs { self.mod_ids.last().copied() } else { parent_node };
+            if let Some(module_id) = base_node {
+                self.check_code_paths(item, &doc, module_id);
+            }
+
             if !may_have_doc_links(&doc) {
                 continue;
             }
             debug!("combined_docs={}", doc);
-            // NOTE: if there are links that start in one crate and end in another, this will not resolve them.
-            // This is a degenerate case and it's not supported by rustdoc.
-            let parent_node = parent_module.or(parent_node);
             let mut tmp_links = self
                 .cx
                 .resolver_caches
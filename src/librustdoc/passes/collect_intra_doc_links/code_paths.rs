@@ -0,0 +1,172 @@
+//! Detects paths written in inline code (like `` `Foo::bar` ``) instead of intra-doc links.
+//!
+//! Such paths are not checked by rustdoc, so they silently go stale when the item they refer to
+//! is renamed or removed. This resolves them with the same machinery as intra-doc links and
+//! either reports them as unresolved, or suggests turning them into intra-doc links.
+
+use pulldown_cmark::{BrokenLink, CowStr, Event, Parser, Tag};
+use rustc_errors::Applicability;
+use rustc_hir::def::Namespace::{self, *};
+use rustc_hir::def_id::DefId;
+use rustc_resolve::ParentScope;
+use rustc_session::lint::Level;
+use rustc_span::{BytePos, SyntaxContext};
+
+use std::ops::Range;
+
+use super::{report_diagnostic, DiagnosticInfo, LinkCollector};
+use crate::clean::Item;
+use crate::core::DocContext;
+use crate::html::markdown::main_body_opts;
+use crate::lint::UNLINKED_CODE_PATHS;
+
+/// A path found in inline code, with the namespaces it can be resolved in.
+struct CodePath {
+    path_str: String,
+    namespaces: &'static [Namespace],
+    /// The range of the inline code in the markdown, including the backticks.
+    range: Range<usize>,
+}
+
+impl LinkCollector<'_, '_> {
+    /// Reports the paths in the inline code of `dox` which aren't intra-doc links.
+    pub(super) fn check_code_paths(&mut self, item: &Item, dox: &str, module_id: DefId) {
+        let Some(hir_id) = DocContext::as_local_hir_id(self.cx.tcx, item.item_id)
+        else {
+            // If non-local, no need to check anything.
+            return;
+        };
+        // Resolving paths is expensive, don't do it for nothing.
+        if self.cx.tcx.lint_level_at_node(UNLINKED_CODE_PATHS, hir_id).0 == Level::Allow {
+            return;
+        }
+
+        let code_paths = code_paths(dox);
+        if code_paths.is_empty() {
+            return;
+        }
+        self.add_traits_in_scope(module_id);
+
+        for CodePath { path_str, namespaces, range } in code_paths {
+            let resolved = namespaces
+                .iter()
+                .any(|&ns| self.resolve(&path_str, ns, item.item_id, module_id).is_ok());
+            let diag_info = DiagnosticInfo { item, dox, ori_link: &path_str, link_range: range };
+            if resolved {
+                let msg = format!("`{}` could be an intra-doc link", path_str);
+                report_diagnostic(
+                    self.cx.tcx,
+                    UNLINKED_CODE_PATHS,
+                    &msg,
+                    &diag_info,
+                    |diag, sp| {
+                        if let Some(sp) = sp {
+                            // `sp` doesn't include the backticks.
+                            let lo = sp.with_lo(sp.lo() - BytePos(1)).shrink_to_lo();
+                            let hi = sp.with_hi(sp.hi() + BytePos(1)).shrink_to_hi();
+                            diag.multipart_suggestion(
+                                "convert it into an intra-doc link",
+                                vec![(lo, "[".to_owned()), (hi, "]".to_owned())],
+                                Applicability::MaybeIncorrect,
+                            );
+                        } else {
+                            diag.help(&format!(
+                                "convert it into an intra-doc link: [`{}`]",
+                                path_str
+                            ));
+                        }
+                    },
+                );
+            } else {
+                let msg = format!("unresolved path `{}` in inline code", path_str);
+                report_diagnostic(
+                    self.cx.tcx,
+                    UNLINKED_CODE_PATHS,
+                    &msg,
+                    &diag_info,
+                    |diag, sp| {
+                        if let Some(sp) = sp {
+                            diag.span_label(sp, "no item with this path is in scope");
+                        }
+                        diag.help("the item may have been renamed or removed");
+                    },
+                );
+            }
+        }
+    }
+
+    /// The traits in scope are computed ahead of time for the modules with intra-doc links only,
+    /// but resolving an associated item like `Foo::bar` needs them.
+    fn add_traits_in_scope(&mut self, module_id: DefId) {
+        if self.cx.resolver_caches.traits_in_scope.contains_key(&module_id) {
+            return;
+        }
+        let traits_in_scope = self.cx.enter_resolver(|resolver| {
+            let parent_scope = ParentScope::module(resolver.expect_module(module_id), resolver);
+            resolver.traits_in_scope(None, &parent_scope, SyntaxContext::root(), None)
+        });
+        self.cx.resolver_caches.traits_in_scope.insert(module_id, traits_in_scope);
+    }
+}
+
+/// Returns the paths written in inline code in `dox`, outside of links.
+fn code_paths(dox: &str) -> Vec<CodePath> {
+    // Broken links like `` [`Foo::bar`] `` are still intra-doc links, make them real links so
+    // that the inline code inside of them is skipped.
+    let mut replace_broken_link =
+        |_: BrokenLink<'_>| Some((CowStr::Borrowed(""), CowStr::Borrowed("")));
+    let parser = Parser::new_with_broken_link_callback(
+        dox,
+        main_body_opts(),
+        Some(&mut replace_broken_link),
+    );
+
+    let mut code_paths = Vec::new();
+    let mut in_link = 0;
+    for (event, range) in parser.into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link(..)) => in_link += 1,
+            Event::End(Tag::Link(..)) => in_link -= 1,
+            // Only look at inline code delimited by single backticks, so that the backticks can
+            // be left out of the diagnostics.
+            Event::Code(code)
+                if in_link == 0
+                    && dox[range.clone()].starts_with('`')
+                    && !dox[range.clone()].starts_with("``") =>
+            {
+                if let Some((path_str, namespaces)) = parse_code_path(&code) {
+                    code_paths.push(CodePath { path_str: path_str.to_owned(), namespaces, range });
+                }
+            }
+            _ => {}
+        }
+    }
+    code_paths
+}
+
+/// Returns the path in `code` and the namespaces it can be resolved in, if `code` looks like a
+/// path with at least two segments, like `Foo::bar`, `Foo::bar()` or `foo::bar!`.
+///
+/// Paths starting with a single letter segment, like `T::default()`, are likely to start with a
+/// generic parameter and are skipped.
+fn parse_code_path(code: &str) -> Option<(&str, &'static [Namespace])> {
+    let (path_str, namespaces): (_, &[_]) = if let Some(path) = code.strip_suffix("()") {
+        (path, &[ValueNS])
+    } else if let Some(path) = code.strip_suffix('!') {
+        (path, &[MacroNS])
+    } else {
+        (code, &[TypeNS, ValueNS, MacroNS])
+    };
+    let mut segments = path_str.split("::");
+    let first = segments.next()?;
+    if first.len() < 2 || !is_ident(first) || !path_str.contains("::") {
+        return None;
+    }
+    segments.all(is_ident).then(|| (path_str, namespaces))
+}
+
+fn is_ident(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    chars.next().map_or(false, |c| c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+}
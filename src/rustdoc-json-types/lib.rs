@@ -9,7 +9,7 @@
 use serde::{Deserialize, Serialize};
 
 /// rustdoc format-version.
-pub const FORMAT_VERSION: u32 = 15;
+pub const FORMAT_VERSION: u32 = 16;
 
 /// A `Crate` is the root of the emitted JSON blob. It contains all type/documentation information
 /// about the language items in the local crate, as well as info about external items to allow
@@ -79,6 +79,10 @@ pub struct Item {
     /// Stringified versions of the attributes on this item (e.g. `"#[inline]"`)
     pub attrs: Vec<String>,
     pub deprecation: Option<Deprecation>,
+    /// The configuration under which this item is available, from its `#[doc(cfg(...))]`
+    /// attribute and those of its parents. Absent if the item is always available, or if rustdoc
+    /// wasn't asked to compute it with `#![feature(doc_cfg)]`.
+    pub cfg: Option<Cfg>,
     #[serde(flatten)]
     pub inner: ItemEnum,
 }
@@ -99,6 +103,23 @@ pub struct Deprecation {
     pub note: Option<String>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Cfg {
+    /// Accepts all configurations.
+    True,
+    /// Denies all configurations.
+    False,
+    /// A configuration option, like `unix` (without a `value`) or `target_os = "linux"`.
+    Cfg { name: String, value: Option<String> },
+    /// `not(...)`
+    Not(Box<Cfg>),
+    /// `any(...)`
+    Any(Vec<Cfg>),
+    /// `all(...)`
+    All(Vec<Cfg>),
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum Visibility {
@@ -523,6 +544,8 @@ pub struct FnDecl {
 pub struct Trait {
     pub is_auto: bool,
     pub is_unsafe: bool,
+    /// Whether the trait can be used as `dyn Trait`.
+    pub is_object_safe: bool,
     pub items: Vec<Id>,
     pub generics: Generics,
     pub bounds: Vec<GenericBound>,
@@ -546,7 +569,12 @@ pub struct Impl {
     pub for_: Type,
     pub items: Vec<Id>,
     pub negative: bool,
+    /// Whether this is an implementation of an auto trait which rustdoc synthesized from the
+    /// fields of the type, like `impl<T: Send> Send for Foo<T>`. Negative ones are included.
     pub synthetic: bool,
+    /// For blanket implementations listed on the type `for_` they apply to, the type that they
+    /// are implemented for, like `T` in `impl<T: Display> ToString for T`. `generics` are those
+    /// of the blanket implementation.
     pub blanket_impl: Option<Type>,
 }
 
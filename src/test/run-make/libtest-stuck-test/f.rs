@@ -0,0 +1,14 @@
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn passes() {
+    assert_eq!(1 + 1, 2);
+}
+
+#[test]
+fn stuck() {
+    loop {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,6 @@
+// One warning and one error, so that rustc also summarizes the session with
+// "aborting due to previous error; 1 warning emitted".
+
+fn main() {
+    let _: u32 = ("not a number");
+}
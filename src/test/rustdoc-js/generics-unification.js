@@ -0,0 +1,72 @@
+// exact-check
+
+const QUERY = [
+    'Vec<T> -> Option<T>',
+    'Vec<T> -> Option<U>',
+    'impl Stream<Item = u8> -> String',
+    'Bytes -> String',
+    'LabelBuf -> usize',
+    '-> Label',
+    '-> "Label"',
+    'Stream<u8>',
+];
+
+const EXPECTED = [
+    {
+        // Vec<T> -> Option<T>
+        'others': [
+            { 'path': 'generics_unification', 'name': 'first' },
+        ],
+    },
+    {
+        // Vec<T> -> Option<U>
+        'others': [
+            { 'path': 'generics_unification', 'name': 'convert' },
+        ],
+    },
+    {
+        // impl Stream<Item = u8> -> String
+        'others': [
+            { 'path': 'generics_unification', 'name': 'concat' },
+        ],
+    },
+    {
+        // Bytes -> String
+        'others': [
+            { 'path': 'generics_unification', 'name': 'join' },
+            { 'path': 'generics_unification', 'name': 'concat' },
+        ],
+    },
+    {
+        // LabelBuf -> usize
+        'others': [
+            { 'path': 'generics_unification', 'name': 'label_len' },
+        ],
+    },
+    {
+        // -> Label
+        'others': [
+            { 'path': 'generics_unification::LabelBuf', 'name': 'deref' },
+            { 'path': 'generics_unification', 'name': 'new_label_buf' },
+            // Only through the Levenshtein distance of the names.
+            { 'path': 'generics_unification::other', 'name': 'new_label_buf' },
+        ],
+    },
+    {
+        // -> "Label"
+        'others': [
+            { 'path': 'generics_unification::LabelBuf', 'name': 'deref' },
+            { 'path': 'generics_unification', 'name': 'new_label_buf' },
+        ],
+    },
+    {
+        // Stream<u8>
+        'others': [
+            { 'path': 'generics_unification', 'name': 'Stream' },
+        ],
+        'in_args': [
+            { 'path': 'generics_unification', 'name': 'concat' },
+        ],
+        'returned': [],
+    },
+];
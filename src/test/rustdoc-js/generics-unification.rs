@@ -0,0 +1,59 @@
+use std::ops::Deref;
+
+pub trait Stream {
+    type Item;
+}
+
+pub struct Bytes;
+
+impl Stream for Bytes {
+    type Item = u8;
+}
+
+pub struct Label;
+
+pub struct LabelBuf;
+
+impl Deref for LabelBuf {
+    type Target = Label;
+
+    fn deref(&self) -> &Label {
+        &Label
+    }
+}
+
+pub fn first<T>(_: Vec<T>) -> Option<T> {
+    None
+}
+
+pub fn convert<T, U>(_: Vec<T>) -> Option<U> {
+    None
+}
+
+pub fn concat(_: impl Stream<Item = u8>) -> String {
+    String::new()
+}
+
+pub fn join<S>(_: S) -> String
+where
+    S: Stream<Item = String>,
+{
+    String::new()
+}
+
+pub fn label_len(_: &Label) -> usize {
+    0
+}
+
+pub fn new_label_buf() -> LabelBuf {
+    LabelBuf
+}
+
+pub mod other {
+    /// Shares its name with `crate::LabelBuf`, but doesn't dereference to `Label`.
+    pub struct LabelBuf;
+
+    pub fn new_label_buf() -> LabelBuf {
+        LabelBuf
+    }
+}
@@ -0,0 +1,15 @@
+#![feature(doc_cfg)]
+
+// @is doc_cfg.json "$.index[*][?(@.name=='always')].cfg" null
+pub fn always() {}
+
+// @is - "$.index[*][?(@.name=='unix_only')].cfg" '{"cfg": {"name": "unix", "value": null}}'
+#[doc(cfg(unix))]
+pub fn unix_only() {}
+
+#[doc(cfg(unix))]
+pub mod unix {
+    // @is - "$.index[*][?(@.name=='inherited')].cfg" '{"all": [{"cfg": {"name": "unix", "value": null}}, {"cfg": {"name": "feature", "value": "x"}}]}'
+    #[doc(cfg(feature = "x"))]
+    pub fn inherited() {}
+}
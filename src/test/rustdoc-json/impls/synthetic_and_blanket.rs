@@ -0,0 +1,16 @@
+// ignore-tidy-linelength
+
+// Like in the HTML output, the auto trait impls synthesized by rustdoc and the blanket impls which
+// apply to a type are listed with the impls of the type.
+
+pub trait Describe {}
+
+impl<T: Clone> Describe for T {}
+
+// @has synthetic_and_blanket.json "$.index[*].inner[?(@.synthetic==true)].for.inner.name" '"Wrapper"'
+// @has - "$.index[*].inner[?(@.synthetic==true)].trait.inner.name" '"Send"'
+// @has - "$.index[*].inner[?(@.blanket_impl.kind=='generic')].for.inner.name" '"Wrapper"'
+// @has - "$.index[*].inner[?(@.blanket_impl.kind=='generic')].trait.inner.name" '"Describe"'
+// @has - "$.index[*].inner[?(@.blanket_impl.kind=='generic')].blanket_impl.inner" '"T"'
+#[derive(Clone)]
+pub struct Wrapper<T>(T);
@@ -0,0 +1,9 @@
+// @is object_safety.json "$.index[*][?(@.name=='ObjectSafe')].inner.is_object_safe" true
+pub trait ObjectSafe {
+    fn method(&self);
+}
+
+// @is - "$.index[*][?(@.name=='NotObjectSafe')].inner.is_object_safe" false
+pub trait NotObjectSafe {
+    fn new() -> Self;
+}
@@ -0,0 +1,18 @@
+// compile-flags:-Z unstable-options --show-coverage --coverage-min-docs 50
+
+//! The crate root is documented.
+
+#![feature(doc_coverage)]
+
+/// Documented.
+pub struct Foo;
+
+pub mod strict {
+    #![doc(coverage(docs = 100))] //~ ERROR documentation coverage of `thresholds::strict`
+    //! Documented.
+
+    /// Documented.
+    pub fn documented() {}
+
+    pub fn undocumented() {}
+}
@@ -0,0 +1,36 @@
+// edition:2021
+// compile-flags:--test --test-args=--test-threads=1 -Zunstable-options --merge-doctests
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// check-pass
+
+// Merged doctests are reported like the ones compiled on their own.
+
+/// ```
+/// assert_eq!(doctest_merged::add(1, 1), 2);
+/// ```
+///
+/// ```
+/// fn main() {
+///     assert_eq!(doctest_merged::add(1, 2), 3);
+/// }
+/// ```
+///
+/// ```should_panic
+/// panic!("still in a process of its own");
+/// ```
+///
+/// ```
+/// #![allow(unused_mut)]
+/// let mut x = 1;
+/// assert_eq!(x, 1);
+/// ```
+///
+/// ```
+/// let x: u32 = "1".parse()?;
+/// assert_eq!(doctest_merged::add(x, 1), 2);
+/// Ok::<(), std::num::ParseIntError>(())
+/// ```
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
@@ -0,0 +1,14 @@
+#![deny(rustdoc::unlinked_code_paths)]
+
+/// Creates a new `Foo`, see `Foo::new`.
+//~^ ERROR `Foo::new` could be an intra-doc link
+///
+/// `Foo::old_name` was renamed, but `T::default()` and [`Foo::new`] are fine.
+//~^ ERROR unresolved path `Foo::old_name` in inline code
+pub struct Foo;
+
+impl Foo {
+    pub fn new() -> Self {
+        Foo
+    }
+}
@@ -0,0 +1,26 @@
+// compile-flags: -Z unstable-options --show-call-graph --document-private-items
+
+#![crate_name = "foo"]
+
+// @has foo/fn.helper.html
+// @has - '//*[@class="docblock call-graph"]//a[@href="fn.internal.html"]' 'foo::internal'
+// @has - '//*[@class="docblock call-graph"]//a[@href="struct.Foo.html#method.reset"]' \
+//      'foo::Foo::reset'
+pub fn helper() {}
+
+// @has foo/fn.internal.html
+// @has - '//*[@class="docblock call-graph"]//a[@href="fn.helper.html"]' 'foo::helper'
+fn internal() {
+    helper();
+}
+
+pub struct Foo;
+
+impl Foo {
+    fn reset(&self) {
+        helper();
+    }
+}
+
+// @has foo/call-graph.json 'foo::internal'
+// @has - 'foo::Foo::reset'
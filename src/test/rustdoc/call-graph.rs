@@ -0,0 +1,67 @@
+// compile-flags: -Z unstable-options --show-call-graph
+
+#![crate_name = "foo"]
+
+// @has foo/fn.helper.html
+// @has - '//*[@id="used-by"]' 'Used by'
+// @has - '//*[@class="docblock call-graph"]//a[@href="fn.run.html"]' 'foo::run'
+// @has - '//*[@class="docblock call-graph"]//a[@href="struct.Foo.html#method.start"]' \
+//      'foo::Foo::start'
+// @!has - '//*[@id="calls"]' 'Calls'
+// @!has - '//*[@class="docblock call-graph"]//code' 'foo::internal'
+// @!has - '//*[@class="docblock call-graph"]//code' 'foo::Foo::reset'
+pub fn helper() {}
+
+// @has foo/fn.run.html
+// @has - '//*[@id="calls"]' 'Calls'
+// @has - '//*[@class="docblock call-graph"]//a[@href="fn.helper.html"]' 'foo::helper'
+// @has - '//*[@class="docblock call-graph"]//a[@href="trait.Task.html#tymethod.poll"]' \
+//      'foo::Task::poll'
+// @!has - '//*[@id="used-by"]' 'Used by'
+pub fn run<T: Task>(task: &T) {
+    helper();
+    task.poll();
+}
+
+pub struct Foo;
+
+// @has foo/struct.Foo.html
+// @has - '//*[@class="docblock call-graph"]//a[@href="fn.helper.html"]' 'foo::helper'
+// @has - '//*[@class="docblock call-graph"]//a[@href="struct.Foo.html#method.start"]' \
+//      'foo::Foo::start'
+// @has - '//*[@class="docblock call-graph"]//a[@href="struct.Foo.html#method.restart"]' \
+//      'foo::Foo::restart'
+impl Foo {
+    pub fn start(&self) {
+        helper();
+    }
+
+    pub fn restart(&self) {
+        self.start();
+    }
+
+    fn reset(&self) {
+        helper();
+    }
+}
+
+// Private functions aren't documented, so they aren't part of the call graph either.
+// @has foo/fn.entry.html
+// @!has - '//*[@id="calls"]' 'Calls'
+pub fn entry() {
+    internal();
+}
+
+fn internal() {
+    helper();
+}
+
+// @has foo/trait.Task.html
+// @has - '//*[@class="docblock call-graph"]//a[@href="fn.run.html"]' 'foo::run'
+pub trait Task {
+    fn poll(&self);
+}
+
+// @has foo/call-graph.json '"foo::helper"'
+// @!has - 'foo::internal'
+// @!has - 'foo::Foo::reset'
@@ -0,0 +1,38 @@
+// compile-flags: -Z unstable-options --output-format man
+
+#![crate_name = "foo"]
+
+// @has man3/foo.3 '.TH "foo" 3 "" "foo" "foo documentation"'
+// @has - '.SH STRUCTS .TP \fBfoo.Foo\fP(3) A struct with a private field.'
+// @has - '.SH MODULES .TP \fBfoo.bar\fP(3) A module.'
+
+/// A struct with a private field.
+// @has man3/foo.Foo.3 '.TH "foo.Foo" 3 "" "foo" "foo documentation"'
+// @has - '.SH NAME foo::Foo \- A struct with a private field.'
+// @has - '.SH SYNOPSIS .nf pub struct Foo<T: Clone> { pub x: T, /* private fields */ } .fi'
+// @has - '.SH FIELDS .SS "x: T"'
+// @has - '.SH METHODS .SS "pub fn get(&self) \-> &T"'
+// @has - '.SH SEE ALSO \fBfoo\fP(3)'
+pub struct Foo<T: Clone> {
+    /// The public field.
+    pub x: T,
+    y: u8,
+}
+
+impl<T: Clone> Foo<T> {
+    /// Returns the public field.
+    pub fn get(&self) -> &T {
+        &self.x
+    }
+}
+
+/// A module.
+pub mod bar {
+    // @has man3/foo.bar.3 '.SH NAME foo::bar \- A module.'
+    // @has - '.SH FUNCTIONS .TP \fBfoo.bar.baz\fP(3)'
+    // @has - '.SH SEE ALSO \fBfoo\fP(3)'
+
+    // @has man3/foo.bar.baz.3 '.SH SYNOPSIS .nf pub fn baz() .fi'
+    // @has - '.SH SEE ALSO \fBfoo.bar\fP(3)'
+    pub fn baz() {}
+}
@@ -0,0 +1,52 @@
+// compile-flags: -Z unstable-options --output-format markdown
+
+#![crate_name = "foo"]
+
+// @has foo/index.md '# Crate `foo`'
+// @has - '## Structs - [`Foo`](struct.Foo.md): A struct with a private field.'
+// @has - '## Modules - [`bar`](bar/index.md): A module.'
+
+/// A struct with a private field.
+// @has foo/struct.Foo.md '# Struct `foo::Foo`'
+// @has - '```rust pub struct Foo<T: Clone> { pub x: T, /* private fields */ } ```'
+// @has - '## Fields <a id="structfield.x"></a> ### `x: T` The public field.'
+// @has - '## Implementations ### `impl<T: Clone> Foo<T>`'
+// @has - '<a id="method.get"></a> #### `pub fn get(&self) -> &T` Returns the public field.'
+pub struct Foo<T: Clone> {
+    /// The public field.
+    pub x: T,
+    y: u8,
+}
+
+impl<T: Clone> Foo<T> {
+    /// Returns the public field.
+    pub fn get(&self) -> &T {
+        &self.x
+    }
+}
+
+/// A module.
+pub mod bar {
+    use super::Foo;
+
+    // @has foo/bar/index.md '# Module `foo::bar`'
+    // @has - '- [`Baz`](trait.Baz.md)'
+
+    // @has foo/bar/trait.Baz.md '# Trait `foo::bar::Baz`'
+    // @has - '```rust pub trait Baz { fn baz<U>(&mut self, u: U) -> Option<U> where U: Copy; } ```'
+    // @has - '## Implementors - [`impl<T: Clone> Baz for Foo<T>`](../struct.Foo.md)'
+    pub trait Baz {
+        fn baz<U>(&mut self, u: U) -> Option<U>
+        where
+            U: Copy;
+    }
+
+    impl<T: Clone> Baz for Foo<T> {
+        fn baz<U>(&mut self, u: U) -> Option<U>
+        where
+            U: Copy,
+        {
+            Some(u)
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// run-pass
+// Test that users are able to use the stable mir APIs to retrieve information of the current crate
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_smir;
+
+use rustc_smir::mir::{Operand, StatementKind, TerminatorKind};
+use rustc_smir::ty::TyKind;
+use rustc_smir::{Context, ItemKind};
+use std::io::Write;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(cx: &Context<'_>) {
+    let local = cx.local_crate();
+    assert_eq!(&local.name, CRATE_NAME);
+    assert!(local.is_local);
+    assert!(cx.external_crates().iter().any(|krate| krate.name == "std" && !krate.is_local));
+
+    let items = cx.all_local_items();
+    let get_item = |name: &str| items.iter().find(|item| item.name == name).unwrap();
+    assert!(items.iter().all(|item| cx.crate_of(item.def_id) == local));
+
+    let foo_bar = get_item("foo_bar");
+    assert_eq!(foo_bar.kind, ItemKind::Fn);
+    let body = cx.mir_body(foo_bar.def_id).unwrap();
+    assert_eq!(body.arg_count, 2);
+    assert!(matches!(body.locals[0].ty.kind(), TyKind::Uint(_)));
+    assert!(body.blocks.iter().any(|block| {
+        block.statements.iter().any(|stmt| matches!(stmt.kind, StatementKind::Assign(..)))
+    }));
+
+    let bar = get_item("bar");
+    let body = cx.mir_body(bar.def_id).unwrap();
+    let callee = body
+        .blocks
+        .iter()
+        .find_map(|block| match block.terminator.kind {
+            TerminatorKind::Call { func: Operand::Constant(ref func), .. } => {
+                Some(func.literal.ty.clone())
+            }
+            _ => None,
+        })
+        .unwrap();
+    match callee.kind() {
+        TyKind::FnDef(def_id, _) => assert_eq!(cx.def_path_str(*def_id), "foo_bar"),
+        kind => panic!("unexpected callee {:?}", kind),
+    }
+    for block in &body.blocks {
+        assert!(block.terminator.successors().iter().all(|&bb| bb < body.blocks.len()));
+    }
+
+    let point = get_item("Point");
+    assert_eq!(point.kind, ItemKind::Ctor);
+}
+
+fn main() {
+    let path = "input.rs";
+    generate_input(path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_smir::run_compiler(&args, test_stable_mir).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub struct Point(pub u32, pub u32);
+
+    pub fn foo_bar(x: u32, y: u32) -> u32 {{
+        let sum = x.wrapping_add(y);
+        sum / 2
+    }}
+
+    pub fn bar(p: Point) -> u32 {{
+        foo_bar(p.0, p.1)
+    }}"#
+    )?;
+    Ok(())
+}
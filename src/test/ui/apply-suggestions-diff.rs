@@ -0,0 +1,8 @@
+// check-pass
+// compile-flags: -Z apply-suggestions=machine-applicable -Z apply-suggestions-diff
+#![warn(unused_mut)]
+fn main() {
+    let mut x = 1;
+    //~^ WARN variable does not need to be mutable
+    println!("{}", x);
+}
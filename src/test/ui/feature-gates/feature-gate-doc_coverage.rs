@@ -0,0 +1,3 @@
+#![doc(coverage(docs = 80))] //~ ERROR: `#[doc(coverage)]` is experimental
+
+fn main() {}
@@ -0,0 +1,14 @@
+// compile-flags: -Z group-diagnostics
+
+macro_rules! bad_consts {
+    () => {
+        const A: u32 = "a";
+        //~^ ERROR mismatched types
+        const B: u32 = "b";
+        const C: u32 = "c";
+    };
+}
+
+bad_consts!();
+
+fn main() {}
@@ -501,6 +501,8 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         logfile: config.logfile.clone(),
         run_tests: true,
         bench_benchmarks: true,
+        save_baseline: None,
+        baseline: None,
         nocapture: match env::var("RUST_TEST_NOCAPTURE") {
             Ok(val) => &val != "0",
             Err(_) => false,
@@ -508,11 +510,14 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         color: config.color,
         shuffle: false,
         shuffle_seed: None,
+        shard: None,
         test_threads: None,
         skip: config.skip.clone(),
         list: false,
         options: test::Options::new(),
         time_options: None,
+        test_timeout: None,
+        rerun_failed: None,
         force_run_in_process: false,
     }
 }